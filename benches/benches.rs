@@ -1,4 +1,4 @@
-use hibitgraph::BitGraph;
+use hibitgraph::{generators, BitGraph};
 use criterion::BatchSize::SmallInput;
 use criterion::{criterion_group, criterion_main, Criterion};
 
@@ -80,5 +80,15 @@ fn contract(c: &mut Criterion) {
     });
 }
 
-criterion_group!(bench, contract, add_edge, add_edge_unchecked, remove_edge, remove_edge_unchecked);
+fn shortest_path_long_path(c: &mut Criterion) {
+    let graph = generators::path(20000);
+    c.bench_function("shortest_path_long_path", |b| {
+        b.iter(|| graph.shortest_path(0, 19999))
+    });
+    c.bench_function("shortest_path_bidirectional_long_path", |b| {
+        b.iter(|| graph.shortest_path_bidirectional(0, 19999))
+    });
+}
+
+criterion_group!(bench, contract, add_edge, add_edge_unchecked, remove_edge, remove_edge_unchecked, shortest_path_long_path);
 criterion_main!(bench);
@@ -0,0 +1,65 @@
+/*
+ * C interface to hibitgraph, built as a static or shared library with the `ffi` feature:
+ *
+ *     cargo rustc --release --features ffi --crate-type staticlib
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * and linked against target/release/libhibitgraph.a or the shared library. Vertices of a graph with
+ * capacity n are 0..n-1. Graphs and iterators are owned by the caller and released with
+ * bitgraph_free and bitgraph_neighbors_free. A graph must not be freed while one of its
+ * neighbor iterators is in use.
+ */
+
+#ifndef HIBITGRAPH_H
+#define HIBITGRAPH_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct BitGraph BitGraph;
+typedef struct BitGraphNeighbors BitGraphNeighbors;
+
+/* Creates a graph with `capacity` vertices and no edges, NULL if the capacity is too large */
+BitGraph *bitgraph_new(uint32_t capacity);
+/* Releases a graph, NULL is ignored */
+void bitgraph_free(BitGraph *graph);
+/* Returns a copy of `graph` */
+BitGraph *bitgraph_clone(const BitGraph *graph);
+
+/* Edge updates return false if an endpoint is out of bounds or u == v */
+bool bitgraph_add_edge(BitGraph *graph, uint32_t u, uint32_t v);
+bool bitgraph_remove_edge(BitGraph *graph, uint32_t u, uint32_t v);
+bool bitgraph_has_edge(const BitGraph *graph, uint32_t u, uint32_t v);
+/* Merges `source` into `target`, false if the edge (target, source) does not exist */
+bool bitgraph_contract_edge(BitGraph *graph, uint32_t target, uint32_t source);
+/* Removes all edges of `v`, false if `v` is out of bounds */
+bool bitgraph_clear_vertex(BitGraph *graph, uint32_t v);
+
+uint32_t bitgraph_capacity(const BitGraph *graph);
+/* Number of vertices with at least one edge */
+uint32_t bitgraph_order(const BitGraph *graph);
+/* Number of edges */
+uint64_t bitgraph_size(const BitGraph *graph);
+/* Number of neighbors of `v`, 0 if `v` is out of bounds */
+uint32_t bitgraph_degree(const BitGraph *graph, uint32_t v);
+
+/* Iterates the neighbors of `v` in increasing order, NULL if `v` is out of bounds:
+ *
+ *     BitGraphNeighbors *it = bitgraph_neighbors_iter(graph, v);
+ *     uint32_t w;
+ *     while (bitgraph_neighbors_next(it, &w)) { ... }
+ *     bitgraph_neighbors_free(it);
+ */
+BitGraphNeighbors *bitgraph_neighbors_iter(const BitGraph *graph, uint32_t v);
+bool bitgraph_neighbors_next(BitGraphNeighbors *iter, uint32_t *out);
+void bitgraph_neighbors_free(BitGraphNeighbors *iter);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
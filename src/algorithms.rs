@@ -0,0 +1,619 @@
+//! Algorithms for any `UndirectedGraph`: paths, traversals, cycles and spanning trees
+//!
+//! The methods of `BitGraph` with the same name call these functions, so the results are the
+//! same on every backend. The exceptions are `is_forest`, `is_tree` and `has_cycle`, which use
+//! the cached invariants of a `BitGraph`, and the Eulerian functions, which walk a copy of the
+//! rows. `BitGraph::bfs_layers` and `BitGraph::hamiltonian_cycle` operate on whole rows and have
+//! no counterpart here. Vertices without neighbors are left out of components, as in
+//! `BitGraph::component_count`
+
+use crate::random::RandomSource;
+use crate::traverse::{
+    BfsWith, DfsBounded, DfsEdges, DfsEvents, DfsPostorder, DfsWith, MultiBfs, TraversalScratch,
+    Visitor,
+};
+use crate::{BitGraph, DfsIterator, SimpleCycles, UndirectedGraph};
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::BitSetLike;
+
+const NONE: u32 = u32::MAX;
+
+#[inline]
+fn check_bounds<G: UndirectedGraph>(graph: &G, v: u32) {
+    if v >= graph.capacity() {
+        panic!("Out of bounds. Given: {}, Allowed: {}", v, graph.capacity())
+    }
+}
+
+/// Follows `parent` pointers from `v` back to the root and returns the path root -> `v`
+pub(crate) fn unwind(parent: &[u32], v: u32) -> Vec<u32> {
+    let mut path = vec![v];
+    let mut x = v;
+    while parent[x as usize] != x {
+        x = parent[x as usize];
+        path.push(x);
+    }
+    path.reverse();
+    path
+}
+
+/// Returns some path from `u` to `v` found by a depth first search, `None` if `v` is not
+/// reachable from `u`. The path is not necessarily a shortest one
+pub fn find_path<G: UndirectedGraph>(graph: &G, u: u32, v: u32) -> Option<Vec<u32>> {
+    check_bounds(graph, u);
+    check_bounds(graph, v);
+    let mut parent = vec![NONE; graph.capacity() as usize];
+    parent[u as usize] = u;
+    let mut stack = vec![u];
+    while let Some(x) = stack.pop() {
+        if x == v {
+            return Some(unwind(&parent, v));
+        }
+        for w in graph.neighbors(x) {
+            if parent[w as usize] == NONE {
+                parent[w as usize] = x;
+                stack.push(w);
+            }
+        }
+    }
+    None
+}
+
+/// Returns a shortest path from `u` to `v` found by a breadth first search,
+/// `None` if `v` is not reachable from `u`
+pub fn shortest_path<G: UndirectedGraph>(graph: &G, u: u32, v: u32) -> Option<Vec<u32>> {
+    check_bounds(graph, v);
+    search_path(graph, u, |x| x == v)
+}
+
+/// Returns a shortest path from `v` to the closest vertex for which `predicate` holds,
+/// `v` itself included. The breadth first search stops as soon as a match is found
+pub fn search_path<G: UndirectedGraph, P: FnMut(u32) -> bool>(
+    graph: &G,
+    v: u32,
+    mut predicate: P,
+) -> Option<Vec<u32>> {
+    check_bounds(graph, v);
+    let mut parent = vec![NONE; graph.capacity() as usize];
+    parent[v as usize] = v;
+    let mut queue = vec![v];
+    let mut head = 0;
+    while head < queue.len() {
+        let x = queue[head];
+        head += 1;
+        if predicate(x) {
+            return Some(unwind(&parent, x));
+        }
+        for w in graph.neighbors(x) {
+            if parent[w as usize] == NONE {
+                parent[w as usize] = x;
+                queue.push(w);
+            }
+        }
+    }
+    None
+}
+
+/// Number of edges on a shortest path from `v` to every vertex, `None` for unreachable vertices
+pub fn distances<G: UndirectedGraph>(graph: &G, v: u32) -> Vec<Option<u32>> {
+    check_bounds(graph, v);
+    let mut distances = vec![None; graph.capacity() as usize];
+    distances[v as usize] = Some(0);
+    let mut queue = vec![v];
+    let mut head = 0;
+    while head < queue.len() {
+        let x = queue[head];
+        head += 1;
+        let d = distances[x as usize].map(|d| d + 1);
+        for w in graph.neighbors(x) {
+            if distances[w as usize].is_none() {
+                distances[w as usize] = d;
+                queue.push(w);
+            }
+        }
+    }
+    distances
+}
+
+/// Returns the connected components with at least one edge, each sorted and ordered by their
+/// smallest vertex
+pub fn connected_components<G: UndirectedGraph>(graph: &G) -> Vec<Vec<u32>> {
+    let n = graph.capacity();
+    let mut visited = hibitset::BitSet::with_capacity(n);
+    let mut components = Vec::new();
+    for v in 0..n {
+        if visited.contains(v) || graph.degree(v) == 0 {
+            continue;
+        }
+        visited.add(v);
+        let mut component = vec![v];
+        let mut stack = vec![v];
+        while let Some(x) = stack.pop() {
+            for w in graph.neighbors(x) {
+                if !visited.add(w) {
+                    component.push(w);
+                    stack.push(w);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+    components
+}
+
+/// Returns a `DfsIterator` starting at vertex `v`
+pub fn dfs<G: UndirectedGraph>(graph: &G, v: u32) -> DfsIterator<'_, G> {
+    check_bounds(graph, v);
+    DfsIterator::new(graph, v)
+}
+
+/// Returns a `DfsEvents` iterator starting at vertex `v`
+pub fn dfs_events<G: UndirectedGraph>(graph: &G, v: u32) -> DfsEvents<'_, G> {
+    check_bounds(graph, v);
+    DfsEvents::new(graph, v)
+}
+
+/// Returns a `DfsPostorder` iterator starting at vertex `v`
+pub fn dfs_postorder<G: UndirectedGraph>(graph: &G, v: u32) -> DfsPostorder<'_, G> {
+    check_bounds(graph, v);
+    DfsPostorder::new(graph, v)
+}
+
+/// Returns a `DfsBounded` iterator starting at vertex `v` that does not descend deeper than
+/// `max_depth` edges
+pub fn dfs_bounded<G: UndirectedGraph>(graph: &G, v: u32, max_depth: u32) -> DfsBounded<'_, G> {
+    check_bounds(graph, v);
+    DfsBounded::new(graph, v, max_depth)
+}
+
+/// Returns a shortest path from `v` to `target` found by iterative deepening depth first
+/// search, `None` if `target` is not reachable from `v`
+pub fn iddfs<G: UndirectedGraph>(graph: &G, v: u32, target: u32) -> Option<Vec<u32>> {
+    check_bounds(graph, target);
+    for limit in 0.. {
+        match dfs_bounded(graph, v, limit).path_to(target) {
+            Ok(path) => return Some(path),
+            Err(false) => break,
+            Err(true) => {}
+        }
+    }
+    None
+}
+
+/// Returns a `DfsEdges` iterator starting at vertex `v`
+pub fn dfs_edges<G: UndirectedGraph>(graph: &G, v: u32) -> DfsEdges<'_, G> {
+    check_bounds(graph, v);
+    DfsEdges::new(graph, v)
+}
+
+/// Performs a depth first search from `v` and reports its progress to `visitor`.
+/// Every edge of the explored component is reported exactly once, either as a tree edge or
+/// as a non-tree edge from a vertex to one of its ancestors
+pub fn depth_first_visit<G: UndirectedGraph, V: Visitor>(graph: &G, v: u32, visitor: &mut V) {
+    check_bounds(graph, v);
+    let mut depth = vec![u32::MAX; graph.capacity() as usize];
+    depth[v as usize] = 0;
+    visitor.discover(v);
+    let mut stack = vec![(v, graph.neighbors(v))];
+    while !stack.is_empty() {
+        let len = stack.len();
+        let parent = if len > 1 {
+            Some(stack[len - 2].0)
+        } else {
+            None
+        };
+        let (x, neighbors) = stack.last_mut().unwrap();
+        let x = *x;
+        let mut next = None;
+        for w in neighbors {
+            if depth[w as usize] == u32::MAX {
+                next = Some(w);
+                break;
+            }
+            if depth[w as usize] < depth[x as usize] && parent != Some(w) {
+                visitor.non_tree_edge(x, w);
+            }
+        }
+        match next {
+            Some(w) => {
+                depth[w as usize] = depth[x as usize] + 1;
+                visitor.tree_edge(x, w);
+                visitor.discover(w);
+                stack.push((w, graph.neighbors(w)));
+            }
+            None => {
+                stack.pop();
+                visitor.finish(x);
+            }
+        }
+    }
+}
+
+/// Like `dfs`, but keeps its state in `scratch` instead of allocating it
+pub fn dfs_with<'a, G: UndirectedGraph>(
+    graph: &'a G,
+    scratch: &'a mut TraversalScratch,
+    v: u32,
+) -> DfsWith<'a, G> {
+    check_bounds(graph, v);
+    DfsWith::new(graph, scratch, v)
+}
+
+/// Returns a `BfsWith` iterator starting at vertex `v` that keeps its state in `scratch`
+pub fn bfs_with<'a, G: UndirectedGraph>(
+    graph: &'a G,
+    scratch: &'a mut TraversalScratch,
+    v: u32,
+) -> BfsWith<'a, G> {
+    check_bounds(graph, v);
+    BfsWith::new(graph, scratch, v)
+}
+
+/// Returns a `MultiBfs` iterator that starts a breadth first search from all vertices in
+/// `sources` at once and yields every reached vertex with its distance to the closest source
+pub fn bfs_multi<'a, G: UndirectedGraph>(
+    graph: &'a G,
+    sources: &hibitset::BitSet,
+) -> MultiBfs<'a, G> {
+    for s in sources.iter() {
+        check_bounds(graph, s);
+    }
+    MultiBfs::new(graph, sources)
+}
+
+/// Returns `true` if the graph contains no cycle
+pub fn is_forest<G: UndirectedGraph>(graph: &G) -> bool {
+    let components = connected_components(graph);
+    let order: u64 = components.iter().map(|c| c.len() as u64).sum();
+    graph.size() + components.len() as u64 == order
+}
+
+/// Returns `true` if the graph is a forest with exactly one component.
+/// Isolated vertices are ignored, so a graph without edges is not a tree
+pub fn is_tree<G: UndirectedGraph>(graph: &G) -> bool {
+    let components = connected_components(graph);
+    components.len() == 1 && graph.size() + 1 == components[0].len() as u64
+}
+
+/// Returns `true` if the graph contains at least one cycle
+pub fn has_cycle<G: UndirectedGraph>(graph: &G) -> bool {
+    !is_forest(graph)
+}
+
+/// Length of a shortest cycle, `None` if the graph is a forest.
+/// Runs a BFS from every vertex, each one stops as soon as it can't find a shorter cycle
+pub fn girth<G: UndirectedGraph>(graph: &G) -> Option<u32> {
+    let n = graph.capacity() as usize;
+    let mut best = u32::MAX;
+    let mut dist = vec![u32::MAX; n];
+    let mut parent = vec![u32::MAX; n];
+    let mut queue: Vec<u32> = Vec::new();
+    for s in 0..n as u32 {
+        if graph.degree(s) < 2 {
+            continue;
+        }
+        dist[s as usize] = 0;
+        queue.push(s);
+        let mut head = 0;
+        'bfs: while head < queue.len() {
+            let u = queue[head];
+            head += 1;
+            let d = dist[u as usize];
+            if 2 * d + 1 >= best {
+                break;
+            }
+            for w in graph.neighbors(u) {
+                if dist[w as usize] == u32::MAX {
+                    dist[w as usize] = d + 1;
+                    parent[w as usize] = u;
+                    queue.push(w);
+                } else if parent[u as usize] != w {
+                    best = best.min(d + dist[w as usize] + 1);
+                    if best == 3 {
+                        break 'bfs;
+                    }
+                }
+            }
+        }
+        for &v in queue.iter() {
+            dist[v as usize] = u32::MAX;
+            parent[v as usize] = u32::MAX;
+        }
+        queue.clear();
+        if best == 3 {
+            break;
+        }
+    }
+    if best == u32::MAX {
+        None
+    } else {
+        Some(best)
+    }
+}
+
+/// Returns a fundamental cycle basis with respect to a DFS spanning forest.
+/// Every non-tree edge `(u, w)` closes exactly one cycle, given as the vertex sequence
+/// `u, parent(u), ..., w`
+pub fn cycle_basis<G: UndirectedGraph>(graph: &G) -> Vec<Vec<u32>> {
+    let n = graph.capacity() as usize;
+    let mut depth = vec![u32::MAX; n];
+    let mut parent = vec![u32::MAX; n];
+    let mut cycles = Vec::new();
+    for root in 0..n as u32 {
+        if graph.degree(root) == 0 || depth[root as usize] != u32::MAX {
+            continue;
+        }
+        depth[root as usize] = 0;
+        let mut stack = vec![(root, graph.neighbors(root))];
+        while let Some((u, neighbors)) = stack.last_mut() {
+            let u = *u;
+            match neighbors.next() {
+                Some(w) if depth[w as usize] == u32::MAX => {
+                    depth[w as usize] = depth[u as usize] + 1;
+                    parent[w as usize] = u;
+                    stack.push((w, graph.neighbors(w)));
+                }
+                Some(w) if depth[w as usize] < depth[u as usize] && parent[u as usize] != w => {
+                    let mut cycle = vec![u];
+                    let mut x = u;
+                    while x != w {
+                        x = parent[x as usize];
+                        cycle.push(x);
+                    }
+                    cycles.push(cycle);
+                }
+                Some(_) => {}
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+    cycles
+}
+
+/// Returns a lazy iterator over all simple cycles with at most `max_length` vertices (all
+/// cycles if `None`). Every cycle is reported once, starting at its smallest vertex
+pub fn simple_cycles<G: UndirectedGraph>(
+    graph: &G,
+    max_length: Option<u32>,
+) -> SimpleCycles<'_, G> {
+    SimpleCycles::new(graph, max_length.unwrap_or(u32::MAX))
+}
+
+/// Returns an Eulerian circuit as a closed vertex sequence (first and last vertex are equal)
+/// that uses every edge exactly once, `None` if no such circuit exists
+pub fn eulerian_circuit<G: UndirectedGraph>(graph: &G) -> Option<Vec<u32>> {
+    // Hierholzer's algorithm removes the edges it walks, so it runs on a scratch copy
+    to_bitgraph(graph).eulerian_circuit()
+}
+
+/// Returns an Eulerian trail as a vertex sequence that uses every edge exactly once, `None`
+/// if no such trail exists. If the graph has two vertices of odd degree, the trail starts at
+/// the smaller one and ends at the other, otherwise it is a circuit
+pub fn eulerian_trail<G: UndirectedGraph>(graph: &G) -> Option<Vec<u32>> {
+    to_bitgraph(graph).eulerian_trail()
+}
+
+/// Returns a BFS spanning tree of the component containing `root`.
+/// The tree has the same capacity as the graph, vertices outside the component are isolated
+pub fn spanning_tree<G: UndirectedGraph>(graph: &G, root: u32) -> BitGraph {
+    check_bounds(graph, root);
+    let n = graph.capacity();
+    let mut tree = BitGraph::with_capacity(n);
+    let mut visited = hibitset::BitSet::with_capacity(n);
+    grow_bfs_tree(graph, root, &mut visited, &mut tree);
+    tree
+}
+
+/// Returns a spanning forest consisting of one BFS spanning tree per component
+pub fn spanning_forest<G: UndirectedGraph>(graph: &G) -> BitGraph {
+    let n = graph.capacity();
+    let mut forest = BitGraph::with_capacity(n);
+    let mut visited = hibitset::BitSet::with_capacity(n);
+    for v in 0..n {
+        if graph.degree(v) > 0 && !visited.contains(v) {
+            grow_bfs_tree(graph, v, &mut visited, &mut forest);
+        }
+    }
+    forest
+}
+
+fn grow_bfs_tree<G: UndirectedGraph>(
+    graph: &G,
+    root: u32,
+    visited: &mut hibitset::BitSet,
+    tree: &mut BitGraph,
+) {
+    let mut queue = vec![root];
+    visited.add(root);
+    let mut head = 0;
+    while head < queue.len() {
+        let u = queue[head];
+        head += 1;
+        for w in graph.neighbors(u) {
+            if !visited.add(w) {
+                tree.add_edge(u, w);
+                queue.push(w);
+            }
+        }
+    }
+}
+
+/// Returns the Prüfer sequence of the graph if it is a tree spanning all `capacity`
+/// vertices, `None` otherwise. Repeatedly removes the smallest leaf and records its neighbor
+pub fn to_prufer<G: UndirectedGraph>(graph: &G) -> Option<Vec<u32>> {
+    let n = graph.capacity();
+    let mut degree: Vec<u32> = (0..n).map(|v| graph.degree(v)).collect();
+    if n < 2 || degree.contains(&0) || !is_tree(graph) {
+        return None;
+    }
+    let mut removed = hibitset::BitSet::with_capacity(n);
+    let mut sequence = Vec::with_capacity(n as usize - 2);
+    let mut next = (0..n).find(|&v| degree[v as usize] == 1).unwrap();
+    let mut leaf = next;
+    while sequence.len() + 2 < n as usize {
+        removed.add(leaf);
+        let parent = graph
+            .neighbors(leaf)
+            .find(|&w| !removed.contains(w))
+            .unwrap();
+        sequence.push(parent);
+        degree[parent as usize] -= 1;
+        if parent < next && degree[parent as usize] == 1 {
+            leaf = parent;
+        } else {
+            next = (next + 1..n).find(|&w| degree[w as usize] == 1).unwrap();
+            leaf = next;
+        }
+    }
+    Some(sequence)
+}
+
+/// Returns a uniformly random spanning tree of each component using Wilson's loop-erased
+/// random walk algorithm. For connected graphs this is a uniform spanning tree
+pub fn random_spanning_tree<G: UndirectedGraph, R: RandomSource>(
+    graph: &G,
+    rng: &mut R,
+) -> BitGraph {
+    let n = graph.capacity() as usize;
+    let adj: Vec<Vec<u32>> = (0..n as u32)
+        .map(|v| graph.neighbors(v).collect())
+        .collect();
+    let mut tree = BitGraph::with_capacity(n as u32);
+    let mut in_tree = hibitset::BitSet::with_capacity(n as u32);
+    // one root per component, any root yields the uniform distribution
+    let mut labeled = hibitset::BitSet::with_capacity(n as u32);
+    let mut stack = Vec::new();
+    for v in 0..n as u32 {
+        if adj[v as usize].is_empty() || labeled.contains(v) {
+            continue;
+        }
+        in_tree.add(v);
+        labeled.add(v);
+        stack.push(v);
+        while let Some(u) = stack.pop() {
+            for &w in adj[u as usize].iter() {
+                if !labeled.add(w) {
+                    stack.push(w);
+                }
+            }
+        }
+    }
+    let mut next = vec![0u32; n];
+    for v in 0..n as u32 {
+        if adj[v as usize].is_empty() {
+            continue;
+        }
+        let mut u = v;
+        while !in_tree.contains(u) {
+            let neighbors = &adj[u as usize];
+            next[u as usize] = neighbors[rng.below(neighbors.len() as u64) as usize];
+            u = next[u as usize];
+        }
+        // following the successors erases the loops of the walk
+        let mut u = v;
+        while !in_tree.add(u) {
+            tree.add_edge(u, next[u as usize]);
+            u = next[u as usize];
+        }
+    }
+    tree
+}
+
+/// Copies `graph` into a `BitGraph` of the same capacity, for algorithms that modify it
+fn to_bitgraph<G: UndirectedGraph>(graph: &G) -> BitGraph {
+    let n = graph.capacity();
+    let mut copy = BitGraph::with_capacity(n);
+    for u in 0..n {
+        for w in graph.neighbors(u).filter(|&w| w > u) {
+            copy.add_edge(u, w);
+        }
+    }
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms;
+    use crate::generators;
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::traverse::TraversalScratch;
+    use crate::BitDiGraph;
+
+    #[test]
+    fn distances_and_components() {
+        let capacity: usize = 50;
+        let mut rng = SplitMix64::new(75);
+        let c = generators::gnp(capacity as u32, 0.05, &mut rng);
+        let distances = algorithms::distances(&c, 0);
+        for v in 0..capacity as u32 {
+            let hops = c.shortest_path(0, v).map(|p| p.len() as u32 - 1);
+            assert_eq!(distances[v as usize], hops);
+        }
+        let components = algorithms::connected_components(&c.view());
+        assert_eq!(components.len() as u32, c.component_count());
+        for component in components.iter() {
+            assert!(component.windows(2).all(|pair| pair[0] < pair[1]));
+            let first = component[0];
+            assert!(component
+                .iter()
+                .all(|&v| algorithms::find_path(&c, first, v).is_some()));
+        }
+    }
+
+    #[test]
+    fn traversals_cycles_and_trees() {
+        let capacity: usize = 30;
+        let mut rng = SplitMix64::new(78);
+        let mut d = BitDiGraph::with_capacity(capacity as u32);
+        for _ in 0..45 {
+            let u = rng.below(capacity as u64) as u32;
+            let v = rng.below(capacity as u64) as u32;
+            if u != v {
+                d.add_arc(u, v);
+            }
+        }
+        // the view has no rows of its own, so every result comes from the generic code
+        let g = d.underlying_view();
+        let c = d.underlying();
+        let mut scratch = TraversalScratch::new();
+        for v in 0..capacity as u32 {
+            assert!(algorithms::dfs(&g, v).eq(c.dfs(v)));
+            assert!(algorithms::dfs_events(&g, v).eq(c.dfs_events(v)));
+            assert!(algorithms::dfs_postorder(&g, v).eq(c.dfs_postorder(v)));
+            assert!(algorithms::dfs_bounded(&g, v, 3).eq(c.dfs_bounded(v, 3)));
+            assert!(algorithms::dfs_edges(&g, v).eq(c.dfs_edges(v)));
+            let tmp: Vec<u32> = algorithms::bfs_with(&g, &mut scratch, v).collect();
+            assert!(c.bfs_with(&mut scratch, v).eq(tmp));
+            assert_eq!(algorithms::iddfs(&g, 0, v), c.iddfs(0, v));
+        }
+        let mut sources = hibitset::BitSet::new();
+        sources.add(3);
+        sources.add(17);
+        assert!(algorithms::bfs_multi(&g, &sources).eq(c.bfs_multi(&sources)));
+
+        assert_eq!(algorithms::is_forest(&g), c.is_forest());
+        assert_eq!(algorithms::is_tree(&g), c.is_tree());
+        assert_eq!(algorithms::girth(&g), c.girth());
+        assert_eq!(algorithms::cycle_basis(&g), c.cycle_basis());
+        assert!(algorithms::simple_cycles(&g, Some(6)).eq(c.simple_cycles(Some(6))));
+        assert_eq!(algorithms::eulerian_trail(&g), c.eulerian_trail());
+        assert!(algorithms::spanning_forest(&g)
+            .edges()
+            .eq(c.spanning_forest().edges()));
+        assert!(algorithms::spanning_tree(&g, 3)
+            .edges()
+            .eq(c.spanning_tree(3).edges()));
+        let t = algorithms::random_spanning_tree(&g, &mut rng);
+        assert_eq!(algorithms::cycle_basis(&t).len(), 0);
+        assert_eq!(t.size(), c.spanning_forest().size());
+
+        let mut rng = SplitMix64::new(79);
+        let tree = generators::random_tree(capacity as u32, &mut rng);
+        assert!(algorithms::is_tree(&tree.view()));
+        assert_eq!(algorithms::to_prufer(&tree.view()), tree.to_prufer());
+    }
+}
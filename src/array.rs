@@ -0,0 +1,457 @@
+//! Small graphs stored inline without heap allocation
+
+use crate::{BitGraph, UndirectedGraph};
+use core::iter::{FromIterator, FusedIterator};
+
+/// An undirected graph on the vertices `0..N` with every row stored inline as `W` words, so
+/// creating, copying and dropping one never touches the heap. Stable Rust cannot compute the
+/// number of words from `N`, so `W` has to be at least `N / 64` rounded up, which is checked
+/// at compile time. The aliases `ArrayBitGraph64`, `ArrayBitGraph128` and `ArrayBitGraph256`
+/// cover the common sizes. Methods mirror the ones of `BitGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayBitGraph<const N: usize, const W: usize> {
+    m_rows: [[u64; W]; N],
+}
+
+/// An `ArrayBitGraph` with up to 64 vertices, one word per row
+pub type ArrayBitGraph64 = ArrayBitGraph<64, 1>;
+/// An `ArrayBitGraph` with up to 128 vertices
+pub type ArrayBitGraph128 = ArrayBitGraph<128, 2>;
+/// An `ArrayBitGraph` with up to 256 vertices
+pub type ArrayBitGraph256 = ArrayBitGraph<256, 4>;
+
+impl<const N: usize, const W: usize> ArrayBitGraph<N, W> {
+    const WORDS: () = assert!(W * 64 >= N, "W words per row cannot hold N vertices");
+
+    /// Creates a new ArrayBitGraph with `N` vertices and no edges
+    pub fn new() -> ArrayBitGraph<N, W> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::WORDS;
+        ArrayBitGraph {
+            m_rows: [[0; W]; N],
+        }
+    }
+
+    /// Creates a new ArrayBitGraph with all `N` vertices connected to each other
+    pub fn complete() -> ArrayBitGraph<N, W> {
+        let mut graph = Self::new();
+        for v in 0..N {
+            let row = &mut graph.m_rows[v];
+            for (i, word) in row.iter_mut().enumerate() {
+                let bits = N.saturating_sub(64 * i).min(64);
+                *word = if bits == 64 { !0 } else { (1 << bits) - 1 };
+            }
+            row[v / 64] &= !(1 << (v % 64));
+        }
+        graph
+    }
+
+    /// Returns `graph` as an ArrayBitGraph, `None` if its capacity is larger than `N`
+    pub fn from_bit_graph(graph: &BitGraph) -> Option<ArrayBitGraph<N, W>> {
+        if graph.capacity() as usize > N {
+            return None;
+        }
+        let mut array = Self::new();
+        for (u, v) in graph.edges() {
+            array.add_edge(u, v);
+        }
+        Some(array)
+    }
+
+    /// Returns the graph as a `BitGraph` with capacity `N`
+    pub fn to_bit_graph(&self) -> BitGraph {
+        BitGraph::from_rows((0..N as u32).map(|v| self.neighbors(v).collect()).collect())
+    }
+
+    #[inline]
+    fn check_bounds(&self, idx: u32) {
+        if idx as usize >= N {
+            panic!("Out of bounds. Given: {}, Allowed: {}", idx, N)
+        }
+    }
+
+    #[inline]
+    fn check_is_same(&self, u: u32, v: u32) {
+        if u == v {
+            panic!("Edge needs two distinct endpoints, given: {} {}", u, v)
+        }
+    }
+
+    #[inline]
+    fn set(&mut self, u: u32, v: u32) {
+        self.m_rows[u as usize][v as usize / 64] |= 1 << (v % 64);
+    }
+
+    #[inline]
+    fn unset(&mut self, u: u32, v: u32) {
+        self.m_rows[u as usize][v as usize / 64] &= !(1 << (v % 64));
+    }
+
+    /// Adds a new undirected edge from `u` to `v`
+    /// If the edge already exists, the graph is not updated
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.check_is_same(u, v);
+        self.set(u, v);
+        self.set(v, u);
+    }
+
+    /// Removes the edge from `u` to `v` after performing boundary checks.
+    /// If the edge is not present the graph is not updated
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.unset(u, v);
+        self.unset(v, u);
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.m_rows[u as usize][v as usize / 64] & (1 << (v % 64)) != 0
+    }
+
+    /// Removes all edges
+    pub fn clear(&mut self) {
+        self.m_rows = [[0; W]; N];
+    }
+
+    /// Removes all edges incident to `v`
+    pub fn clear_vertex(&mut self, v: u32) {
+        self.check_bounds(v);
+        for &w in self.neighbors(v).collect::<ArrayStack<N>>().items() {
+            self.unset(w, v);
+        }
+        self.m_rows[v as usize] = [0; W];
+    }
+
+    /// Contracts the edge (target, source) by adding all neighbors
+    /// of source to `target` and removing `source`
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        self.check_bounds(target);
+        self.check_bounds(source);
+        self.check_is_same(target, source);
+        if !self.has_edge(target, source) {
+            panic!(
+                "Edge ({}, {}) does not exist. Can't contract!",
+                target, source
+            );
+        }
+        for &w in self.neighbors(source).collect::<ArrayStack<N>>().items() {
+            self.unset(w, source);
+            if w != target {
+                self.set(w, target);
+                self.set(target, w);
+            }
+        }
+        self.m_rows[source as usize] = [0; W];
+    }
+
+    /// Returns an iterator over the neighborhood of vertex `v`
+    pub fn neighbors(&self, v: u32) -> ArrayNeighbors<'_> {
+        self.check_bounds(v);
+        ArrayNeighbors::new(&self.m_rows[v as usize])
+    }
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    pub fn capacity(&self) -> u32 {
+        N as u32
+    }
+
+    /// Number of neighbors of `v`, a popcount over its row
+    pub fn degree(&self, v: u32) -> u32 {
+        self.check_bounds(v);
+        self.m_rows[v as usize]
+            .iter()
+            .map(|word| word.count_ones())
+            .sum()
+    }
+
+    /// Number of vertices in the graph
+    pub fn order(&self) -> u32 {
+        self.m_rows
+            .iter()
+            .filter(|row| row.iter().any(|&word| word != 0))
+            .count() as u32
+    }
+
+    /// Number of edges in the graph
+    pub fn size(&self) -> u64 {
+        self.m_rows
+            .iter()
+            .flat_map(|row| row.iter())
+            .map(|word| word.count_ones() as u64)
+            .sum::<u64>()
+            / 2
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u < v`, ordered by `u` and then `v`
+    pub fn edges(&self) -> ArrayEdges<'_, N, W> {
+        ArrayEdges {
+            m_graph: self,
+            m_vertex: 0,
+            m_neighbors: None,
+        }
+    }
+
+    /// Returns an iterator that performs a depth first search from vertex `v` in the order of
+    /// `BitGraph::dfs`, keeping its state inline as well
+    pub fn dfs(&self, v: u32) -> ArrayDfs<'_, N, W> {
+        self.check_bounds(v);
+        let mut visited = [0; W];
+        visited[v as usize / 64] |= 1 << (v % 64);
+        let mut stack = ArrayStack::new();
+        stack.push(v);
+        ArrayDfs {
+            m_graph: self,
+            m_visited: visited,
+            m_stack: stack,
+        }
+    }
+}
+
+impl<const N: usize, const W: usize> Default for ArrayBitGraph<N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const W: usize> UndirectedGraph for ArrayBitGraph<N, W> {
+    type Neighbors<'a> = ArrayNeighbors<'a>;
+
+    fn capacity(&self) -> u32 {
+        N as u32
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        ArrayBitGraph::neighbors(self, v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        ArrayBitGraph::degree(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        ArrayBitGraph::has_edge(self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        ArrayBitGraph::size(self)
+    }
+}
+
+/// Up to `N` vertices on the stack, each graph vertex fits in once
+struct ArrayStack<const N: usize> {
+    m_items: [u32; N],
+    m_len: usize,
+}
+
+impl<const N: usize> ArrayStack<N> {
+    fn new() -> ArrayStack<N> {
+        ArrayStack {
+            m_items: [0; N],
+            m_len: 0,
+        }
+    }
+
+    fn push(&mut self, v: u32) {
+        self.m_items[self.m_len] = v;
+        self.m_len += 1;
+    }
+
+    fn pop(&mut self) -> Option<u32> {
+        self.m_len = self.m_len.checked_sub(1)?;
+        Some(self.m_items[self.m_len])
+    }
+
+    fn len(&self) -> usize {
+        self.m_len
+    }
+
+    fn items(&self) -> &[u32] {
+        &self.m_items[..self.m_len]
+    }
+}
+
+impl<const N: usize> FromIterator<u32> for ArrayStack<N> {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut stack = ArrayStack::new();
+        for v in iter {
+            stack.push(v);
+        }
+        stack
+    }
+}
+
+/// Iterator over the set bits of a row of words, created by `ArrayBitGraph::neighbors` and
+/// `MatrixBitGraph::neighbors`
+pub struct ArrayNeighbors<'a> {
+    m_words: &'a [u64],
+    m_index: usize,
+    m_current: u64,
+}
+
+impl<'a> ArrayNeighbors<'a> {
+    pub(crate) fn new(words: &'a [u64]) -> ArrayNeighbors<'a> {
+        ArrayNeighbors {
+            m_words: words,
+            m_index: 0,
+            m_current: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl<'a> Iterator for ArrayNeighbors<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.m_current == 0 {
+            self.m_index += 1;
+            self.m_current = *self.m_words.get(self.m_index)?;
+        }
+        let bit = self.m_current.trailing_zeros();
+        self.m_current &= self.m_current - 1;
+        Some(self.m_index as u32 * 64 + bit)
+    }
+}
+
+impl<'a> FusedIterator for ArrayNeighbors<'a> {}
+
+/// Iterator over the edges of an `ArrayBitGraph`, created by `ArrayBitGraph::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct ArrayEdges<'a, const N: usize, const W: usize> {
+    m_graph: &'a ArrayBitGraph<N, W>,
+    m_vertex: u32,
+    m_neighbors: Option<ArrayNeighbors<'a>>,
+}
+
+impl<'a, const N: usize, const W: usize> Iterator for ArrayEdges<'a, N, W> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(neighbors) = self.m_neighbors.as_mut() {
+                let u = self.m_vertex - 1;
+                if let Some(v) = neighbors.find(|&v| v > u) {
+                    return Some((u, v));
+                }
+                self.m_neighbors = None;
+            }
+            if self.m_vertex as usize >= N {
+                return None;
+            }
+            self.m_neighbors = Some(self.m_graph.neighbors(self.m_vertex));
+            self.m_vertex += 1;
+        }
+    }
+}
+
+impl<'a, const N: usize, const W: usize> FusedIterator for ArrayEdges<'a, N, W> {}
+
+/// Iterator that performs a depth first search on an `ArrayBitGraph`, created by
+/// `ArrayBitGraph::dfs`
+pub struct ArrayDfs<'a, const N: usize, const W: usize> {
+    m_graph: &'a ArrayBitGraph<N, W>,
+    m_visited: [u64; W],
+    m_stack: ArrayStack<N>,
+}
+
+impl<'a, const N: usize, const W: usize> Iterator for ArrayDfs<'a, N, W> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_stack.pop()?;
+        for (i, row) in self.m_graph.m_rows[v as usize].iter().enumerate() {
+            let mut fresh = row & !self.m_visited[i];
+            self.m_visited[i] |= fresh;
+            while fresh != 0 {
+                self.m_stack.push(i as u32 * 64 + fresh.trailing_zeros());
+                fresh &= fresh - 1;
+            }
+        }
+        Some(v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let marked: u32 = self.m_visited.iter().map(|word| word.count_ones()).sum();
+        let pending = self.m_stack.len();
+        (pending, Some(pending + N - marked as usize))
+    }
+}
+
+impl<'a, const N: usize, const W: usize> FusedIterator for ArrayDfs<'a, N, W> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{algorithms, ArrayBitGraph, ArrayBitGraph64, BitGraph};
+
+    #[test]
+    fn array_graph() {
+        let mut c = ArrayBitGraph64::new();
+        for i in 0..5 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(63, 0);
+        c.add_edge(0, 1);
+        assert_eq!(c.size(), 6);
+        assert_eq!(c.order(), 7);
+        assert_eq!(c.degree(0), 2);
+        assert!(c.has_edge(0, 63));
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 63]);
+        c.contract_edge(0, 1);
+        assert!(c.has_edge(0, 2) && !c.has_edge(0, 1));
+        assert_eq!(c.degree(1), 0);
+        c.clear_vertex(0);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(2, 3), (3, 4), (4, 5)]);
+        let copy = c;
+        c.clear();
+        assert_eq!(c.size(), 0);
+        assert_eq!(copy.size(), 3);
+
+        let complete = ArrayBitGraph::<70, 2>::complete();
+        assert_eq!(complete.size(), 70 * 69 / 2);
+        assert_eq!(complete.degree(69), 69);
+        assert_eq!(complete.to_bit_graph(), BitGraph::complete(70));
+        assert!(ArrayBitGraph64::from_bit_graph(&BitGraph::with_capacity(65)).is_none());
+    }
+
+    #[test]
+    fn matches_bit_graph() {
+        let mut rng = SplitMix64::new(79);
+        for _ in 0..20 {
+            let mut c = ArrayBitGraph::<100, 2>::new();
+            let mut d = BitGraph::with_capacity(100);
+            for _ in 0..150 {
+                let (u, v) = (rng.below(100) as u32, rng.below(100) as u32);
+                if u == v {
+                    continue;
+                }
+                if rng.below(4) == 0 {
+                    c.remove_edge(u, v);
+                    d.remove_edge(u, v);
+                } else {
+                    c.add_edge(u, v);
+                    d.add_edge(u, v);
+                }
+            }
+            if let Some((u, v)) = d.edges().next() {
+                c.contract_edge(u, v);
+                d.contract_edge(u, v);
+            }
+            assert_eq!(c.to_bit_graph(), d);
+            assert_eq!(ArrayBitGraph::from_bit_graph(&d), Some(c));
+            assert_eq!((c.size(), c.order()), (d.size(), d.order()));
+            assert!(c.edges().eq(d.edges()));
+            assert!(c.dfs(7).eq(d.dfs(7)));
+            assert_eq!(
+                algorithms::connected_components(&c),
+                algorithms::connected_components(&d)
+            );
+        }
+    }
+}
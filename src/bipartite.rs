@@ -0,0 +1,261 @@
+//! Bipartite graphs with fixed sides
+
+use crate::{BitGraph, Edges};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Deref, Range};
+
+/// Side of a vertex in a `BitBipartiteGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// A vertex of `BitBipartiteGraph::left`
+    Left,
+    /// A vertex of `BitBipartiteGraph::right`
+    Right,
+}
+
+/// A bipartite graph, the left side is `0..left` and the right side `left..left + right` as in
+/// `generators::complete_bipartite`. Edges within a side are rejected. All query methods of
+/// `BitGraph` are available through `Deref`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitBipartiteGraph {
+    m_graph: BitGraph,
+    m_left: u32,
+}
+
+impl BitBipartiteGraph {
+    /// Creates a new BitBipartiteGraph with `left` and `right` vertices on the sides and no edges
+    pub fn with_sides(left: u32, right: u32) -> BitBipartiteGraph {
+        BitBipartiteGraph {
+            m_graph: BitGraph::with_capacity(left + right),
+            m_left: left,
+        }
+    }
+
+    /// Returns `graph` as a bipartite graph with the first `left` vertices on the left side,
+    /// `None` if an edge lies within a side
+    pub fn from_graph(graph: BitGraph, left: u32) -> Option<BitBipartiteGraph> {
+        if left > graph.capacity() {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                left,
+                graph.capacity()
+            )
+        }
+        let bipartite = BitBipartiteGraph {
+            m_graph: graph,
+            m_left: left,
+        };
+        if bipartite
+            .m_graph
+            .edges()
+            .all(|(u, v)| bipartite.side(u) != bipartite.side(v))
+        {
+            Some(bipartite)
+        } else {
+            None
+        }
+    }
+
+    /// Side of vertex `v`
+    pub fn side(&self, v: u32) -> Side {
+        self.m_graph.check_bounds(v);
+        if v < self.m_left {
+            Side::Left
+        } else {
+            Side::Right
+        }
+    }
+
+    /// Vertices of the left side
+    pub fn left(&self) -> Range<u32> {
+        0..self.m_left
+    }
+
+    /// Vertices of the right side
+    pub fn right(&self) -> Range<u32> {
+        self.m_left..self.m_graph.capacity()
+    }
+
+    /// Vertices of `side`
+    pub fn vertices(&self, side: Side) -> Range<u32> {
+        match side {
+            Side::Left => self.left(),
+            Side::Right => self.right(),
+        }
+    }
+
+    /// Adds the edge from `u` to `v`, which have to be on different sides
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        if self.side(u) == self.side(v) {
+            panic!("Edge needs endpoints on both sides, given: {} {}", u, v)
+        }
+        self.m_graph.add_edge(u, v);
+    }
+
+    /// Removes the edge from `u` to `v`, if it is present
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        self.m_graph.remove_edge(u, v);
+    }
+
+    /// Returns an iterator over all edges `(l, r)` with `l` on the left side, ordered by `l`
+    pub fn edges(&self) -> Edges<'_> {
+        // every edge has its smaller end on the left
+        self.m_graph.edges()
+    }
+
+    /// The graph without the sides
+    pub fn graph(&self) -> &BitGraph {
+        &self.m_graph
+    }
+
+    /// Returns a maximum matching as edges `(l, r)` with `l` on the left side, ordered by `l`.
+    /// Uses the Hopcroft–Karp algorithm, which augments along a maximal set of disjoint
+    /// shortest paths per phase and needs O(sqrt(n)) phases
+    pub fn maximum_matching(&self) -> Vec<(u32, u32)> {
+        const NONE: u32 = u32::MAX;
+        let n = self.m_graph.capacity() as usize;
+        let mut mate = vec![NONE; n];
+        let mut distance = vec![NONE; self.m_left as usize];
+        let mut queue = VecDeque::new();
+        loop {
+            for l in self.left() {
+                distance[l as usize] = if mate[l as usize] == NONE {
+                    queue.push_back(l);
+                    0
+                } else {
+                    NONE
+                };
+            }
+            let mut found = false;
+            while let Some(l) = queue.pop_front() {
+                for r in self.m_graph.neighbors(l) {
+                    let m = mate[r as usize];
+                    if m == NONE {
+                        found = true;
+                    } else if distance[m as usize] == NONE {
+                        distance[m as usize] = distance[l as usize] + 1;
+                        queue.push_back(m);
+                    }
+                }
+            }
+            if !found {
+                break;
+            }
+            for l in self.left() {
+                if mate[l as usize] == NONE {
+                    self.augment(l, &mut mate, &mut distance);
+                }
+            }
+        }
+        self.left()
+            .filter(|&l| mate[l as usize] != NONE)
+            .map(|l| (l, mate[l as usize]))
+            .collect()
+    }
+
+    /// Searches an augmenting path from the free vertex `l` along increasing distances
+    fn augment(&self, l: u32, mate: &mut [u32], distance: &mut [u32]) -> bool {
+        for r in self.m_graph.neighbors(l) {
+            let m = mate[r as usize];
+            if m == u32::MAX
+                || (distance[m as usize] == distance[l as usize] + 1
+                    && self.augment(m, mate, distance))
+            {
+                mate[l as usize] = r;
+                mate[r as usize] = l;
+                return true;
+            }
+        }
+        distance[l as usize] = u32::MAX;
+        false
+    }
+}
+
+impl Deref for BitBipartiteGraph {
+    type Target = BitGraph;
+
+    fn deref(&self) -> &BitGraph {
+        &self.m_graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{BitBipartiteGraph, Side};
+
+    /// Size of a maximum matching by trying every partner for every left vertex
+    fn brute_force(c: &BitBipartiteGraph, l: u32, used: &mut Vec<bool>) -> usize {
+        if l == c.left().end {
+            return 0;
+        }
+        let mut best = brute_force(c, l + 1, used);
+        for r in c.neighbors(l) {
+            if !used[r as usize] {
+                used[r as usize] = true;
+                best = best.max(1 + brute_force(c, l + 1, used));
+                used[r as usize] = false;
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn sides() {
+        let mut c = BitBipartiteGraph::with_sides(2, 3);
+        assert_eq!(c.left(), 0..2);
+        assert_eq!(c.vertices(Side::Right), 2..5);
+        assert_eq!(c.side(1), Side::Left);
+        assert_eq!(c.side(2), Side::Right);
+        c.add_edge(3, 0);
+        c.add_edge(1, 4);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(0, 3), (1, 4)]);
+        assert_eq!(c.size(), 2);
+        c.remove_edge(0, 3);
+        assert_eq!(c.degree(3), 0);
+
+        assert!(BitBipartiteGraph::from_graph(generators::complete_bipartite(3, 4), 3).is_some());
+        assert!(BitBipartiteGraph::from_graph(generators::complete_bipartite(3, 4), 2).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn edge_within_a_side() {
+        BitBipartiteGraph::with_sides(2, 3).add_edge(2, 4);
+    }
+
+    #[test]
+    fn maximum_matching() {
+        let c = BitBipartiteGraph::from_graph(generators::complete_bipartite(3, 5), 3).unwrap();
+        assert_eq!(c.maximum_matching(), vec![(0, 3), (1, 4), (2, 5)]);
+        assert_eq!(
+            BitBipartiteGraph::with_sides(4, 0).maximum_matching(),
+            vec![]
+        );
+
+        let mut rng = SplitMix64::new(72);
+        for _ in 0..30 {
+            let (left, right) = (1 + rng.below(7) as u32, 1 + rng.below(7) as u32);
+            let mut c = BitBipartiteGraph::with_sides(left, right);
+            for l in c.left() {
+                for r in c.right() {
+                    if rng.below(3) == 0 {
+                        c.add_edge(l, r);
+                    }
+                }
+            }
+            let matching = c.maximum_matching();
+            let mut used = vec![false; (left + right) as usize];
+            assert_eq!(matching.len(), brute_force(&c, 0, &mut used));
+            for &(l, r) in matching.iter() {
+                assert!(c.row(l).contains(r));
+                assert!(!used[r as usize]);
+                used[r as usize] = true;
+            }
+        }
+    }
+}
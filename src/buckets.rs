@@ -0,0 +1,254 @@
+//! Opt-in grouping of the vertices by degree
+//!
+//! Degrees only change in steps of one per touched vertex and edge, so moving a vertex to the
+//! neighboring bucket is a swap with the first or last vertex of its bucket.
+
+use crate::{BitGraph, VertexId};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Vertices sorted by degree, the vertices of degree `d` are `m_sorted[m_start[d]..m_start[d + 1]]`
+#[derive(Debug, Clone)]
+pub(crate) struct DegreeBuckets {
+    m_sorted: Vec<u32>,
+    m_position: Vec<u32>,
+    m_start: Vec<u32>,
+    m_degrees: Vec<u32>,
+}
+
+impl DegreeBuckets {
+    fn new(degrees: &[u32]) -> DegreeBuckets {
+        let n = degrees.len();
+        let mut start = vec![0u32; n + 2];
+        for &d in degrees.iter() {
+            start[d as usize + 1] += 1;
+        }
+        for d in 1..start.len() {
+            start[d] += start[d - 1];
+        }
+        let mut next = start.clone();
+        let mut sorted = vec![0; n];
+        let mut position = vec![0; n];
+        for (v, &d) in degrees.iter().enumerate() {
+            let i = next[d as usize];
+            next[d as usize] += 1;
+            sorted[i as usize] = v as u32;
+            position[v] = i;
+        }
+        DegreeBuckets {
+            m_sorted: sorted,
+            m_position: position,
+            m_start: start,
+            m_degrees: degrees.to_vec(),
+        }
+    }
+
+    /// Bytes held by the bucket vectors
+    pub(crate) fn memory_usage(&self) -> usize {
+        (self.m_sorted.capacity()
+            + self.m_position.capacity()
+            + self.m_start.capacity()
+            + self.m_degrees.capacity())
+            * core::mem::size_of::<u32>()
+    }
+
+    fn swap(&mut self, i: u32, j: u32) {
+        self.m_sorted.swap(i as usize, j as usize);
+        self.m_position[self.m_sorted[i as usize] as usize] = i;
+        self.m_position[self.m_sorted[j as usize] as usize] = j;
+    }
+
+    /// Moves `v` bucket by bucket until it is in the one of `degree`
+    fn update(&mut self, v: u32, degree: u32) {
+        while self.m_degrees[v as usize] < degree {
+            let d = self.m_degrees[v as usize] as usize;
+            let last = self.m_start[d + 1] - 1;
+            self.swap(self.m_position[v as usize], last);
+            self.m_start[d + 1] -= 1;
+            self.m_degrees[v as usize] += 1;
+        }
+        while self.m_degrees[v as usize] > degree {
+            let d = self.m_degrees[v as usize] as usize;
+            let first = self.m_start[d];
+            self.swap(self.m_position[v as usize], first);
+            self.m_start[d] += 1;
+            self.m_degrees[v as usize] -= 1;
+        }
+    }
+}
+
+impl BitGraph {
+    /// Enables the degree buckets, which make `min_degree_vertex` and `max_degree_vertex`
+    /// constant time. The buckets are kept up to date by all mutating methods
+    pub fn enable_degree_buckets(&mut self) {
+        if self.m_buckets.is_none() {
+            self.m_buckets = Some(DegreeBuckets::new(&self.m_degrees));
+        }
+    }
+
+    /// Disables the degree buckets and drops them
+    pub fn disable_degree_buckets(&mut self) {
+        self.m_buckets = None;
+    }
+
+    /// Returns `true` if the degree buckets are enabled
+    pub fn has_degree_buckets(&self) -> bool {
+        self.m_buckets.is_some()
+    }
+
+    /// A non-isolated vertex of smallest degree, `None` if the graph has no edges.
+    /// Takes constant time with degree buckets and a scan over the degrees otherwise
+    pub fn min_degree_vertex(&self) -> Option<u32> {
+        match &self.m_buckets {
+            Some(buckets) => buckets.m_sorted.get(buckets.m_start[1] as usize).copied(),
+            None => (0..self.m_degrees.len() as u32)
+                .filter(|&v| self.m_degrees[v as usize] > 0)
+                .min_by_key(|&v| self.m_degrees[v as usize]),
+        }
+    }
+
+    /// A vertex of largest degree, `None` if the graph has no edges.
+    /// Takes constant time with degree buckets and a scan over the degrees otherwise
+    pub fn max_degree_vertex(&self) -> Option<u32> {
+        let v = match &self.m_buckets {
+            Some(buckets) => *buckets.m_sorted.last()?,
+            None => (0..self.m_degrees.len() as u32).max_by_key(|&v| self.m_degrees[v as usize])?,
+        };
+        Some(v).filter(|&v| self.m_degrees[v as usize] > 0)
+    }
+}
+
+impl<I: VertexId> BitGraph<I> {
+    /// Hook called after the degree of `v` changed
+    #[inline]
+    pub(crate) fn degree_changed(&mut self, v: u32) {
+        if let Some(buckets) = self.m_buckets.as_mut() {
+            buckets.update(v, self.m_degrees[v as usize].index() as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{generators, BitGraph};
+
+    fn check_buckets(c: &BitGraph) {
+        let positive = c.m_degrees.iter().copied().filter(|&d| d > 0);
+        match c.min_degree_vertex() {
+            Some(v) => assert_eq!(Some(c.degree(v)), positive.min()),
+            None => assert_eq!(c.size(), 0),
+        }
+        match c.max_degree_vertex() {
+            Some(v) => assert_eq!(c.degree(v), c.max_degree()),
+            None => assert_eq!(c.size(), 0),
+        }
+        let buckets = c.m_buckets.as_ref().unwrap();
+        for (i, &v) in buckets.m_sorted.iter().enumerate() {
+            assert_eq!(buckets.m_position[v as usize], i as u32);
+            let d = c.degree(v) as usize;
+            assert!(buckets.m_start[d] as usize <= i && i < buckets.m_start[d + 1] as usize);
+        }
+    }
+
+    #[test]
+    fn degree_buckets() {
+        let capacity: usize = 30;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.min_degree_vertex(), None);
+        assert_eq!(c.max_degree_vertex(), None);
+        c.enable_degree_buckets();
+        assert!(c.has_degree_buckets());
+        assert_eq!(c.min_degree_vertex(), None);
+        assert_eq!(c.max_degree_vertex(), None);
+        c.add_edge(3, 4);
+        c.add_edge(3, 5);
+        assert_eq!(c.max_degree_vertex(), Some(3));
+        check_buckets(&c);
+
+        let mut rng = SplitMix64::new(41);
+        for _ in 0..2000 {
+            let u = rng.below(capacity as u64) as u32;
+            let v = rng.below(capacity as u64) as u32;
+            if u == v {
+                continue;
+            }
+            match rng.below(10) {
+                0 if c.row(u).contains(v) => c.contract_edge(u, v),
+                1..=4 => c.remove_edge(u, v),
+                _ => c.add_edge(u, v),
+            }
+            check_buckets(&c);
+        }
+        let mut d = c.clone();
+        d.disable_degree_buckets();
+        assert_eq!(
+            c.degree(c.min_degree_vertex().unwrap()),
+            d.degree(d.min_degree_vertex().unwrap())
+        );
+        assert_eq!(
+            c.degree(c.max_degree_vertex().unwrap()),
+            d.degree(d.max_degree_vertex().unwrap())
+        );
+        d.enable_degree_buckets();
+        check_buckets(&d);
+    }
+
+    #[test]
+    fn degree_buckets_after_bulk_edits() {
+        let capacity: usize = 12;
+        // a star with center 0 and a path 6-7-8-9 hanging off leaf 5
+        let mut c = generators::star(6);
+        c.grow_to(capacity as u32);
+        c.add_edge(5, 6);
+        c.add_edge(6, 7);
+        c.add_edge(7, 8);
+        c.add_edge(8, 9);
+        c.enable_journal();
+        c.enable_degree_buckets();
+        check_buckets(&c);
+        assert_eq!(c.max_degree_vertex(), Some(0));
+        assert_eq!(c.degree(c.min_degree_vertex().unwrap()), 1);
+
+        // 0 takes over the path neighbor of 5, 5 loses all of its edges
+        c.contract_edge(0, 5);
+        check_buckets(&c);
+        assert_eq!(c.degree(0), 5);
+        assert_eq!(c.max_degree_vertex(), Some(0));
+
+        // the leaves of 0 become a clique of degree 4, 6 keeps its edge to 7
+        c.eliminate_vertex(0);
+        check_buckets(&c);
+        assert_eq!(c.degree(0), 0);
+        assert_eq!(c.degree(c.max_degree_vertex().unwrap()), 5);
+        assert_eq!(c.max_degree_vertex(), Some(6));
+        assert_eq!(c.degree(c.min_degree_vertex().unwrap()), 1);
+
+        // only the path is left
+        c.retain_edges(|u, v| u >= 6 && v >= 6);
+        check_buckets(&c);
+        assert_eq!(c.size(), 3);
+        assert_eq!(c.degree(c.max_degree_vertex().unwrap()), 2);
+        assert_eq!(c.degree(c.min_degree_vertex().unwrap()), 1);
+
+        // the new vertices are isolated and end up in bucket 0
+        c.grow_to(2 * capacity as u32);
+        check_buckets(&c);
+        c.add_edge(20, 7);
+        check_buckets(&c);
+        assert_eq!(c.max_degree_vertex(), Some(7));
+
+        assert_eq!(c.undo(2), 2);
+        check_buckets(&c);
+        assert_eq!(c.capacity(), capacity as u32);
+        assert_eq!(c.undo(3), 3);
+        check_buckets(&c);
+        assert_eq!(c.max_degree_vertex(), Some(0));
+        assert_eq!(c.degree(0), 5);
+        assert_eq!(c.redo(5), 5);
+        check_buckets(&c);
+        assert_eq!(c.capacity(), 2 * capacity as u32);
+        assert_eq!(c.max_degree_vertex(), Some(7));
+        assert_eq!(c.degree(7), 3);
+    }
+}
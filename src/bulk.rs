@@ -0,0 +1,427 @@
+//! Mutations that change many edges at once
+//!
+//! These edit the rows directly and recount the degrees of the touched vertices at the end,
+//! instead of going through the per-edge bookkeeping of `add_edge` and `remove_edge`.
+
+use crate::{BitGraph, VertexId};
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::{BitSetLike, BitSetNot};
+
+impl BitGraph {
+    /// Adds all `edges`, existing ones and duplicates are ignored. Buckets the endpoints by row
+    /// so every row is written in one go, and recounts the degrees of the touched rows afterwards
+    pub fn add_edges(&mut self, edges: &[(u32, u32)]) {
+        self.journal_begin();
+        let n = self.m_data.len();
+        let mut start = vec![0usize; n + 1];
+        for &(u, v) in edges.iter() {
+            self.check_bounds(u);
+            self.check_bounds(v);
+            self.check_is_same(u, v);
+            start[u as usize + 1] += 1;
+            start[v as usize + 1] += 1;
+        }
+        for u in 0..n {
+            start[u + 1] += start[u];
+        }
+        let mut next = start.clone();
+        let mut endpoints = vec![0u32; 2 * edges.len()];
+        for &(u, v) in edges.iter() {
+            endpoints[next[u as usize]] = v;
+            next[u as usize] += 1;
+            endpoints[next[v as usize]] = u;
+            next[v as usize] += 1;
+        }
+        let mut touched = hibitset::BitSet::with_capacity(n as u32);
+        for u in (0..n).filter(|&u| start[u] < start[u + 1]) {
+            let row = self.row_mut(u as u32);
+            for &v in endpoints[start[u]..start[u + 1]].iter() {
+                row.add(v);
+            }
+            touched.add(u as u32);
+        }
+        self.recount(&touched);
+    }
+
+    /// Adds the edges between consecutive vertices of `path`. Bounds are checked once for the
+    /// largest vertex, consecutive vertices have to be distinct
+    pub fn add_path(&mut self, path: &[u32]) {
+        self.journal_begin();
+        if let Some(&max) = path.iter().max() {
+            self.check_bounds(max);
+        }
+        for pair in path.windows(2) {
+            self.link(pair[0], pair[1]);
+        }
+    }
+
+    /// Adds the edges between consecutive vertices of `cycle` and from its last vertex back to
+    /// its first one. A non-empty cycle needs at least 3 vertices
+    pub fn add_cycle(&mut self, cycle: &[u32]) {
+        self.journal_begin();
+        if cycle.is_empty() {
+            return;
+        }
+        if cycle.len() < 3 {
+            panic!("Cycle needs at least 3 vertices, given: {:?}", cycle)
+        }
+        self.add_path(cycle);
+        self.link(cycle[cycle.len() - 1], cycle[0]);
+    }
+
+    /// Same as `add_edge` after the bounds were checked
+    fn link(&mut self, u: u32, v: u32) {
+        self.check_is_same(u, v);
+        if self.add_endpoint(u, v) {
+            self.add_endpoint(v, u);
+            self.edge_added(u, v);
+        }
+    }
+
+    /// Makes `set` a clique by or-ing the set into the row of each member without its own bit.
+    /// Returns the number of edges that were added
+    pub fn add_clique(&mut self, set: &hibitset::BitSet) -> u64 {
+        self.journal_begin();
+        let mut before = 0;
+        for v in set.iter() {
+            self.check_bounds(v);
+            before += self.m_degrees[v as usize] as u64;
+        }
+        for v in set.iter() {
+            let row = self.row_mut(v);
+            *row |= set;
+            row.remove(v);
+        }
+        self.recount(set);
+        let after: u64 = set.iter().map(|v| self.m_degrees[v as usize] as u64).sum();
+        (after - before) / 2
+    }
+
+    /// Turns the neighborhood of `v` into a clique and removes all edges of `v`, one step of the
+    /// elimination game. Returns the number of fill edges that were added
+    pub fn eliminate_vertex(&mut self, v: u32) -> u64 {
+        self.check_bounds(v);
+        self.journal_begin();
+        if self.m_degrees[v as usize] == 0 {
+            return 0;
+        }
+        let neighborhood: hibitset::BitSet = self.row(v).clone();
+        let degree = self.m_degrees[v as usize] as u64;
+        let before: u64 = (&neighborhood)
+            .iter()
+            .map(|w| self.m_degrees[w as usize] as u64)
+            .sum();
+        for w in (&neighborhood).iter() {
+            let row = self.row_mut(w);
+            *row |= &neighborhood;
+            row.remove(w);
+            row.remove(v);
+        }
+        self.row_mut(v).clear();
+        let mut touched = neighborhood;
+        touched.add(v);
+        self.recount(&touched);
+        touched.remove(v);
+        let after: u64 = (&touched)
+            .iter()
+            .map(|w| self.m_degrees[w as usize] as u64)
+            .sum();
+        (after + degree - before) / 2
+    }
+
+    /// Keeps only the edges `(u, v)` with `u < v` for which `predicate(u, v)` returns `true`.
+    /// Every edge is passed to the predicate once
+    pub fn retain_edges<P: FnMut(u32, u32) -> bool>(&mut self, mut predicate: P) {
+        self.journal_begin();
+        let n = self.m_data.len() as u32;
+        let mut touched = hibitset::BitSet::with_capacity(n);
+        let mut dropped: Vec<u32> = Vec::new();
+        for u in 0..n {
+            dropped.clear();
+            dropped.extend(self.neighbors(u).filter(|&v| u < v && !predicate(u, v)));
+            if dropped.is_empty() {
+                continue;
+            }
+            touched.add(u);
+            for &v in dropped.iter() {
+                self.row_mut(u).remove(v);
+                self.row_mut(v).remove(u);
+                touched.add(v);
+            }
+        }
+        self.recount(&touched);
+    }
+
+    /// Removes every edge with one end in `a` and the other one in `b`. Clears the bits of the
+    /// other set word by word in the rows of the members of both sets
+    pub fn remove_edges_between(&mut self, a: &hibitset::BitSet, b: &hibitset::BitSet) {
+        self.journal_begin();
+        let mut touched = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        for x in a.iter() {
+            self.check_bounds(x);
+            if self.m_degrees[x as usize] == 0 {
+                continue;
+            }
+            *self.row_mut(x) &= &BitSetNot(b);
+            touched.add(x);
+        }
+        for x in b.iter() {
+            self.check_bounds(x);
+            if self.m_degrees[x as usize] == 0 {
+                continue;
+            }
+            *self.row_mut(x) &= &BitSetNot(a);
+            touched.add(x);
+        }
+        self.recount(&touched);
+    }
+
+    /// Recomputes the degrees of the vertices in `touched` from their rows, and the order.
+    /// Drops cached invariants if anything was touched
+    pub(crate) fn recount(&mut self, touched: &hibitset::BitSet) {
+        let mut any = false;
+        for v in touched.iter() {
+            any = true;
+            let degree = self
+                .row(v)
+                .layer0_as_slice()
+                .iter()
+                .map(|word| word.count_ones())
+                .sum();
+            let old = core::mem::replace(&mut self.m_degrees[v as usize], degree);
+            match (old, degree) {
+                (0, d) if d > 0 => self.m_order += 1,
+                (d, 0) if d > 0 => self.m_order -= 1,
+                _ => {}
+            }
+            self.degree_changed(v);
+        }
+        if any {
+            self.invalidate_cache();
+        }
+    }
+}
+
+impl<I: VertexId> BitGraph<I> {
+    /// Same as `remove_edge` after the bounds were checked
+    pub(crate) fn unlink(&mut self, u: u32, v: u32) {
+        self.check_is_same(u, v);
+        if self.remove_endpoint(u, v) {
+            self.remove_endpoint(v, u);
+            self.edge_removed(u, v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    #[test]
+    fn add_edges() {
+        let mut rng = SplitMix64::new(50);
+        let d = generators::gnp(50, 0.2, &mut rng);
+        let mut edges: Vec<(u32, u32)> = d.edges().map(|(u, v)| (v, u)).collect();
+        edges.push(edges[0]);
+        let mut c = BitGraph::with_capacity(50);
+        c.enable_invariant_cache();
+        c.enable_degree_buckets();
+        c.add_edge(edges[1].0, edges[1].1);
+        assert_eq!(c.triangle_count(), 0);
+        c.add_edges(&edges);
+        assert_eq!(c, d);
+        assert_eq!(c.order(), d.order());
+        assert_eq!(c.triangle_count(), d.triangle_count());
+        assert_eq!(c.degree(c.max_degree_vertex().unwrap()), d.max_degree());
+        c.add_edges(&[]);
+        assert_eq!(c, d);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_edges_self_loop() {
+        BitGraph::with_capacity(3).add_edges(&[(0, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn add_clique() {
+        let capacity: usize = 10;
+        let mut c = generators::path(capacity as u32);
+        c.enable_invariant_cache();
+        assert_eq!(c.triangle_count(), 0);
+        let mut set = hibitset::BitSet::new();
+        for &v in [2, 3, 4, 7].iter() {
+            set.add(v);
+        }
+        assert_eq!(c.add_clique(&set), 4);
+        assert_eq!(c.size(), 13);
+        assert_eq!(c.degree(7), 5);
+        assert_eq!(c.degree(3), 3);
+        assert_eq!(c.triangle_count(), 4);
+        assert!(c.neighbors(2).all(|w| w != 2));
+        assert_eq!(c.add_clique(&set), 0);
+
+        let mut single = hibitset::BitSet::new();
+        single.add(9);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(d.add_clique(&single), 0);
+        assert_eq!(d.order(), 0);
+        assert_eq!(d.neighbors(9).next(), None);
+        assert_eq!(d.add_clique(&hibitset::BitSet::new()), 0);
+    }
+
+    #[test]
+    fn add_path_and_cycle() {
+        let capacity: usize = 8;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.enable_invariant_cache();
+        assert_eq!(c.component_count(), 0);
+        c.add_path(&[3, 1, 4, 0]);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(0, 4), (1, 3), (1, 4)]);
+        assert_eq!(c.component_count(), 1);
+        c.add_cycle(&[5, 6, 7]);
+        assert_eq!(c.size(), 6);
+        assert_eq!(c.triangle_count(), 1);
+        assert_eq!(c.component_count(), 2);
+        c.add_path(&[2]);
+        c.add_path(&[]);
+        c.add_cycle(&[]);
+        assert_eq!(c.size(), 6);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(3, 1), (1, 4), (4, 0), (5, 6), (6, 7), (7, 5)].iter() {
+            d.add_edge(u, v);
+        }
+        assert_eq!(c, d);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_path_out_of_bounds() {
+        BitGraph::with_capacity(4).add_path(&[0, 1, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_cycle_too_short() {
+        BitGraph::with_capacity(4).add_cycle(&[0, 1]);
+    }
+
+    #[test]
+    fn eliminate_vertex() {
+        let capacity: usize = 7;
+        let mut c = generators::cycle(capacity as u32);
+        c.enable_degree_buckets();
+        assert_eq!(c.eliminate_vertex(0), 1);
+        assert_eq!(c.degree(0), 0);
+        assert!(c.row(1).contains(6));
+        assert_eq!(c.size(), 6);
+        assert_eq!(c.order(), 6);
+        assert_eq!(c.eliminate_vertex(0), 0);
+        // eliminating the rest of a cycle only ever adds one fill edge per step
+        let fill: u64 = (1..5).map(|v| c.eliminate_vertex(v)).sum();
+        assert_eq!(fill, 3);
+        assert_eq!(c.size(), 1);
+        assert_eq!(c.min_degree_vertex().map(|v| c.degree(v)), Some(1));
+
+        let mut rng = SplitMix64::new(53);
+        let mut c = generators::gnp(30, 0.2, &mut rng);
+        let mut d = c.clone();
+        for v in 0..30 {
+            let neighbors: Vec<u32> = d.neighbors(v).collect();
+            let mut fill = 0;
+            for (i, &a) in neighbors.iter().enumerate() {
+                for &b in neighbors[i + 1..].iter() {
+                    if !d.row(a).contains(b) {
+                        d.add_edge(a, b);
+                        fill += 1;
+                    }
+                }
+            }
+            d.clear_vertex(v);
+            assert_eq!(c.eliminate_vertex(v), fill);
+            assert_eq!(c, d);
+        }
+        assert_eq!(c.order(), 0);
+    }
+
+    #[test]
+    fn retain_edges() {
+        let mut rng = SplitMix64::new(51);
+        let mut c = generators::gnp(40, 0.3, &mut rng);
+        c.enable_invariant_cache();
+        c.enable_degree_buckets();
+        let triangles = c.triangle_count();
+        let mut d = c.clone();
+        let mut seen = 0;
+        c.retain_edges(|u, v| {
+            assert!(u < v);
+            seen += 1;
+            (u + v) % 3 != 0
+        });
+        assert_eq!(seen, d.size());
+        let edges: Vec<(u32, u32)> = d.edges().filter(|&(u, v)| (u + v) % 3 == 0).collect();
+        for (u, v) in edges {
+            d.remove_edge(u, v);
+        }
+        assert_eq!(c, d);
+        assert_eq!(c.order(), d.order());
+        assert_eq!(c.triangle_count(), d.triangle_count());
+        assert!(c.triangle_count() < triangles);
+        assert_eq!(c.degree(c.max_degree_vertex().unwrap()), d.max_degree());
+
+        c.retain_edges(|u, _| u >= 20);
+        assert!(c.edges().all(|(u, _)| u >= 20));
+        assert_eq!(
+            c.order() as usize,
+            (20..40).filter(|&v| c.degree(v) > 0).count()
+        );
+        c.retain_edges(|_, _| false);
+        assert_eq!(c.size(), 0);
+        assert_eq!(c.order(), 0);
+        assert_eq!(c.component_count(), 0);
+    }
+
+    #[test]
+    fn remove_edges_between() {
+        let mut rng = SplitMix64::new(52);
+        let mut c = generators::gnp(60, 0.4, &mut rng);
+        let mut d = c.clone();
+        let mut a = hibitset::BitSet::new();
+        let mut b = hibitset::BitSet::new();
+        for v in 0..30 {
+            a.add(v);
+        }
+        for v in 20..50 {
+            b.add(v);
+        }
+        let guard = c.neighbors_guard(25);
+        c.remove_edges_between(&a, &b);
+        let cut: Vec<(u32, u32)> = d
+            .edges()
+            .filter(|&(u, v)| {
+                (u < 30 && (20..50).contains(&v)) || (v < 30 && (20..50).contains(&u))
+            })
+            .collect();
+        assert!(!cut.is_empty());
+        for (u, v) in cut {
+            d.remove_edge(u, v);
+        }
+        assert_eq!(c, d);
+        assert_eq!(c.order(), d.order());
+        assert!(guard.iter().any(|w| w < 30));
+        assert!(c.neighbors(25).all(|w| w >= 50));
+
+        let mut c = BitGraph::complete(4);
+        let (mut a, mut b) = (hibitset::BitSet::new(), hibitset::BitSet::new());
+        a.add(0);
+        b.add(1);
+        b.add(2);
+        c.remove_edges_between(&a, &b);
+        assert_eq!(c.size(), 4);
+        assert_eq!(c.degree(0), 1);
+    }
+}
@@ -0,0 +1,269 @@
+//! Centrality measures
+//!
+//! Distances are obtained from `bfs_layers`, so each BFS works on whole frontier bitsets.
+//! All `capacity` vertices count as vertices of the graph, isolated ones included
+
+use crate::random::RandomSource;
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::BitSetLike;
+
+/// Per vertex sums over the distances to a set of sources
+struct DistanceSums {
+    distance: Vec<f64>,
+    inverse: Vec<f64>,
+    reached: Vec<f64>,
+}
+
+impl BitGraph {
+    /// Closeness centrality of every vertex, normalized as in Wasserman and Faust so that it
+    /// also works for disconnected graphs: `(r - 1)^2 / ((n - 1) * d)` where `r` is the number of
+    /// vertices reachable from `v` including itself and `d` the sum of their distances to `v`
+    pub fn closeness_centrality(&self) -> Vec<f64> {
+        let sources: Vec<u32> = (0..self.m_data.len() as u32).collect();
+        self.distance_sums(&sources).closeness()
+    }
+
+    /// Harmonic centrality of every vertex, the sum of `1 / d(u, v)` over all `u != v`
+    pub fn harmonic_centrality(&self) -> Vec<f64> {
+        let sources: Vec<u32> = (0..self.m_data.len() as u32).collect();
+        self.distance_sums(&sources).inverse
+    }
+
+    /// Estimate of `closeness_centrality` from breadth first searches started at `samples`
+    /// random vertices instead of all of them
+    pub fn closeness_centrality_sampled<R: RandomSource>(
+        &self,
+        samples: u32,
+        rng: &mut R,
+    ) -> Vec<f64> {
+        self.distance_sums(&self.sample_vertices(samples, rng))
+            .closeness()
+    }
+
+    /// Estimate of `harmonic_centrality` from breadth first searches started at `samples`
+    /// random vertices instead of all of them
+    pub fn harmonic_centrality_sampled<R: RandomSource>(
+        &self,
+        samples: u32,
+        rng: &mut R,
+    ) -> Vec<f64> {
+        self.distance_sums(&self.sample_vertices(samples, rng))
+            .inverse
+    }
+
+    /// Eigenvector centrality by power iteration on `A + I`, normalized to unit Euclidean length.
+    /// Each product sums the scores of the neighbors in the row bitsets. Returns `None` if the
+    /// scores did not change by less than `n * tol` in total within `max_iters` iterations.
+    /// Needs the `std` feature for the square root of the norm
+    #[cfg(feature = "std")]
+    pub fn eigenvector_centrality(&self, max_iters: u32, tol: f64) -> Option<Vec<f64>> {
+        let n = self.m_data.len();
+        if n == 0 {
+            return Some(Vec::new());
+        }
+        let mut x = vec![1.0 / n as f64; n];
+        for _ in 0..max_iters {
+            // the identity shift keeps the iteration from oscillating on bipartite graphs
+            let mut next: Vec<f64> = (0..n as u32)
+                .map(|v| x[v as usize] + self.neighbors(v).map(|w| x[w as usize]).sum::<f64>())
+                .collect();
+            let norm = next.iter().map(|y| y * y).sum::<f64>().sqrt();
+            for y in next.iter_mut() {
+                *y /= norm;
+            }
+            let change: f64 = next.iter().zip(x.iter()).map(|(a, b)| (a - b).abs()).sum();
+            x = next;
+            if change < n as f64 * tol {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    /// PageRank of every vertex with every edge used in both directions. The scores sum to 1,
+    /// isolated vertices spread their score evenly over all vertices. Iterates until the scores
+    /// change by less than `n * tol` in total, but at most 1000 times.
+    /// `damping` is the probability of following an edge and must be in `[0, 1)`
+    pub fn pagerank(&self, damping: f64, tol: f64) -> Vec<f64> {
+        assert!(
+            (0.0..1.0).contains(&damping),
+            "Damping must be in [0, 1), given: {}",
+            damping
+        );
+        let n = self.m_data.len();
+        let mut x = vec![1.0 / n as f64; n];
+        for _ in 0..1000 {
+            let dangling: f64 = (0..n)
+                .filter(|&v| self.m_degrees[v] == 0)
+                .map(|v| x[v])
+                .sum();
+            let base = (1.0 - damping + damping * dangling) / n as f64;
+            let next: Vec<f64> = (0..n as u32)
+                .map(|v| {
+                    base + damping
+                        * self
+                            .neighbors(v)
+                            .map(|w| x[w as usize] / self.m_degrees[w as usize] as f64)
+                            .sum::<f64>()
+                })
+                .collect();
+            let change: f64 = next.iter().zip(x.iter()).map(|(a, b)| (a - b).abs()).sum();
+            x = next;
+            if change < n as f64 * tol {
+                break;
+            }
+        }
+        x
+    }
+
+    /// Returns `samples` distinct vertices chosen uniformly at random
+    fn sample_vertices<R: RandomSource>(&self, samples: u32, rng: &mut R) -> Vec<u32> {
+        let mut vertices: Vec<u32> = (0..self.m_data.len() as u32).collect();
+        let k = (samples as usize).min(vertices.len());
+        for i in 0..k {
+            let j = i + rng.below((vertices.len() - i) as u64) as usize;
+            vertices.swap(i, j);
+        }
+        vertices.truncate(k);
+        vertices
+    }
+
+    /// Sums the distances from `sources` to every vertex, scaled up to all `n - 1` other vertices
+    fn distance_sums(&self, sources: &[u32]) -> DistanceSums {
+        let n = self.m_data.len();
+        let mut sums = DistanceSums {
+            distance: vec![0.0; n],
+            inverse: vec![0.0; n],
+            reached: vec![0.0; n],
+        };
+        let mut is_source = hibitset::BitSet::with_capacity(n as u32);
+        for &s in sources {
+            is_source.add(s);
+            for (d, layer) in self.bfs_layers(s).enumerate().skip(1) {
+                for t in layer.iter() {
+                    sums.distance[t as usize] += d as f64;
+                    sums.inverse[t as usize] += 1.0 / d as f64;
+                    sums.reached[t as usize] += 1.0;
+                }
+            }
+        }
+        for t in 0..n {
+            let others = sources.len() - is_source.contains(t as u32) as usize;
+            if others > 0 {
+                let scale = (n - 1) as f64 / others as f64;
+                sums.distance[t] *= scale;
+                sums.inverse[t] *= scale;
+                sums.reached[t] *= scale;
+            }
+        }
+        sums
+    }
+}
+
+impl DistanceSums {
+    fn closeness(&self) -> Vec<f64> {
+        let n = self.distance.len();
+        self.distance
+            .iter()
+            .zip(self.reached.iter())
+            .map(|(&d, &r)| {
+                if d > 0.0 {
+                    r * r / ((n - 1) as f64 * d)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn closeness_and_harmonic() {
+        let capacity: usize = 6;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(2, 3);
+        c.add_edge(4, 5);
+        assert_close(&c.closeness_centrality(), &[0.3, 0.45, 0.45, 0.3, 0.2, 0.2]);
+        let third = 1.0 / 3.0;
+        assert_close(
+            &c.harmonic_centrality(),
+            &[1.5 + third, 2.5, 2.5, 1.5 + third, 1.0, 1.0],
+        );
+        let mut rng = SplitMix64::new(1);
+        assert_close(
+            &c.closeness_centrality_sampled(10, &mut rng),
+            &c.closeness_centrality(),
+        );
+        assert_close(
+            &BitGraph::with_capacity(3).closeness_centrality(),
+            &[0.0; 3],
+        );
+
+        let c = generators::torus(12, 12, false);
+        let exact = c.harmonic_centrality();
+        let estimate = c.harmonic_centrality_sampled(72, &mut rng);
+        for (x, y) in exact.iter().zip(estimate.iter()) {
+            assert!((x - y).abs() < 0.3 * x, "{} {}", x, y);
+        }
+        let estimate = c.closeness_centrality_sampled(72, &mut rng);
+        for (x, y) in c.closeness_centrality().iter().zip(estimate.iter()) {
+            assert!((x - y).abs() < 0.3 * x, "{} {}", x, y);
+        }
+    }
+
+    #[test]
+    fn eigenvector_centrality() {
+        let x = generators::star(5)
+            .eigenvector_centrality(100, 1e-9)
+            .unwrap();
+        let leaf = 0.5_f64.sqrt() / 2.0;
+        assert_close(&x, &[0.5_f64.sqrt(), leaf, leaf, leaf, leaf]);
+        let x = generators::cycle(6)
+            .eigenvector_centrality(100, 1e-9)
+            .unwrap();
+        assert_close(&x, &[1.0 / 6.0_f64.sqrt(); 6]);
+        let x = generators::path(4)
+            .eigenvector_centrality(1000, 1e-12)
+            .unwrap();
+        assert!(x[1] > x[0] && (x[1] - x[2]).abs() < 1e-9);
+        assert_eq!(generators::path(30).eigenvector_centrality(2, 1e-12), None);
+        assert_eq!(
+            BitGraph::with_capacity(0).eigenvector_centrality(1, 0.1),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn pagerank() {
+        let x = generators::cycle(5).pagerank(0.85, 1e-10);
+        assert_close(&x, &[0.2; 5]);
+        let x = generators::star(4).pagerank(0.85, 1e-12);
+        assert!((x.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        // the center gets (1 - d) / 4 + d * (1 - x0), every leaf (1 - d) / 4 + d * x0 / 3
+        let center = (0.15 / 4.0 + 0.85) / 1.85;
+        assert!((x[0] - center).abs() < 1e-9);
+        let mut c = BitGraph::with_capacity(3);
+        c.add_edge(0, 1);
+        let x = c.pagerank(0.5, 1e-12);
+        assert!((x.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!((x[0] - x[1]).abs() < 1e-12 && x[0] > x[2]);
+        assert!(BitGraph::with_capacity(0).pagerank(0.85, 1e-6).is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! Community detection
+
+use crate::random::RandomSource;
+use crate::BitGraph;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+impl BitGraph {
+    /// Detects communities by asynchronous label propagation and returns a community id per
+    /// vertex, numbered from 0 in order of first appearance. In random order, every vertex
+    /// adopts a label that is most frequent among its neighbors, ties broken at random, until all
+    /// vertices carry a most frequent label (at most 1000 rounds). Isolated vertices form their
+    /// own communities
+    pub fn label_propagation_communities<R: RandomSource>(&self, rng: &mut R) -> Vec<u32> {
+        let n = self.m_data.len();
+        let mut labels: Vec<u32> = (0..n as u32).collect();
+        let mut order: Vec<u32> = (0..n as u32).filter(|&v| self.degree(v) > 0).collect();
+        let mut counts = vec![0u32; n];
+        let mut seen: Vec<u32> = Vec::new();
+        let mut best: Vec<u32> = Vec::new();
+        for _ in 0..1000 {
+            for i in (1..order.len()).rev() {
+                order.swap(i, rng.below(i as u64 + 1) as usize);
+            }
+            let mut stable = true;
+            for &v in order.iter() {
+                for w in self.neighbors(v) {
+                    let l = labels[w as usize];
+                    if counts[l as usize] == 0 {
+                        seen.push(l);
+                    }
+                    counts[l as usize] += 1;
+                }
+                let max = seen.iter().map(|&l| counts[l as usize]).max().unwrap();
+                best.clear();
+                best.extend(seen.iter().filter(|&&l| counts[l as usize] == max));
+                if counts[labels[v as usize] as usize] != max {
+                    stable = false;
+                    labels[v as usize] = best[rng.below(best.len() as u64) as usize];
+                }
+                for l in seen.drain(..) {
+                    counts[l as usize] = 0;
+                }
+            }
+            if stable {
+                break;
+            }
+        }
+        // renumber in order of first appearance
+        let mut ids = vec![u32::MAX; n];
+        let mut next = 0;
+        for label in labels.iter_mut() {
+            if ids[*label as usize] == u32::MAX {
+                ids[*label as usize] = next;
+                next += 1;
+            }
+            *label = ids[*label as usize];
+        }
+        labels
+    }
+}
+
+impl BitGraph {
+    /// Modularity of the partition that puts vertex `v` into community `labels[v]`,
+    /// 0 for a graph without edges
+    pub fn modularity(&self, labels: &[u32]) -> f64 {
+        assert_eq!(labels.len(), self.m_data.len(), "Need one label per vertex");
+        let m = self.size() as f64;
+        if m == 0.0 {
+            return 0.0;
+        }
+        let k = labels.iter().max().map_or(0, |&l| l as usize + 1);
+        let mut inner = vec![0.0; k];
+        let mut degrees = vec![0.0; k];
+        for v in 0..self.m_data.len() as u32 {
+            let l = labels[v as usize];
+            degrees[l as usize] += self.degree(v) as f64;
+            inner[l as usize] += self
+                .neighbors(v)
+                .filter(|&w| labels[w as usize] == l)
+                .count() as f64;
+        }
+        // every inner edge was seen from both endpoints
+        inner
+            .iter()
+            .zip(degrees.iter())
+            .map(|(&e, &d)| e / (2.0 * m) - (d / (2.0 * m)) * (d / (2.0 * m)))
+            .sum()
+    }
+
+    /// Greedy agglomerative modularity clustering in the style of Clauset, Newman and Moore.
+    /// Starting from singletons, the two adjacent communities whose merge increases the
+    /// modularity the most are merged until no merge helps. Communities are contracted into
+    /// one vertex of a working copy of the graph, whose rows then hold the adjacent communities.
+    /// Returns a community id per vertex, numbered from 0 in order of first appearance
+    pub fn greedy_modularity_communities(&self) -> Vec<u32> {
+        let n = self.m_data.len();
+        let mut representative: Vec<u32> = (0..n as u32).collect();
+        let two_m = 2.0 * self.size() as f64;
+        if two_m > 0.0 {
+            let mut working = self.clone();
+            working.disable_invariant_cache();
+            // fraction of edge ends between two communities, keyed by their representatives
+            let mut between: BTreeMap<(u32, u32), f64> = BTreeMap::new();
+            for u in 0..n as u32 {
+                for v in self.neighbors(u).filter(|&v| u < v) {
+                    between.insert((u, v), 1.0 / two_m);
+                }
+            }
+            let mut share: Vec<f64> = self.m_degrees.iter().map(|&d| d as f64 / two_m).collect();
+            loop {
+                let best = between
+                    .iter()
+                    .map(|(&(i, j), &e)| {
+                        ((i, j), 2.0 * (e - share[i as usize] * share[j as usize]))
+                    })
+                    .fold(None, |best: Option<((u32, u32), f64)>, x| match best {
+                        Some(b) if b.1 >= x.1 => Some(b),
+                        _ => Some(x),
+                    });
+                let (i, j) = match best {
+                    Some((pair, gain)) if gain > 0.0 => pair,
+                    _ => break,
+                };
+                // merge j into i
+                for k in working.neighbors(j).collect::<Vec<u32>>() {
+                    let e = between.remove(&(j.min(k), j.max(k))).unwrap();
+                    if k != i {
+                        *between.entry((i.min(k), i.max(k))).or_insert(0.0) += e;
+                    }
+                }
+                working.contract_edge(i, j);
+                share[i as usize] += share[j as usize];
+                representative[j as usize] = i;
+            }
+        }
+        let mut ids = vec![u32::MAX; n];
+        let mut next = 0;
+        (0..n)
+            .map(|v| {
+                let mut r = v;
+                while representative[r] as usize != r {
+                    r = representative[r] as usize;
+                }
+                if ids[r] == u32::MAX {
+                    ids[r] = next;
+                    next += 1;
+                }
+                ids[r]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    #[test]
+    fn label_propagation_communities() {
+        // two 5-cliques joined by one edge
+        let capacity: usize = 11;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for u in 0..5 {
+            for v in u + 1..5 {
+                c.add_edge(u, v);
+                c.add_edge(u + 5, v + 5);
+            }
+        }
+        c.add_edge(4, 5);
+        let mut rng = SplitMix64::new(8);
+        for _ in 0..20 {
+            let labels = c.label_propagation_communities(&mut rng);
+            assert_eq!(labels, vec![0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 2]);
+        }
+        let labels = generators::complete_bipartite(1, 1).label_propagation_communities(&mut rng);
+        assert_eq!(labels.len(), 2);
+        assert!(BitGraph::with_capacity(0)
+            .label_propagation_communities(&mut rng)
+            .is_empty());
+    }
+
+    #[test]
+    fn modularity() {
+        let capacity: usize = 11;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for u in 0..5 {
+            for v in u + 1..5 {
+                c.add_edge(u, v);
+                c.add_edge(u + 5, v + 5);
+            }
+        }
+        c.add_edge(4, 5);
+        let labels = vec![0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 2];
+        let q = 20.0 / 21.0 - 0.5;
+        assert!((c.modularity(&labels) - q).abs() < 1e-12);
+        assert!(c.modularity(&[0; 11]).abs() < 1e-12);
+        assert_eq!(c.greedy_modularity_communities(), labels);
+        assert_eq!(
+            generators::cycle(6).greedy_modularity_communities(),
+            vec![0, 0, 1, 1, 2, 2]
+        );
+        assert_eq!(
+            BitGraph::with_capacity(2).greedy_modularity_communities(),
+            vec![0, 1]
+        );
+        assert_eq!(BitGraph::with_capacity(2).modularity(&[0, 1]), 0.0);
+    }
+}
@@ -0,0 +1,159 @@
+//! A graph for building in parallel
+
+use crate::BitGraph;
+use core::sync::atomic::{AtomicU32, Ordering};
+use hibitset::{AtomicBitSet, BitSetLike};
+
+/// An undirected graph whose edges can be added from many threads at once without a lock.
+/// The rows are `hibitset::AtomicBitSet`s and the degrees atomic counters, each endpoint is
+/// counted by the thread that set its bit. Removing edges needs exclusive access.
+/// Turn it into a `BitGraph` with `into_bit_graph` once it is built
+#[derive(Debug, Default)]
+pub struct ConcurrentBitGraph {
+    m_data: Vec<AtomicBitSet>,
+    m_degrees: Vec<AtomicU32>,
+}
+
+impl ConcurrentBitGraph {
+    /// Creates a new ConcurrentBitGraph with `capacity` vertices and no edges
+    pub fn with_capacity(capacity: u32) -> ConcurrentBitGraph {
+        BitGraph::check_capacity(capacity);
+        ConcurrentBitGraph {
+            m_data: (0..capacity).map(|_| AtomicBitSet::new()).collect(),
+            m_degrees: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    /// Number of vertices the graph can hold
+    pub fn capacity(&self) -> u32 {
+        self.m_data.len() as u32
+    }
+
+    /// Adds a new undirected edge from `u` to `v`, existing edges are ignored.
+    /// Can be called from many threads at once
+    pub fn add_edge(&self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if u == v {
+            panic!("Edge needs two distinct endpoints, given: {} {}", u, v)
+        }
+        if !self.m_data[u as usize].add_atomic(v) {
+            self.m_degrees[u as usize].fetch_add(1, Ordering::Relaxed);
+        }
+        if !self.m_data[v as usize].add_atomic(u) {
+            self.m_degrees[v as usize].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Removes the edge from `u` to `v` if it is present
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if self.m_data[u as usize].remove(v) {
+            *self.m_degrees[u as usize].get_mut() -= 1;
+        }
+        if self.m_data[v as usize].remove(u) {
+            *self.m_degrees[v as usize].get_mut() -= 1;
+        }
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.m_data[u as usize].contains(v)
+    }
+
+    /// Number of neighbors of `v` whose edges were added so far
+    pub fn degree(&self, v: u32) -> u32 {
+        self.check_bounds(v);
+        self.m_degrees[v as usize].load(Ordering::Relaxed)
+    }
+
+    /// Number of edges added so far
+    pub fn size(&self) -> u64 {
+        let twice: u64 = self
+            .m_degrees
+            .iter()
+            .map(|d| d.load(Ordering::Relaxed) as u64)
+            .sum();
+        twice / 2
+    }
+
+    /// Copies the rows into a `BitGraph` of the same capacity
+    pub fn into_bit_graph(self) -> BitGraph {
+        BitGraph::from_rows(
+            self.m_data
+                .iter()
+                .map(|row| row.iter().collect::<hibitset::BitSet>())
+                .collect(),
+        )
+    }
+
+    #[inline]
+    fn check_bounds(&self, idx: u32) {
+        if idx >= self.m_data.len() as u32 {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                idx,
+                self.m_data.len()
+            )
+        }
+    }
+}
+
+impl From<ConcurrentBitGraph> for BitGraph {
+    fn from(graph: ConcurrentBitGraph) -> BitGraph {
+        graph.into_bit_graph()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::{BitGraph, ConcurrentBitGraph};
+
+    #[test]
+    fn concurrent_add_edge() {
+        let capacity: usize = 300;
+        let mut rng = SplitMix64::new(67);
+        let expected = generators::gnp(capacity as u32, 0.1, &mut rng);
+        let edges: Vec<(u32, u32)> = expected.edges().collect();
+        let c = ConcurrentBitGraph::with_capacity(capacity as u32);
+        // every edge is added by two threads, one of them in reverse
+        std::thread::scope(|scope| {
+            for shard in 0..4 {
+                let (c, edges) = (&c, &edges);
+                scope.spawn(move || {
+                    for (i, &(u, v)) in edges.iter().enumerate() {
+                        match (i + shard) % 4 {
+                            0 => c.add_edge(u, v),
+                            1 => c.add_edge(v, u),
+                            _ => {}
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(c.size(), expected.size());
+        assert!((0..capacity as u32).all(|v| c.degree(v) == expected.degree(v)));
+        let (u, v) = edges[0];
+        assert!(c.has_edge(v, u));
+        let mut c = c;
+        c.remove_edge(u, v);
+        c.remove_edge(u, v);
+        assert!(!c.has_edge(u, v));
+        assert_eq!(c.degree(u), expected.degree(u) - 1);
+        let mut graph = BitGraph::from(c);
+        assert_eq!(graph.order(), expected.order());
+        graph.add_edge(u, v);
+        assert_eq!(graph, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn concurrent_out_of_bounds() {
+        ConcurrentBitGraph::with_capacity(3).add_edge(0, 3);
+    }
+}
@@ -0,0 +1,698 @@
+//! Cycle related predicates and algorithms
+
+use crate::{algorithms, BitGraph, UndirectedGraph};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::BitSetLike;
+
+/// Largest number of vertices for which `hamiltonian_cycle` uses the subset dynamic program.
+/// It keeps one 32-bit set of path ends per subset of the other vertices, 32MB at the limit
+pub const HELD_KARP_LIMIT: usize = 24;
+
+impl BitGraph {
+    /// Returns `true` if the graph contains no cycle
+    pub fn is_forest(&self) -> bool {
+        self.size() + self.component_count() as u64 == self.order() as u64
+    }
+
+    /// Returns `true` if the graph is a forest with exactly one component.
+    /// Isolated vertices are ignored, so a graph without edges is not a tree
+    pub fn is_tree(&self) -> bool {
+        self.order() > 0 && self.size() + 1 == self.order() as u64 && self.component_count() == 1
+    }
+
+    /// Returns `true` if the graph contains at least one cycle
+    pub fn has_cycle(&self) -> bool {
+        !self.is_forest()
+    }
+
+    /// Length of a shortest cycle, `None` if the graph is a forest.
+    /// Runs a BFS from every vertex, each one stops as soon as it can't find a shorter cycle
+    pub fn girth(&self) -> Option<u32> {
+        algorithms::girth(self)
+    }
+
+    /// Returns a fundamental cycle basis with respect to a DFS spanning forest.
+    /// Every non-tree edge `(u, w)` closes exactly one cycle, given as the vertex sequence
+    /// `u, parent(u), ..., w`, so the basis contains `size() - order() + component_count()` cycles
+    pub fn cycle_basis(&self) -> Vec<Vec<u32>> {
+        algorithms::cycle_basis(self)
+    }
+
+    /// Returns an Eulerian circuit as a closed vertex sequence (first and last vertex are equal)
+    /// that uses every edge exactly once, `None` if no such circuit exists
+    pub fn eulerian_circuit(&self) -> Option<Vec<u32>> {
+        if self.m_degrees.iter().any(|d| d % 2 == 1) {
+            return None;
+        }
+        let start = self.m_degrees.iter().position(|&d| d > 0).unwrap_or(0) as u32;
+        self.hierholzer(start)
+    }
+
+    /// Returns an Eulerian trail as a vertex sequence that uses every edge exactly once,
+    /// `None` if no such trail exists. If the graph has two vertices of odd degree, the
+    /// trail starts at the smaller one and ends at the other, otherwise it is a circuit
+    pub fn eulerian_trail(&self) -> Option<Vec<u32>> {
+        let mut odd = (0..self.m_degrees.len()).filter(|&v| self.m_degrees[v] % 2 == 1);
+        match (odd.next(), odd.next(), odd.next()) {
+            (None, _, _) => self.eulerian_circuit(),
+            (Some(u), Some(_), None) => self.hierholzer(u as u32),
+            _ => None,
+        }
+    }
+
+    /// Hierholzer's algorithm on a working copy of the rows
+    fn hierholzer(&self, start: u32) -> Option<Vec<u32>> {
+        if self.size() == 0 {
+            return Some(Vec::new());
+        }
+        let mut rows: Vec<hibitset::BitSet> = self.m_data.iter().map(|r| (**r).clone()).collect();
+        let mut trail = Vec::with_capacity(self.size() as usize + 1);
+        let mut stack = vec![start];
+        while let Some(&v) = stack.last() {
+            match (&rows[v as usize]).iter().next() {
+                Some(w) => {
+                    rows[v as usize].remove(w);
+                    rows[w as usize].remove(v);
+                    stack.push(w);
+                }
+                None => trail.push(stack.pop().unwrap()),
+            }
+        }
+        // edges left over belong to another component
+        if trail.len() as u64 != self.size() + 1 {
+            return None;
+        }
+        trail.reverse();
+        Some(trail)
+    }
+
+    /// Returns a Hamiltonian cycle through all non-isolated vertices, `None` if there is none.
+    /// Every vertex is listed once, the edge from the last back to the first vertex closes the cycle.
+    ///
+    /// Graphs with up to `HELD_KARP_LIMIT` vertices are solved with a Held-Karp style dynamic
+    /// program over vertex subsets, larger graphs with a backtracking search that prunes
+    /// partial paths leaving some vertex with less than two usable neighbors
+    pub fn hamiltonian_cycle(&self) -> Option<Vec<u32>> {
+        let vertices: Vec<u32> = (0..self.m_data.len() as u32)
+            .filter(|&v| self.degree(v) > 0)
+            .collect();
+        if vertices.len() < 3
+            || vertices.iter().any(|&v| self.degree(v) < 2)
+            || self.component_count() != 1
+            || self.unbalanced_bipartite()
+        {
+            return None;
+        }
+        if vertices.len() <= HELD_KARP_LIMIT {
+            self.hamiltonian_held_karp(&vertices)
+        } else {
+            self.hamiltonian_backtrack(&vertices)
+        }
+    }
+
+    /// Returns `true` if the graph is bipartite with sides of different size.
+    /// A Hamiltonian cycle alternates sides, so such graphs have none
+    fn unbalanced_bipartite(&self) -> bool {
+        let n = self.m_data.len();
+        let mut side = vec![u8::MAX; n];
+        let mut balance: i64 = 0;
+        let mut stack = Vec::new();
+        for root in 0..n as u32 {
+            if self.degree(root) == 0 || side[root as usize] != u8::MAX {
+                continue;
+            }
+            side[root as usize] = 0;
+            stack.push(root);
+            while let Some(u) = stack.pop() {
+                balance += if side[u as usize] == 0 { 1 } else { -1 };
+                for w in self.neighbors(u) {
+                    if side[w as usize] == u8::MAX {
+                        side[w as usize] = 1 - side[u as usize];
+                        stack.push(w);
+                    } else if side[w as usize] == side[u as usize] {
+                        return false;
+                    }
+                }
+            }
+        }
+        balance != 0
+    }
+
+    fn hamiltonian_held_karp(&self, vertices: &[u32]) -> Option<Vec<u32>> {
+        // vertices[0] is the start, bit i - 1 of a mask stands for vertices[i]
+        let k = vertices.len() - 1;
+        let mut index = vec![usize::MAX; self.m_data.len()];
+        for (i, &v) in vertices.iter().enumerate() {
+            index[v as usize] = i;
+        }
+        let adj: Vec<u32> = vertices[1..]
+            .iter()
+            .map(|&v| {
+                self.neighbors(v)
+                    .map(|w| index[w as usize])
+                    .filter(|&i| i > 0)
+                    .fold(0, |mask, i| mask | 1 << (i - 1))
+            })
+            .collect();
+        let start_adj = self
+            .neighbors(vertices[0])
+            .fold(0u32, |mask, w| mask | 1 << (index[w as usize] - 1));
+        // dp[mask] holds all possible end vertices of a path from the start through `mask`
+        let mut dp = vec![0u32; 1 << k];
+        for e in 0..k {
+            if start_adj & (1 << e) != 0 {
+                dp[1 << e] = 1 << e;
+            }
+        }
+        for mask in 1..(1usize << k) {
+            let mut ends = dp[mask];
+            let mut next = 0;
+            while ends != 0 {
+                next |= adj[ends.trailing_zeros() as usize];
+                ends &= ends - 1;
+            }
+            next &= !(mask as u32);
+            while next != 0 {
+                let f = next.trailing_zeros();
+                dp[mask | 1 << f] |= 1 << f;
+                next &= next - 1;
+            }
+        }
+        let mut mask = (1usize << k) - 1;
+        let closing = dp[mask] & start_adj;
+        if closing == 0 {
+            return None;
+        }
+        let mut cur = closing.trailing_zeros();
+        let mut cycle = vec![vertices[cur as usize + 1]];
+        while mask.count_ones() > 1 {
+            mask ^= 1 << cur;
+            cur = (dp[mask] & adj[cur as usize]).trailing_zeros();
+            cycle.push(vertices[cur as usize + 1]);
+        }
+        cycle.push(vertices[0]);
+        cycle.reverse();
+        Some(cycle)
+    }
+
+    fn hamiltonian_backtrack(&self, vertices: &[u32]) -> Option<Vec<u32>> {
+        let bits = hibitset::BitSet::BITS_PER_USIZE;
+        let mut free = vec![0usize; self.m_data.len() / bits + 1];
+        for &v in vertices {
+            free[v as usize / bits] |= 1 << (v as usize % bits);
+        }
+        let free_neighbors = |free: &[usize], v: u32| -> u32 {
+            self.row(v)
+                .layer0_as_slice()
+                .iter()
+                .zip(free)
+                .map(|(a, b)| (a & b).count_ones())
+                .sum()
+        };
+        let candidates = |free: &[usize], v: u32| -> Vec<u32> {
+            let mut c: Vec<u32> = self
+                .neighbors(v)
+                .filter(|&w| free[w as usize / bits] & (1 << (w as usize % bits)) != 0)
+                .collect();
+            // pop() takes the most constrained vertex first
+            c.sort_by_key(|&w| core::cmp::Reverse(free_neighbors(free, w)));
+            c
+        };
+        let start = *vertices.iter().min_by_key(|&&v| self.degree(v)).unwrap();
+        free[start as usize / bits] &= !(1 << (start as usize % bits));
+        let mut path = vec![start];
+        let mut frames = vec![candidates(&free, start)];
+        while let Some(cands) = frames.last_mut() {
+            let w = match cands.pop() {
+                Some(w) => w,
+                None => {
+                    frames.pop();
+                    let v = path.pop().unwrap();
+                    free[v as usize / bits] |= 1 << (v as usize % bits);
+                    continue;
+                }
+            };
+            let x = *path.last().unwrap();
+            path.push(w);
+            free[w as usize / bits] &= !(1 << (w as usize % bits));
+            if path.len() == vertices.len() {
+                if self.row(w).contains(start) {
+                    return Some(path);
+                }
+            } else {
+                // x is now an inner vertex, its free neighbors lost a possible partner
+                let dead = x != start
+                    && self
+                        .neighbors(x)
+                        .filter(|&u| free[u as usize / bits] & (1 << (u as usize % bits)) != 0)
+                        .any(|u| {
+                            let row = self.row(u);
+                            free_neighbors(&free, u)
+                                + row.contains(w) as u32
+                                + (row.contains(start) as u32)
+                                < 2
+                        });
+                if !dead {
+                    frames.push(candidates(&free, w));
+                    continue;
+                }
+            }
+            path.pop();
+            free[w as usize / bits] |= 1 << (w as usize % bits);
+        }
+        None
+    }
+
+    /// Returns a lazy iterator over all simple cycles with at most `max_length` vertices
+    /// (all cycles if `None`). Every cycle is reported once, starting at its smallest vertex
+    pub fn simple_cycles(&self, max_length: Option<u32>) -> SimpleCycles<'_> {
+        algorithms::simple_cycles(self, max_length)
+    }
+}
+
+/// Iterator over the simple cycles of an `UndirectedGraph`, created by `BitGraph::simple_cycles`
+///
+/// Cycles are enumerated by backtracking from each start vertex `s` through vertices
+/// greater than `s`. Of the two traversal directions only the one whose second vertex is
+/// smaller than its last vertex is reported.
+///
+/// Without a length bound this is Johnson's algorithm: a vertex that closed no cycle stays
+/// blocked when it is left and is only unblocked, along its unblock lists, once one of its
+/// neighbors closes a cycle. With a bound, an extension is pruned if its BFS distance to `s`
+/// does not fit into the remaining length
+pub struct SimpleCycles<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_max_length: u32,
+    m_start: u32,
+    m_path: Vec<u32>,
+    m_iters: Vec<G::Neighbors<'a>>,
+    m_found: Vec<bool>,
+    m_blocked: hibitset::BitSet,
+    m_unblock: Vec<Vec<u32>>,
+    m_distance: Vec<u32>,
+}
+
+impl<'a, G: UndirectedGraph> SimpleCycles<'a, G> {
+    pub(crate) fn new(graph: &'a G, max_length: u32) -> SimpleCycles<'a, G> {
+        let n = graph.capacity() as usize;
+        // a bound below the capacity can cut off cycles that blocking relies on
+        let bounded = (max_length as usize) < n;
+        SimpleCycles {
+            m_graph: graph,
+            m_max_length: max_length,
+            m_start: 0,
+            m_path: Vec::new(),
+            m_iters: Vec::new(),
+            m_found: Vec::new(),
+            m_blocked: hibitset::BitSet::with_capacity(n as u32),
+            m_unblock: if bounded {
+                Vec::new()
+            } else {
+                vec![Vec::new(); n]
+            },
+            m_distance: if bounded {
+                vec![u32::MAX; n]
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    fn start(&mut self, s: u32) {
+        let graph = self.m_graph;
+        self.m_blocked.clear();
+        for list in &mut self.m_unblock {
+            list.clear();
+        }
+        if !self.m_distance.is_empty() {
+            // distances to s through vertices greater than s, as far as a cycle can reach
+            for d in &mut self.m_distance {
+                *d = u32::MAX;
+            }
+            self.m_distance[s as usize] = 0;
+            let mut queue = VecDeque::from(vec![s]);
+            while let Some(u) = queue.pop_front() {
+                let d = self.m_distance[u as usize] + 1;
+                if d >= self.m_max_length {
+                    break;
+                }
+                for w in graph.neighbors(u) {
+                    if w > s && self.m_distance[w as usize] == u32::MAX {
+                        self.m_distance[w as usize] = d;
+                        queue.push_back(w);
+                    }
+                }
+            }
+        }
+        self.push(s);
+    }
+
+    fn push(&mut self, v: u32) {
+        self.m_path.push(v);
+        self.m_iters.push(self.m_graph.neighbors(v));
+        self.m_found.push(false);
+        self.m_blocked.add(v);
+    }
+
+    fn pop(&mut self) {
+        let v = self.m_path.pop().unwrap();
+        self.m_iters.pop();
+        let found = self.m_found.pop().unwrap();
+        if self.m_unblock.is_empty() {
+            self.m_blocked.remove(v);
+        } else if found {
+            self.unblock(v);
+        } else if let Some(&s) = self.m_path.first() {
+            for w in self.m_graph.neighbors(v).filter(|&w| w > s) {
+                let list = &mut self.m_unblock[w as usize];
+                if !list.contains(&v) {
+                    list.push(v);
+                }
+            }
+        }
+        if let Some(parent) = self.m_found.last_mut() {
+            *parent |= found;
+        }
+    }
+
+    fn unblock(&mut self, v: u32) {
+        let mut stack = vec![v];
+        while let Some(u) = stack.pop() {
+            if self.m_blocked.remove(u) {
+                stack.append(&mut self.m_unblock[u as usize]);
+            }
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for SimpleCycles<'a, G> {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.m_graph;
+        if self.m_max_length < 3 {
+            return None;
+        }
+        loop {
+            if self.m_path.is_empty() {
+                let s = self.m_start;
+                if s >= graph.capacity() {
+                    return None;
+                }
+                self.m_start += 1;
+                if graph.degree(s) < 2 {
+                    continue;
+                }
+                self.start(s);
+            }
+            let s = self.m_path[0];
+            let w = match self.m_iters.last_mut().unwrap().next() {
+                Some(w) => w,
+                None => {
+                    self.pop();
+                    continue;
+                }
+            };
+            let len = self.m_path.len() as u32;
+            if w == s {
+                // going back and forth over an edge counts too, it unblocks like a cycle
+                *self.m_found.last_mut().unwrap() = true;
+                if len >= 3 && self.m_path[1] < *self.m_path.last().unwrap() {
+                    return Some(self.m_path.clone());
+                }
+            } else if w > s
+                && !self.m_blocked.contains(w)
+                && (self.m_distance.is_empty()
+                    || len.saturating_add(self.m_distance[w as usize]) <= self.m_max_length)
+            {
+                self.push(w);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SplitMix64;
+    use crate::{generators, BitGraph, HELD_KARP_LIMIT};
+
+    #[test]
+    fn forest_and_tree() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert!(c.is_forest());
+        assert!(!c.is_tree());
+        assert!(!c.has_cycle());
+
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(1, 3);
+        assert!(c.is_forest());
+        assert!(c.is_tree());
+
+        c.add_edge(5, 6);
+        assert!(c.is_forest());
+        assert!(!c.is_tree());
+
+        c.add_edge(2, 3);
+        assert!(!c.is_forest());
+        assert!(!c.is_tree());
+        assert!(c.has_cycle());
+
+        assert!(BitGraph::complete(3).has_cycle());
+        assert!(BitGraph::complete(2).is_tree());
+    }
+
+    #[test]
+    fn girth() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.girth(), None);
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        assert_eq!(c.girth(), None);
+        c.add_edge(4, 0);
+        assert_eq!(c.girth(), Some(5));
+        c.add_edge(1, 6);
+        c.add_edge(6, 3);
+        assert_eq!(c.girth(), Some(4));
+        assert_eq!(BitGraph::complete(5).girth(), Some(3));
+
+        // petersen graph
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..5 {
+            c.add_edge(i, (i + 1) % 5);
+            c.add_edge(i, i + 5);
+            c.add_edge(i + 5, (i + 2) % 5 + 5);
+        }
+        assert_eq!(c.girth(), Some(5));
+    }
+
+    #[test]
+    fn cycle_basis() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert!(c.cycle_basis().is_empty());
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        assert!(c.cycle_basis().is_empty());
+        c.add_edge(4, 0);
+        c.add_edge(1, 3);
+        c.add_edge(6, 7);
+        c.add_edge(7, 8);
+        c.add_edge(8, 6);
+
+        let basis = c.cycle_basis();
+        assert_eq!(
+            basis.len() as u64,
+            c.size() - c.order() as u64 + c.component_count() as u64
+        );
+        for cycle in basis.iter() {
+            assert!(cycle.len() >= 3);
+            for pair in cycle.windows(2) {
+                assert!(c.neighbors(pair[0]).any(|w| w == pair[1]));
+            }
+            assert!(c.neighbors(cycle[0]).any(|w| w == *cycle.last().unwrap()));
+        }
+        assert_eq!(BitGraph::complete(6).cycle_basis().len(), 10);
+    }
+
+    #[test]
+    fn simple_cycles() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.simple_cycles(None).count(), 0);
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        assert_eq!(c.simple_cycles(None).count(), 0);
+        c.add_edge(4, 0);
+        c.add_edge(1, 3);
+        let mut cycles: Vec<Vec<u32>> = c.simple_cycles(None).collect();
+        cycles.sort();
+        assert_eq!(
+            cycles,
+            vec![vec![0, 1, 2, 3, 4], vec![0, 1, 3, 4], vec![1, 2, 3]]
+        );
+        let mut cycles: Vec<Vec<u32>> = c.simple_cycles(Some(4)).collect();
+        cycles.sort();
+        assert_eq!(cycles, vec![vec![0, 1, 3, 4], vec![1, 2, 3]]);
+        assert_eq!(c.simple_cycles(Some(3)).count(), 1);
+        assert_eq!(c.simple_cycles(Some(2)).count(), 0);
+
+        // K5 has 10 triangles, 15 four-cycles and 12 five-cycles
+        let k = BitGraph::complete(5);
+        assert_eq!(k.simple_cycles(Some(3)).count(), 10);
+        assert_eq!(k.simple_cycles(Some(4)).count(), 25);
+        assert_eq!(k.simple_cycles(None).count(), 37);
+    }
+
+    #[test]
+    fn simple_cycles_blocked_and_bounded() {
+        // the Petersen graph has 12, 10, 0, 15 and 20 cycles of length 5 to 9
+        let c = generators::named::petersen();
+        let mut lengths = [0; 11];
+        for cycle in c.simple_cycles(None) {
+            lengths[cycle.len()] += 1;
+        }
+        assert_eq!(lengths, [0, 0, 0, 0, 0, 12, 10, 0, 15, 20, 0]);
+        assert_eq!(c.simple_cycles(Some(4)).count(), 0);
+        assert_eq!(c.simple_cycles(Some(6)).count(), 22);
+        assert_eq!(c.simple_cycles(Some(9)).count(), 57);
+
+        let capacity: usize = 11;
+        let mut rng = SplitMix64::new(5);
+        for p in [0.2, 0.3, 0.4] {
+            let c = generators::gnp(capacity as u32, p, &mut rng);
+            let mut all: Vec<Vec<u32>> = c.simple_cycles(None).collect();
+            all.sort();
+            for k in 3..capacity as u32 {
+                let mut cycles: Vec<Vec<u32>> = c.simple_cycles(Some(k)).collect();
+                cycles.sort();
+                let tmp: Vec<Vec<u32>> = all
+                    .iter()
+                    .filter(|cycle| cycle.len() as u32 <= k)
+                    .cloned()
+                    .collect();
+                assert_eq!(cycles, tmp);
+            }
+        }
+    }
+
+    fn check_trail(c: &BitGraph, trail: &[u32]) {
+        assert_eq!(trail.len() as u64, c.size() + 1);
+        let mut d = c.clone();
+        for pair in trail.windows(2) {
+            assert!(d.neighbors(pair[0]).any(|w| w == pair[1]));
+            d.remove_edge(pair[0], pair[1]);
+        }
+        assert_eq!(d.size(), 0);
+    }
+
+    #[test]
+    fn eulerian() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.eulerian_circuit(), Some(vec![]));
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        assert_eq!(c.eulerian_circuit(), None);
+        assert_eq!(c.eulerian_trail(), Some(vec![0, 1, 2, 3, 4]));
+        c.add_edge(4, 0);
+        c.add_edge(0, 2);
+        c.add_edge(2, 5);
+        c.add_edge(5, 0);
+        let circuit = c.eulerian_circuit().unwrap();
+        assert_eq!(circuit.first(), circuit.last());
+        check_trail(&c, &circuit);
+        c.add_edge(1, 3);
+        assert_eq!(c.eulerian_circuit(), None);
+        let trail = c.eulerian_trail().unwrap();
+        assert_eq!((trail[0], *trail.last().unwrap()), (1, 3));
+        check_trail(&c, &trail);
+
+        // two components
+        c.add_edge(7, 8);
+        assert_eq!(c.eulerian_trail(), None);
+        assert!(BitGraph::complete(5).eulerian_circuit().is_some());
+        assert!(BitGraph::complete(4).eulerian_trail().is_none());
+    }
+
+    fn check_hamiltonian(c: &BitGraph, cycle: &[u32]) {
+        assert_eq!(cycle.len() as u32, c.order());
+        let mut seen = hibitset::BitSet::new();
+        for (i, &v) in cycle.iter().enumerate() {
+            assert!(!seen.add(v));
+            let w = cycle[(i + 1) % cycle.len()];
+            assert!(c.neighbors(v).any(|x| x == w));
+        }
+    }
+
+    #[test]
+    fn hamiltonian_cycle() {
+        let capacity: usize = 100;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.hamiltonian_cycle(), None);
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        assert_eq!(c.hamiltonian_cycle(), None);
+        c.add_edge(4, 0);
+        c.add_edge(1, 3);
+        check_hamiltonian(&c, &c.hamiltonian_cycle().unwrap());
+        check_hamiltonian(
+            &BitGraph::complete(8),
+            &BitGraph::complete(8).hamiltonian_cycle().unwrap(),
+        );
+
+        // the dynamic program at its limit: a 4x6 grid is hamiltonian, a 13-cycle and a 12-cycle
+        // sharing vertex 0 are not, as every cycle through 0 stays in one of them
+        let g = generators::grid(4, 6, false);
+        assert_eq!(g.order() as usize, HELD_KARP_LIMIT);
+        check_hamiltonian(&g, &g.hamiltonian_cycle().unwrap());
+        let mut g = generators::cycle(13);
+        g.grow_to(24);
+        for v in 13..24 {
+            g.add_edge(v, if v == 23 { 0 } else { v + 1 });
+        }
+        g.add_edge(0, 13);
+        assert_eq!(g.order() as usize, HELD_KARP_LIMIT);
+        assert_eq!(g.hamiltonian_cycle(), None);
+
+        // petersen graph is not hamiltonian
+        let mut p = BitGraph::with_capacity(10);
+        for i in 0..5 {
+            p.add_edge(i, (i + 1) % 5);
+            p.add_edge(i, i + 5);
+            p.add_edge(i + 5, (i + 2) % 5 + 5);
+        }
+        assert_eq!(p.hamiltonian_cycle(), None);
+
+        // grids with an even number of vertices are hamiltonian, odd ones are not
+        for &(rows, cols) in [(4u32, 5u32), (6, 6), (5, 5), (7, 9)].iter() {
+            let mut g = BitGraph::with_capacity(capacity as u32);
+            for i in 0..rows {
+                for j in 0..cols {
+                    if j + 1 < cols {
+                        g.add_edge(i * cols + j, i * cols + j + 1);
+                    }
+                    if i + 1 < rows {
+                        g.add_edge(i * cols + j, (i + 1) * cols + j);
+                    }
+                }
+            }
+            match g.hamiltonian_cycle() {
+                Some(cycle) => {
+                    assert_eq!(rows * cols % 2, 0);
+                    check_hamiltonian(&g, &cycle);
+                }
+                None => assert_eq!(rows * cols % 2, 1),
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Differences between graphs as lists of edits
+
+use crate::BitGraph;
+use alloc::vec::Vec;
+use hibitset::{BitSetAnd, BitSetLike, BitSetNot};
+
+/// Edits that turn one graph into another of the same capacity, returned by `BitGraph::diff`.
+/// Edges are listed as `(u, v)` with `u < v`, in increasing order
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct GraphDiff {
+    /// Edges that are only in the new graph
+    pub added_edges: Vec<(u32, u32)>,
+    /// Edges that are only in the old graph
+    pub removed_edges: Vec<(u32, u32)>,
+    /// Vertices that are isolated in the old graph only
+    pub added_vertices: Vec<u32>,
+    /// Vertices that are isolated in the new graph only
+    pub removed_vertices: Vec<u32>,
+}
+
+impl GraphDiff {
+    /// Returns `true` if the graphs are equal
+    pub fn is_empty(&self) -> bool {
+        self.added_edges.is_empty() && self.removed_edges.is_empty()
+    }
+
+    /// Adds the added edges to `graph` and removes the removed ones, which is one mutation for
+    /// the journal. The vertex lists follow from the edges and are not needed for this
+    pub fn apply(&self, graph: &mut BitGraph) {
+        // check everything before the first edit, so a bad diff leaves the graph untouched
+        for &(u, v) in self.removed_edges.iter() {
+            graph.check_bounds(u);
+            graph.check_bounds(v);
+        }
+        graph.add_edges(&self.added_edges);
+        for &(u, v) in self.removed_edges.iter() {
+            graph.unlink(u, v);
+        }
+    }
+}
+
+impl BitGraph {
+    /// Returns the edits that turn `self` into `other`, which has the same capacity
+    pub fn diff(&self, other: &BitGraph) -> GraphDiff {
+        self.check_same_capacity(other);
+        let mut diff = GraphDiff::default();
+        for u in 0..self.m_data.len() as u32 {
+            let (old, new) = (self.m_degrees[u as usize], other.m_degrees[u as usize]);
+            if old == 0 && new > 0 {
+                diff.added_vertices.push(u);
+            } else if old > 0 && new == 0 {
+                diff.removed_vertices.push(u);
+            }
+            if old > 0 {
+                diff.removed_edges.extend(
+                    BitSetAnd(self.row(u), BitSetNot(other.row(u)))
+                        .iter()
+                        .filter(|&v| v > u)
+                        .map(|v| (u, v)),
+                );
+            }
+            if new > 0 {
+                diff.added_edges.extend(
+                    BitSetAnd(other.row(u), BitSetNot(self.row(u)))
+                        .iter()
+                        .filter(|&v| v > u)
+                        .map(|v| (u, v)),
+                );
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::{BitGraph, GraphDiff};
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[test]
+    fn diff() {
+        let capacity: usize = 6;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edges(&[(0, 1), (1, 2), (2, 3)]);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        d.add_edges(&[(1, 2), (2, 3), (3, 4)]);
+        let diff = c.diff(&d);
+        assert_eq!(diff.added_edges, vec![(3, 4)]);
+        assert_eq!(diff.removed_edges, vec![(0, 1)]);
+        assert_eq!(diff.added_vertices, vec![4]);
+        assert_eq!(diff.removed_vertices, vec![0]);
+        assert!(!diff.is_empty());
+        assert!(c.diff(&c).is_empty());
+        diff.apply(&mut c);
+        assert_eq!(c, d);
+
+        let mut rng = SplitMix64::new(64);
+        let a = generators::gnp(80, 0.1, &mut rng);
+        let b = generators::gnp(80, 0.1, &mut rng);
+        let diff = a.diff(&b);
+        assert_eq!(
+            (diff.added_edges.len() + diff.removed_edges.len()) as u64,
+            a.edit_distance_edges(&b)
+        );
+        let mut e = a.clone();
+        e.enable_invariant_cache();
+        e.enable_degree_buckets();
+        diff.apply(&mut e);
+        assert_eq!(e, b);
+        assert_eq!(e.triangle_count(), b.triangle_count());
+        b.diff(&a).apply(&mut e);
+        assert_eq!(e, a);
+    }
+
+    #[test]
+    fn apply_out_of_bounds() {
+        let capacity: usize = 4;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.enable_journal();
+        c.add_edge(0, 1);
+        let diff = GraphDiff {
+            added_edges: vec![(1, 2)],
+            removed_edges: vec![(0, 1), (2, 4)],
+            ..GraphDiff::default()
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| diff.apply(&mut c)));
+        assert!(result.is_err());
+        assert_eq!(c.size(), 1);
+        assert_eq!(c.degree(2), 0);
+        assert_eq!(c.undo(2), 1);
+        assert_eq!(c.size(), 0);
+    }
+}
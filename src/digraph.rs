@@ -0,0 +1,475 @@
+//! Directed graphs with the same bitset rows
+
+use crate::{row_words, BitGraph, UndirectedGraph};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use hibitset::{BitIter, BitSetLike, BitSetOr};
+
+/// A directed graph without self-loops or parallel arcs, the directed sibling of `BitGraph`.
+/// Every vertex stores a row of out-neighbors and one of in-neighbors, so arcs are added and
+/// removed in constant time and both directions can be iterated
+#[derive(Debug, Clone, Default)]
+pub struct BitDiGraph {
+    m_out: Vec<hibitset::BitSet>,
+    m_in: Vec<hibitset::BitSet>,
+    m_out_degrees: Vec<u32>,
+    m_in_degrees: Vec<u32>,
+    m_size: u64,
+}
+
+/// Digraphs are equal if they have the same capacity and the same arcs
+impl PartialEq for BitDiGraph {
+    fn eq(&self, other: &BitDiGraph) -> bool {
+        self.m_out.len() == other.m_out.len()
+            && self.m_out_degrees == other.m_out_degrees
+            && self
+                .m_out
+                .iter()
+                .zip(other.m_out.iter())
+                .all(|(a, b)| row_words(a) == row_words(b))
+    }
+}
+
+impl Eq for BitDiGraph {}
+
+impl BitDiGraph {
+    /// Creates a new BitDiGraph with `capacity` vertices and no arcs
+    pub fn with_capacity(capacity: u32) -> BitDiGraph {
+        BitGraph::check_capacity(capacity);
+        // rows grow up to their largest neighbor as arcs are added
+        BitDiGraph {
+            m_out: (0..capacity).map(|_| hibitset::BitSet::new()).collect(),
+            m_in: (0..capacity).map(|_| hibitset::BitSet::new()).collect(),
+            m_out_degrees: vec![0; capacity as usize],
+            m_in_degrees: vec![0; capacity as usize],
+            m_size: 0,
+        }
+    }
+
+    /// Number of vertices the digraph can hold
+    pub fn capacity(&self) -> u32 {
+        self.m_out.len() as u32
+    }
+
+    /// Number of arcs
+    pub fn size(&self) -> u64 {
+        self.m_size
+    }
+
+    /// Adds the arc from `u` to `v`. If the arc already exists, the digraph is not updated
+    pub fn add_arc(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if u == v {
+            panic!("Arc needs two distinct endpoints, given: {} {}", u, v)
+        }
+        if !self.m_out[u as usize].add(v) {
+            self.m_in[v as usize].add(u);
+            self.m_out_degrees[u as usize] += 1;
+            self.m_in_degrees[v as usize] += 1;
+            self.m_size += 1;
+        }
+    }
+
+    /// Removes the arc from `u` to `v`. If the arc is not present the digraph is not updated
+    pub fn remove_arc(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if self.m_out[u as usize].remove(v) {
+            self.m_in[v as usize].remove(u);
+            self.m_out_degrees[u as usize] -= 1;
+            self.m_in_degrees[v as usize] -= 1;
+            self.m_size -= 1;
+        }
+    }
+
+    /// Returns `true` if there is an arc from `u` to `v`
+    pub fn has_arc(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.m_out[u as usize].contains(v)
+    }
+
+    /// Returns an iterator over the heads of the arcs leaving `v`
+    pub fn out_neighbors(&self, v: u32) -> BitIter<&hibitset::BitSet> {
+        self.check_bounds(v);
+        (&self.m_out[v as usize]).iter()
+    }
+
+    /// Returns an iterator over the tails of the arcs entering `v`
+    pub fn in_neighbors(&self, v: u32) -> BitIter<&hibitset::BitSet> {
+        self.check_bounds(v);
+        (&self.m_in[v as usize]).iter()
+    }
+
+    /// Number of arcs leaving `v`
+    pub fn out_degree(&self, v: u32) -> u32 {
+        self.check_bounds(v);
+        self.m_out_degrees[v as usize]
+    }
+
+    /// Number of arcs entering `v`
+    pub fn in_degree(&self, v: u32) -> u32 {
+        self.check_bounds(v);
+        self.m_in_degrees[v as usize]
+    }
+
+    /// Returns an iterator over all arcs `(u, v)`, ordered by `u` and then `v`
+    pub fn arcs(&self) -> Arcs<'_> {
+        Arcs {
+            m_graph: self,
+            m_vertex: 0,
+            m_heads: None,
+        }
+    }
+
+    /// Returns a copy with every arc reversed
+    pub fn reversed(&self) -> BitDiGraph {
+        BitDiGraph {
+            m_out: self.m_in.clone(),
+            m_in: self.m_out.clone(),
+            m_out_degrees: self.m_in_degrees.clone(),
+            m_in_degrees: self.m_out_degrees.clone(),
+            m_size: self.m_size,
+        }
+    }
+
+    /// Returns the undirected graph with an edge for every pair of vertices joined by an arc
+    pub fn underlying(&self) -> BitGraph {
+        BitGraph::from_rows(
+            self.m_out
+                .iter()
+                .zip(self.m_in.iter())
+                .map(|(out, into)| {
+                    let mut row = out.clone();
+                    row |= into;
+                    row
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the underlying undirected graph as a view, the neighbors of a vertex are the OR of
+    /// its two rows. Unlike `underlying` nothing is copied
+    pub fn underlying_view(&self) -> UnderlyingGraph<'_> {
+        UnderlyingGraph { m_graph: self }
+    }
+
+    /// Returns a `DiDfsIterator` following the arcs from vertex `v`
+    pub fn dfs(&self, v: u32) -> DiDfsIterator<'_> {
+        self.check_bounds(v);
+        let mut visited = hibitset::BitSet::with_capacity(self.capacity());
+        visited.add(v);
+        DiDfsIterator {
+            m_graph: self,
+            m_visited: visited,
+            m_stack: vec![v],
+        }
+    }
+
+    /// Returns a `DiBfsIterator` following the arcs from vertex `v`
+    pub fn bfs(&self, v: u32) -> DiBfsIterator<'_> {
+        self.check_bounds(v);
+        let mut visited = hibitset::BitSet::with_capacity(self.capacity());
+        visited.add(v);
+        DiBfsIterator {
+            m_graph: self,
+            m_visited: visited,
+            m_queue: vec![v].into(),
+        }
+    }
+
+    /// Returns the strongly connected components with Tarjan's algorithm, every vertex is in
+    /// exactly one of them. A component comes before all components it has an arc to, so the
+    /// list is in reverse topological order of the condensation
+    pub fn strongly_connected_components(&self) -> Vec<Vec<u32>> {
+        let n = self.capacity();
+        let mut index = vec![u32::MAX; n as usize];
+        let mut low = vec![0u32; n as usize];
+        let mut on_stack = hibitset::BitSet::with_capacity(n);
+        let mut stack: Vec<u32> = Vec::new();
+        let mut components: Vec<Vec<u32>> = Vec::new();
+        let mut next = 0;
+        let mut calls: Vec<(u32, BitIter<&hibitset::BitSet>)> = Vec::new();
+        for s in 0..n {
+            if index[s as usize] != u32::MAX {
+                continue;
+            }
+            index[s as usize] = next;
+            low[s as usize] = next;
+            next += 1;
+            stack.push(s);
+            on_stack.add(s);
+            calls.push((s, (&self.m_out[s as usize]).iter()));
+            while let Some((v, heads)) = calls.last_mut() {
+                let v = *v;
+                if let Some(w) = heads.next() {
+                    if index[w as usize] == u32::MAX {
+                        index[w as usize] = next;
+                        low[w as usize] = next;
+                        next += 1;
+                        stack.push(w);
+                        on_stack.add(w);
+                        calls.push((w, (&self.m_out[w as usize]).iter()));
+                    } else if on_stack.contains(w) {
+                        low[v as usize] = low[v as usize].min(index[w as usize]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent as usize] = low[parent as usize].min(low[v as usize]);
+                }
+                if low[v as usize] == index[v as usize] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack.remove(w);
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    #[inline]
+    fn check_bounds(&self, idx: u32) {
+        if idx >= self.m_out.len() as u32 {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                idx,
+                self.m_out.len()
+            )
+        }
+    }
+}
+
+/// Iterator over the arcs of a `BitDiGraph`, created by `BitDiGraph::arcs`
+pub struct Arcs<'a> {
+    m_graph: &'a BitDiGraph,
+    m_vertex: u32,
+    m_heads: Option<BitIter<&'a hibitset::BitSet>>,
+}
+
+impl<'a> Iterator for Arcs<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.m_heads.as_mut().and_then(|heads| heads.next()) {
+                return Some((self.m_vertex - 1, v));
+            }
+            if self.m_vertex >= self.m_graph.capacity() {
+                return None;
+            }
+            let u = self.m_vertex;
+            self.m_vertex += 1;
+            self.m_heads = Some((&self.m_graph.m_out[u as usize]).iter());
+        }
+    }
+}
+
+impl<'a> FusedIterator for Arcs<'a> {}
+
+/// Iterator that performs a depth first search along the arcs of a `BitDiGraph`, yielding every
+/// vertex reachable from the start once. Vertices are marked when they are pushed, as in
+/// `DfsIterator`
+pub struct DiDfsIterator<'a> {
+    m_graph: &'a BitDiGraph,
+    m_visited: hibitset::BitSet,
+    m_stack: Vec<u32>,
+}
+
+impl<'a> Iterator for DiDfsIterator<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_stack.pop()?;
+        for w in (&self.m_graph.m_out[v as usize]).iter() {
+            if !self.m_visited.add(w) {
+                self.m_stack.push(w);
+            }
+        }
+        Some(v)
+    }
+}
+
+impl<'a> FusedIterator for DiDfsIterator<'a> {}
+
+/// Iterator that performs a breadth first search along the arcs of a `BitDiGraph`, yielding
+/// every vertex reachable from the start once in order of non-decreasing distance
+pub struct DiBfsIterator<'a> {
+    m_graph: &'a BitDiGraph,
+    m_visited: hibitset::BitSet,
+    m_queue: VecDeque<u32>,
+}
+
+impl<'a> Iterator for DiBfsIterator<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_queue.pop_front()?;
+        for w in (&self.m_graph.m_out[v as usize]).iter() {
+            if !self.m_visited.add(w) {
+                self.m_queue.push_back(w);
+            }
+        }
+        Some(v)
+    }
+}
+
+impl<'a> FusedIterator for DiBfsIterator<'a> {}
+
+/// The underlying undirected graph of a `BitDiGraph`, created by `BitDiGraph::underlying_view`
+#[derive(Debug, Clone, Copy)]
+pub struct UnderlyingGraph<'a> {
+    m_graph: &'a BitDiGraph,
+}
+
+impl<'g> UndirectedGraph for UnderlyingGraph<'g> {
+    type Neighbors<'a>
+        = BitIter<BitSetOr<&'a hibitset::BitSet, &'a hibitset::BitSet>>
+    where
+        Self: 'a;
+
+    fn capacity(&self) -> u32 {
+        self.m_graph.capacity()
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        self.m_graph.check_bounds(v);
+        BitSetOr(
+            &self.m_graph.m_out[v as usize],
+            &self.m_graph.m_in[v as usize],
+        )
+        .iter()
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.m_graph.has_arc(u, v) || self.m_graph.has_arc(v, u)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{algorithms, BitDiGraph, UndirectedGraph};
+
+    #[test]
+    fn rows_grow_with_their_arcs() {
+        let capacity: usize = 100_000;
+        let mut c = BitDiGraph::with_capacity(capacity as u32);
+        for v in 1..capacity as u32 {
+            c.add_arc(v, 0);
+        }
+        assert_eq!(c.m_in[0].layer0_as_slice().len(), capacity / 64 + 1);
+        assert!((1..capacity)
+            .all(|v| c.m_out[v].layer0_as_slice().len() == 1
+                && c.m_in[v].layer0_as_slice().is_empty()));
+    }
+
+    #[test]
+    fn arcs() {
+        let capacity: usize = 5;
+        let mut c = BitDiGraph::with_capacity(capacity as u32);
+        c.add_arc(0, 1);
+        c.add_arc(1, 0);
+        c.add_arc(1, 2);
+        c.add_arc(1, 2);
+        c.add_arc(3, 1);
+        assert_eq!(c.size(), 4);
+        assert_eq!((c.out_degree(1), c.in_degree(1)), (2, 2));
+        assert!(c.has_arc(1, 2) && !c.has_arc(2, 1));
+        let tmp: Vec<u32> = c.in_neighbors(1).collect();
+        assert_eq!(tmp, vec![0, 3]);
+        let tmp: Vec<(u32, u32)> = c.arcs().collect();
+        assert_eq!(tmp, vec![(0, 1), (1, 0), (1, 2), (3, 1)]);
+        let tmp: Vec<(u32, u32)> = c.reversed().arcs().collect();
+        assert_eq!(tmp, vec![(0, 1), (1, 0), (1, 3), (2, 1)]);
+        let tmp: Vec<(u32, u32)> = c.underlying().edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (1, 2), (1, 3)]);
+        let view = c.underlying_view();
+        let tmp: Vec<u32> = view.neighbors(1).collect();
+        assert_eq!(tmp, vec![0, 2, 3]);
+        assert_eq!(view.size(), 3);
+        assert!(view.has_edge(2, 1) && !view.has_edge(0, 2));
+        assert_eq!(
+            algorithms::connected_components(&view),
+            vec![vec![0, 1, 2, 3]]
+        );
+
+        let mut tmp: Vec<u32> = c.dfs(3).collect();
+        tmp.sort_unstable();
+        assert_eq!(tmp, vec![0, 1, 2, 3]);
+        let tmp: Vec<u32> = c.bfs(0).collect();
+        assert_eq!(tmp, vec![0, 1, 2]);
+        assert_eq!(c.dfs(2).count(), 1);
+
+        c.remove_arc(1, 0);
+        c.remove_arc(1, 0);
+        assert_eq!(c.size(), 3);
+        assert_eq!(c.in_degree(0), 0);
+        let mut d = BitDiGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(3, 1), (1, 2), (0, 1)].iter() {
+            d.add_arc(u, v);
+        }
+        assert_eq!(c, d);
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let capacity: usize = 8;
+        let mut c = BitDiGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (5, 6)].iter() {
+            c.add_arc(u, v);
+        }
+        let components = c.strongly_connected_components();
+        assert_eq!(
+            components,
+            vec![vec![3, 4], vec![0, 1, 2], vec![6], vec![5], vec![7]]
+        );
+
+        // same components as mutual reachability, in reverse topological order
+        let mut rng = SplitMix64::new(70);
+        for _ in 0..20 {
+            let n = 30;
+            let mut c = BitDiGraph::with_capacity(n);
+            for _ in 0..45 {
+                let u = rng.below(n as u64) as u32;
+                let v = rng.below(n as u64) as u32;
+                if u != v {
+                    c.add_arc(u, v);
+                }
+            }
+            let reach: Vec<hibitset::BitSet> = (0..n).map(|v| c.dfs(v).collect()).collect();
+            let components = c.strongly_connected_components();
+            assert_eq!(
+                components.iter().map(|c| c.len()).sum::<usize>(),
+                n as usize
+            );
+            let mut position = vec![0; n as usize];
+            for (i, component) in components.iter().enumerate() {
+                for &v in component.iter() {
+                    position[v as usize] = i;
+                }
+            }
+            for u in 0..n {
+                for v in 0..n {
+                    let mutual = reach[u as usize].contains(v) && reach[v as usize].contains(u);
+                    assert_eq!(mutual, position[u as usize] == position[v as usize]);
+                }
+            }
+            for (u, v) in c.arcs() {
+                assert!(position[u as usize] >= position[v as usize]);
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! C interface to `BitGraph`, declared in `include/hibitgraph.h`
+//!
+//! Graphs and neighbor iterators are opaque pointers owned by the caller, who releases them
+//! with `bitgraph_free` and `bitgraph_neighbors_free`. Nothing panics across the boundary:
+//! invalid vertices are rejected with a `false` result or a null pointer
+
+use crate::BitGraph;
+
+/// Iterator over the neighbors of a vertex, created by `bitgraph_neighbors_iter`. It keeps no
+/// borrow of the graph, every call resumes the scan of the row after the last neighbor
+pub struct BitGraphNeighbors {
+    m_graph: *const BitGraph,
+    m_vertex: u32,
+    m_next: usize,
+}
+
+fn valid_edge(graph: &BitGraph, u: u32, v: u32) -> bool {
+    u != v && u < graph.capacity() && v < graph.capacity()
+}
+
+/// Creates a graph with `capacity` vertices and no edges, null if `capacity` is above
+/// `MAX_CAPACITY`
+#[no_mangle]
+pub extern "C" fn bitgraph_new(capacity: u32) -> *mut BitGraph {
+    if capacity as usize > crate::MAX_CAPACITY {
+        return core::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(BitGraph::with_capacity(capacity)))
+}
+
+/// Releases a graph created by `bitgraph_new` or `bitgraph_clone`, null is ignored
+///
+/// # Safety
+/// `graph` must be null or a graph of this interface that was not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_free(graph: *mut BitGraph) {
+    if !graph.is_null() {
+        drop(Box::from_raw(graph));
+    }
+}
+
+/// Returns a copy of `graph` that shares its rows until either is modified
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_clone(graph: *const BitGraph) -> *mut BitGraph {
+    Box::into_raw(Box::new((*graph).clone()))
+}
+
+/// Adds the edge `(u, v)`, `false` if an endpoint is out of bounds or `u == v`
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_add_edge(graph: *mut BitGraph, u: u32, v: u32) -> bool {
+    let graph = &mut *graph;
+    if !valid_edge(graph, u, v) {
+        return false;
+    }
+    graph.add_edge(u, v);
+    true
+}
+
+/// Removes the edge `(u, v)` if present, `false` if an endpoint is out of bounds or `u == v`
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_remove_edge(graph: *mut BitGraph, u: u32, v: u32) -> bool {
+    let graph = &mut *graph;
+    if !valid_edge(graph, u, v) {
+        return false;
+    }
+    graph.remove_edge(u, v);
+    true
+}
+
+/// Returns `true` if `u` and `v` are adjacent, `false` for vertices out of bounds
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_has_edge(graph: *const BitGraph, u: u32, v: u32) -> bool {
+    let graph = &*graph;
+    valid_edge(graph, u, v) && graph.neighbors_set(u).contains(v)
+}
+
+/// Contracts the edge `(target, source)` into `target`, `false` if the edge does not exist
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_contract_edge(
+    graph: *mut BitGraph,
+    target: u32,
+    source: u32,
+) -> bool {
+    let graph = &mut *graph;
+    if !valid_edge(graph, target, source) || !graph.neighbors_set(target).contains(source) {
+        return false;
+    }
+    graph.contract_edge(target, source);
+    true
+}
+
+/// Removes all edges of `v`, `false` if `v` is out of bounds
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_clear_vertex(graph: *mut BitGraph, v: u32) -> bool {
+    let graph = &mut *graph;
+    if v >= graph.capacity() {
+        return false;
+    }
+    graph.clear_vertex(v);
+    true
+}
+
+/// Number of vertex slots of `graph`
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_capacity(graph: *const BitGraph) -> u32 {
+    (*graph).capacity()
+}
+
+/// Number of vertices with at least one edge
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_order(graph: *const BitGraph) -> u32 {
+    (*graph).order()
+}
+
+/// Number of edges
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_size(graph: *const BitGraph) -> u64 {
+    (*graph).size()
+}
+
+/// Number of neighbors of `v`, `0` if `v` is out of bounds
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_degree(graph: *const BitGraph, v: u32) -> u32 {
+    let graph = &*graph;
+    if v >= graph.capacity() {
+        return 0;
+    }
+    graph.degree(v)
+}
+
+/// Returns an iterator over the neighbors of `v` in increasing order, null if `v` is out of
+/// bounds. The graph must outlive the iterator. Edges changed while iterating are reported
+/// if they lie after the last reported neighbor
+///
+/// # Safety
+/// `graph` must be a valid graph of this interface
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_neighbors_iter(
+    graph: *const BitGraph,
+    v: u32,
+) -> *mut BitGraphNeighbors {
+    if v >= (*graph).capacity() {
+        return core::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(BitGraphNeighbors {
+        m_graph: graph,
+        m_vertex: v,
+        m_next: 0,
+    }))
+}
+
+/// Writes the next neighbor to `out` and returns `true`, or returns `false` once all
+/// neighbors were reported
+///
+/// # Safety
+/// `iter` must be a valid iterator of this interface whose graph was not freed, and `out`
+/// must be writable
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_neighbors_next(
+    iter: *mut BitGraphNeighbors,
+    out: *mut u32,
+) -> bool {
+    let iter = &mut *iter;
+    let words = (*iter.m_graph)
+        .neighbors_set(iter.m_vertex)
+        .layer0_as_slice();
+    let bits = usize::BITS as usize;
+    while iter.m_next / bits < words.len() {
+        let word = words[iter.m_next / bits] & (!0 << (iter.m_next % bits));
+        if word != 0 {
+            let w = iter.m_next / bits * bits + word.trailing_zeros() as usize;
+            iter.m_next = w + 1;
+            *out = w as u32;
+            return true;
+        }
+        iter.m_next = (iter.m_next / bits + 1) * bits;
+    }
+    false
+}
+
+/// Releases an iterator created by `bitgraph_neighbors_iter`, null is ignored
+///
+/// # Safety
+/// `iter` must be null or an iterator of this interface that was not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn bitgraph_neighbors_free(iter: *mut BitGraphNeighbors) {
+    if !iter.is_null() {
+        drop(Box::from_raw(iter));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ffi::*;
+
+    #[test]
+    fn c_interface() {
+        let capacity: usize = 10;
+        unsafe {
+            assert!(bitgraph_new(u32::MAX).is_null());
+            let c = bitgraph_new(capacity as u32);
+            assert!(bitgraph_add_edge(c, 0, 1));
+            assert!(bitgraph_add_edge(c, 0, 7));
+            assert!(bitgraph_add_edge(c, 7, 3));
+            assert!(!bitgraph_add_edge(c, 0, 10) && !bitgraph_add_edge(c, 2, 2));
+            assert_eq!(bitgraph_capacity(c), 10);
+            assert_eq!(bitgraph_order(c), 4);
+            assert_eq!(bitgraph_size(c), 3);
+            assert_eq!(bitgraph_degree(c, 7), 2);
+            assert_eq!(bitgraph_degree(c, 12), 0);
+            assert!(bitgraph_has_edge(c, 3, 7) && !bitgraph_has_edge(c, 3, 12));
+
+            let d = bitgraph_clone(c);
+            assert!(bitgraph_contract_edge(d, 0, 7));
+            assert!(!bitgraph_contract_edge(d, 0, 7));
+            assert!(bitgraph_has_edge(d, 0, 3));
+
+            assert!(bitgraph_neighbors_iter(c, 10).is_null());
+            let iter = bitgraph_neighbors_iter(c, 0);
+            let mut w = 0;
+            let mut tmp = Vec::new();
+            while bitgraph_neighbors_next(iter, &mut w) {
+                tmp.push(w);
+            }
+            assert_eq!(tmp, vec![1, 7]);
+            bitgraph_neighbors_free(iter);
+
+            // the iterator resumes from the row as it is at every call
+            let iter = bitgraph_neighbors_iter(c, 0);
+            assert!(bitgraph_neighbors_next(iter, &mut w) && w == 1);
+            assert!(bitgraph_add_edge(c, 0, 9) && bitgraph_remove_edge(c, 0, 7));
+            assert!(bitgraph_neighbors_next(iter, &mut w) && w == 9);
+            assert!(!bitgraph_neighbors_next(iter, &mut w));
+            bitgraph_neighbors_free(iter);
+            assert!(bitgraph_add_edge(c, 0, 7) && bitgraph_remove_edge(c, 0, 9));
+
+            assert!(bitgraph_remove_edge(c, 1, 0));
+            assert!(bitgraph_clear_vertex(c, 7) && !bitgraph_clear_vertex(c, 10));
+            assert_eq!(bitgraph_size(c), 0);
+            assert_eq!(bitgraph_size(d), 2);
+            bitgraph_free(c);
+            bitgraph_free(d);
+            bitgraph_free(core::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Edge-filtered views of a borrowed graph
+
+use crate::{BitGraph, UndirectedGraph};
+use core::fmt;
+use core::iter::FusedIterator;
+use hibitset::BitIter;
+
+/// The graph with only the edges `(u, v)` for which a predicate holds, returned by
+/// `BitGraph::filtered`. The predicate is always called with `u < v`, so the view stays
+/// symmetric, and it is evaluated on every visit of an edge instead of once up front.
+/// Neither the graph nor its rows are copied
+#[derive(Clone, Copy)]
+pub struct FilteredGraph<'a, F> {
+    m_graph: &'a BitGraph,
+    m_predicate: F,
+}
+
+impl<'a, F: Fn(u32, u32) -> bool> FilteredGraph<'a, F> {
+    /// The graph whose edges are filtered
+    pub fn graph(&self) -> &'a BitGraph {
+        self.m_graph
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent and the edge passes the predicate
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.m_graph.check_bounds(u);
+        self.m_graph.check_bounds(v);
+        self.m_graph.row(u).contains(v) && self.keeps(u, v)
+    }
+
+    /// Number of edges of `v` that pass the predicate
+    pub fn degree(&self, v: u32) -> u32 {
+        self.neighbors(v).count() as u32
+    }
+
+    /// Number of edges that pass the predicate
+    pub fn size(&self) -> u64 {
+        self.edges().count() as u64
+    }
+
+    /// Returns an iterator over the neighbors of `v` along edges that pass the predicate
+    pub fn neighbors(&self, v: u32) -> FilteredNeighbors<'_, F> {
+        self.m_graph.check_bounds(v);
+        FilteredNeighbors {
+            m_neighbors: self.m_graph.neighbors(v),
+            m_vertex: v,
+            m_predicate: &self.m_predicate,
+        }
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u < v` that pass the predicate,
+    /// ordered by `u` and then `v`
+    pub fn edges(&self) -> FilteredEdges<'_, F> {
+        FilteredEdges {
+            m_edges: self.m_graph.edges(),
+            m_predicate: &self.m_predicate,
+        }
+    }
+
+    /// Returns the filtered graph as a new graph with the same capacity
+    pub fn to_graph(&self) -> BitGraph {
+        let mut graph = self.m_graph.clone();
+        graph.retain_edges(|u, v| (self.m_predicate)(u, v));
+        graph
+    }
+
+    #[inline]
+    fn keeps(&self, u: u32, v: u32) -> bool {
+        (self.m_predicate)(u.min(v), u.max(v))
+    }
+}
+
+impl<'g, F: Fn(u32, u32) -> bool> UndirectedGraph for FilteredGraph<'g, F> {
+    type Neighbors<'a>
+        = FilteredNeighbors<'a, F>
+    where
+        Self: 'a;
+
+    fn capacity(&self) -> u32 {
+        self.m_graph.capacity()
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        FilteredGraph::neighbors(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        FilteredGraph::has_edge(self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        FilteredGraph::size(self)
+    }
+}
+
+impl<'a, F> fmt::Debug for FilteredGraph<'a, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredGraph")
+            .field("m_graph", &self.m_graph)
+            .finish()
+    }
+}
+
+impl BitGraph {
+    /// Returns a view with only the edges `(u, v)`, `u < v`, for which `predicate(u, v)`
+    /// returns `true`. Unlike `retain_edges` the graph is not changed
+    pub fn filtered<F: Fn(u32, u32) -> bool>(&self, predicate: F) -> FilteredGraph<'_, F> {
+        FilteredGraph {
+            m_graph: self,
+            m_predicate: predicate,
+        }
+    }
+}
+
+/// Iterator over the neighbors of a vertex in a `FilteredGraph`, created by
+/// `FilteredGraph::neighbors`
+pub struct FilteredNeighbors<'a, F> {
+    m_neighbors: BitIter<&'a hibitset::BitSet>,
+    m_vertex: u32,
+    m_predicate: &'a F,
+}
+
+impl<'a, F: Fn(u32, u32) -> bool> Iterator for FilteredNeighbors<'a, F> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_vertex;
+        let predicate = self.m_predicate;
+        self.m_neighbors.find(|&w| predicate(v.min(w), v.max(w)))
+    }
+}
+
+impl<'a, F: Fn(u32, u32) -> bool> FusedIterator for FilteredNeighbors<'a, F> {}
+
+/// Iterator over the edges of a `FilteredGraph`, created by `FilteredGraph::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct FilteredEdges<'a, F> {
+    m_edges: crate::Edges<'a>,
+    m_predicate: &'a F,
+}
+
+impl<'a, F: Fn(u32, u32) -> bool> Iterator for FilteredEdges<'a, F> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let predicate = self.m_predicate;
+        self.m_edges.find(|&(u, v)| predicate(u, v))
+    }
+}
+
+impl<'a, F: Fn(u32, u32) -> bool> FusedIterator for FilteredEdges<'a, F> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SplitMix64;
+    use crate::{algorithms, generators, BitGraph, UndirectedGraph};
+
+    #[test]
+    fn graph_minus_a_cut() {
+        // two 4-cliques joined by the cut {(0, 4), (3, 7)}
+        let capacity: usize = 8;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for u in 0..4 {
+            for v in u + 1..4 {
+                c.add_edge(u, v);
+                c.add_edge(u + 4, v + 4);
+            }
+        }
+        c.add_edge(0, 4);
+        c.add_edge(7, 3);
+        let cut = [(0, 4), (3, 7)];
+        let view = c.filtered(|u, v| !cut.contains(&(u, v)));
+        assert_eq!(view.size(), 12);
+        assert_eq!(view.degree(0), 3);
+        assert!(!view.has_edge(7, 3) && view.has_edge(1, 0));
+        let tmp: Vec<u32> = view.neighbors(4).collect();
+        assert_eq!(tmp, vec![5, 6, 7]);
+        assert_eq!(
+            algorithms::connected_components(&view),
+            vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]
+        );
+        assert_eq!(algorithms::find_path(&view, 0, 7), None);
+        assert_eq!(c.size(), 14);
+        assert_eq!(view.to_graph().component_count(), 2);
+    }
+
+    #[test]
+    fn matches_retain_edges() {
+        let capacity: usize = 40;
+        let mut rng = SplitMix64::new(78);
+        let c = generators::gnp(capacity as u32, 0.2, &mut rng);
+        let view = c.filtered(|u, v| (u * 7 + v) % 3 != 0);
+        let mut retained = c.clone();
+        retained.retain_edges(|u, v| (u * 7 + v) % 3 != 0);
+        assert_eq!(view.to_graph(), retained);
+        assert_eq!(
+            view.edges().collect::<Vec<_>>(),
+            retained.edges().collect::<Vec<_>>()
+        );
+        for v in 0..capacity as u32 {
+            assert_eq!(UndirectedGraph::degree(&view, v), retained.degree(v));
+            assert!(view.neighbors(v).eq(retained.neighbors(v)));
+        }
+        assert_eq!(
+            algorithms::distances(&view, 0),
+            algorithms::distances(&retained, 0)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Immutable snapshots that share their rows
+
+use crate::BitGraph;
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+/// An immutable graph, returned by `BitGraph::freeze`. Cloning a snapshot is constant time, and
+/// the snapshots returned by `with_edge`, `without_edge` and `modify` share every row with it
+/// that the change did not touch. All query methods of `BitGraph` are available through `Deref`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FrozenBitGraph {
+    m_graph: Arc<BitGraph>,
+}
+
+impl FrozenBitGraph {
+    /// Returns a mutable copy, which shares its rows with the snapshot until they are written
+    pub fn thaw(&self) -> BitGraph {
+        BitGraph::clone(&self.m_graph)
+    }
+
+    /// Returns a snapshot with the edge from `u` to `v` added
+    pub fn with_edge(&self, u: u32, v: u32) -> FrozenBitGraph {
+        self.modify(|graph| graph.add_edge(u, v))
+    }
+
+    /// Returns a snapshot with the edge from `u` to `v` removed
+    pub fn without_edge(&self, u: u32, v: u32) -> FrozenBitGraph {
+        self.modify(|graph| graph.remove_edge(u, v))
+    }
+
+    /// Returns a snapshot with the changes `f` makes to a mutable copy
+    pub fn modify<F: FnOnce(&mut BitGraph)>(&self, f: F) -> FrozenBitGraph {
+        let mut graph = self.thaw();
+        f(&mut graph);
+        graph.freeze()
+    }
+
+    /// Number of rows this snapshot shares with `other`
+    pub fn shared_rows(&self, other: &FrozenBitGraph) -> u32 {
+        self.m_graph
+            .m_data
+            .iter()
+            .zip(other.m_graph.m_data.iter())
+            .filter(|(a, b)| Arc::ptr_eq(a, b))
+            .count() as u32
+    }
+}
+
+impl Deref for FrozenBitGraph {
+    type Target = BitGraph;
+
+    fn deref(&self) -> &BitGraph {
+        &self.m_graph
+    }
+}
+
+impl From<BitGraph> for FrozenBitGraph {
+    fn from(graph: BitGraph) -> FrozenBitGraph {
+        graph.freeze()
+    }
+}
+
+impl BitGraph {
+    /// Turns the graph into an immutable snapshot. The journal is dropped, the invariant cache
+    /// and the degree buckets are kept
+    pub fn freeze(mut self) -> FrozenBitGraph {
+        self.disable_journal();
+        FrozenBitGraph {
+            m_graph: Arc::new(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{BitGraph, FrozenBitGraph};
+
+    #[test]
+    fn frozen_snapshots() {
+        let capacity: usize = 100;
+        let mut rng = SplitMix64::new(66);
+        let mut c = generators::gnp(capacity as u32, 0.1, &mut rng);
+        c.enable_invariant_cache();
+        let triangles = c.triangle_count();
+        let frozen = c.clone().freeze();
+        assert_eq!(*frozen, c);
+        assert_eq!(frozen.size(), c.size());
+        assert_eq!(frozen.triangle_count(), triangles);
+
+        let (u, v) = (0..capacity as u32)
+            .flat_map(|u| (0..capacity as u32).map(move |v| (u, v)))
+            .find(|&(u, v)| u != v && !c.row(u).contains(v))
+            .unwrap();
+        let next = frozen.with_edge(u, v);
+        assert_eq!(next.size(), c.size() + 1);
+        assert_eq!(frozen.size(), c.size());
+        assert_eq!(next.shared_rows(&frozen), capacity as u32 - 2);
+        assert_eq!(next.without_edge(u, v), frozen);
+
+        // a chain of snapshots with one change each
+        let mut snapshots: Vec<FrozenBitGraph> = vec![frozen.clone()];
+        for _ in 0..50 {
+            let u = rng.below(capacity as u64) as u32;
+            let w = rng.below(capacity as u64) as u32;
+            let last = snapshots.last().unwrap();
+            if u == w {
+                continue;
+            }
+            let next = last.modify(|g| {
+                if g.row(u).contains(w) {
+                    g.contract_edge(u, w)
+                } else {
+                    g.add_edge(u, w)
+                }
+            });
+            assert!(next.shared_rows(last) as usize >= capacity - 2 - last.degree(w) as usize);
+            snapshots.push(next);
+        }
+        assert_eq!(snapshots[0], frozen);
+        let mut thawed = snapshots[0].thaw();
+        thawed.add_edge(u, v);
+        assert_eq!(thawed.freeze(), next);
+        assert_eq!(FrozenBitGraph::from(BitGraph::with_capacity(3)).size(), 0);
+    }
+}
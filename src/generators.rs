@@ -0,0 +1,834 @@
+//! Constructors for standard graph families
+//!
+//! Every generator returns a graph whose capacity equals its number of vertices.
+//! Rows are filled directly instead of going through `add_edge`
+
+use crate::random::RandomSource;
+#[cfg(feature = "std")]
+use crate::ArrayNeighbors;
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub mod named;
+
+/// Adjacency rows under construction, every row starts empty and grows up to its largest
+/// neighbor as edges are linked
+pub(crate) struct Rows {
+    m_rows: Vec<hibitset::BitSet>,
+}
+
+impl Rows {
+    pub(crate) fn new(n: u32) -> Rows {
+        BitGraph::check_capacity(n);
+        Rows {
+            m_rows: (0..n).map(|_| hibitset::BitSet::new()).collect(),
+        }
+    }
+
+    pub(crate) fn link(&mut self, u: u32, v: u32) {
+        self.m_rows[u as usize].add(v);
+        self.m_rows[v as usize].add(u);
+    }
+
+    pub(crate) fn build(self) -> BitGraph {
+        BitGraph::from_rows(self.m_rows)
+    }
+}
+
+/// Path on `n` vertices `0 - 1 - ... - n-1`
+pub fn path(n: u32) -> BitGraph {
+    let mut rows = Rows::new(n);
+    for v in 1..n {
+        rows.link(v - 1, v);
+    }
+    rows.build()
+}
+
+/// Cycle on `n >= 3` vertices `0 - 1 - ... - n-1 - 0`
+pub fn cycle(n: u32) -> BitGraph {
+    assert!(n >= 3, "A cycle needs at least 3 vertices, given: {}", n);
+    let mut rows = Rows::new(n);
+    for v in 0..n {
+        rows.link(v, (v + 1) % n);
+    }
+    rows.build()
+}
+
+/// Star on `n` vertices, the center `0` is adjacent to all other vertices
+pub fn star(n: u32) -> BitGraph {
+    let mut rows = Rows::new(n);
+    for v in 1..n {
+        rows.link(0, v);
+    }
+    rows.build()
+}
+
+/// Wheel on `n >= 4` vertices, the hub `0` is adjacent to the cycle `1 - ... - n-1 - 1`
+pub fn wheel(n: u32) -> BitGraph {
+    assert!(n >= 4, "A wheel needs at least 4 vertices, given: {}", n);
+    let mut rows = Rows::new(n);
+    for v in 1..n {
+        rows.link(0, v);
+        rows.link(v, if v + 1 < n { v + 1 } else { 1 });
+    }
+    rows.build()
+}
+
+/// Grid with `rows * cols` vertices, vertex `r * cols + c` is adjacent to its horizontal and
+/// vertical neighbors, and with `diagonals` also to its diagonal neighbors (king's graph)
+pub fn grid(rows: u32, cols: u32, diagonals: bool) -> BitGraph {
+    lattice(rows, cols, diagonals, false)
+}
+
+/// Like `grid`, but the first and last row and column are adjacent as well
+pub fn torus(rows: u32, cols: u32, diagonals: bool) -> BitGraph {
+    lattice(rows, cols, diagonals, true)
+}
+
+/// Hypercube of dimension `d` on `2^d` vertices, two vertices are adjacent if their
+/// indices differ in exactly one bit
+pub fn hypercube(d: u32) -> BitGraph {
+    assert!(d < 32, "Dimension too large, given: {}", d);
+    let n = 1u32 << d;
+    let mut rows = Rows::new(n);
+    for v in 0..n {
+        for bit in 0..d {
+            let w = v ^ (1 << bit);
+            if v < w {
+                rows.link(v, w);
+            }
+        }
+    }
+    rows.build()
+}
+
+/// Complete bipartite graph `K(a, b)`, the sides are `0..a` and `a..a + b`
+pub fn complete_bipartite(a: u32, b: u32) -> BitGraph {
+    complete_multipartite(&[a, b])
+}
+
+/// Complete multipartite graph whose parts have the given `sizes`. The parts are consecutive
+/// ranges of vertices and every vertex is adjacent to all vertices outside of its own part
+pub fn complete_multipartite(sizes: &[u32]) -> BitGraph {
+    let n = sizes
+        .iter()
+        .try_fold(0u32, |n, &size| n.checked_add(size))
+        .expect("Graph too large");
+    BitGraph::check_capacity(n);
+    let mut rows = Vec::with_capacity(n as usize);
+    let mut start = 0;
+    for &size in sizes {
+        let mut row = hibitset::BitSet::with_capacity(n);
+        for v in (0..start).chain(start + size..n) {
+            row.add(v);
+        }
+        // all vertices of a part share the same row
+        for _ in 1..size {
+            rows.push(row.clone());
+        }
+        if size > 0 {
+            rows.push(row);
+        }
+        start += size;
+    }
+    BitGraph::from_rows(rows)
+}
+
+/// Erdős–Rényi random graph `G(n, p)`, every pair of vertices is adjacent independently with
+/// probability `p`. Sparse graphs take O(n + m) random draws by skipping geometrically
+/// distributed runs of absent edges, their rows still hold words up to their largest neighbor.
+/// Dense graphs are drawn a row word at a time, with `p` rounded to a multiple of 2^-32.
+/// Needs the `std` feature for the logarithm of the skip lengths
+#[cfg(feature = "std")]
+pub fn gnp<R: RandomSource>(n: u32, p: f64, rng: &mut R) -> BitGraph {
+    if p >= 1.0 {
+        return BitGraph::complete(n);
+    }
+    if p > 0.25 {
+        return gnp_dense(n, p, rng);
+    }
+    let mut rows = Rows::new(n);
+    if p <= 0.0 {
+        return rows.build();
+    }
+    // Batagelj and Brandes: walk over the pairs (v, w) with w < v in order
+    let log_q = (1.0 - p).ln();
+    let (mut v, mut w) = (1u64, -1i64);
+    let n = n as u64;
+    while v < n {
+        let skip = ((1.0 - rng.next_f64()).ln() / log_q).floor();
+        w += 1 + skip.min(n as f64 * n as f64) as i64;
+        while w >= v as i64 && v < n {
+            w -= v as i64;
+            v += 1;
+        }
+        if v < n {
+            rows.link(v as u32, w as u32);
+        }
+    }
+    rows.build()
+}
+
+/// Dense `gnp`, draws the words of every row above the diagonal and mirrors them once at the end
+#[cfg(feature = "std")]
+fn gnp_dense<R: RandomSource>(n: u32, p: f64, rng: &mut R) -> BitGraph {
+    BitGraph::check_capacity(n);
+    let n = n as usize;
+    let stride = n.div_ceil(64);
+    let mut words = vec![0u64; n * stride];
+    // every bit of a word is set with probability q / 2^32: going through the binary digits of
+    // q from the lowest set one upwards, a one digit ors and a zero digit ands a random word into
+    // it, which halves the probability and adds a half for every one. p = 1/2 takes one draw
+    let q = (p * 4_294_967_296.0) as u64;
+    let digits = 32 - q.trailing_zeros().min(32);
+    for u in 0..n {
+        let first = u + 1;
+        for k in first / 64..stride {
+            let mut word = (32 - digits..32).fold(0, |word, digit| {
+                if q >> digit & 1 == 1 {
+                    word | rng.next_u64()
+                } else {
+                    word & rng.next_u64()
+                }
+            });
+            // only the pairs (u, v) with u < v < n
+            if k == first / 64 {
+                word &= !0 << (first % 64);
+            }
+            if k == stride - 1 && !n.is_multiple_of(64) {
+                word &= (1 << (n % 64)) - 1;
+            }
+            words[u * stride + k] = word;
+        }
+    }
+    for u in 0..n {
+        let upper = words[u * stride..(u + 1) * stride].to_vec();
+        for v in ArrayNeighbors::new(&upper) {
+            words[v as usize * stride + u / 64] |= 1 << (u % 64);
+        }
+    }
+    BitGraph::from_rows(
+        words
+            .chunks(stride.max(1))
+            .take(n)
+            .map(|row| ArrayNeighbors::new(row).collect())
+            .collect(),
+    )
+}
+
+/// Uniform random graph `G(n, m)` with exactly `m` edges, all graphs on `n` vertices with `m`
+/// edges are equally likely. Pairs are drawn with rejection, and for more than half of all
+/// pairs the missing edges are drawn instead
+pub fn gnm<R: RandomSource>(n: u32, m: u64, rng: &mut R) -> BitGraph {
+    let pairs = n as u64 * (n as u64).saturating_sub(1) / 2;
+    assert!(
+        m <= pairs,
+        "Too many edges, given: {}, Allowed: {}",
+        m,
+        pairs
+    );
+    let complement = m > pairs / 2;
+    let mut c = if complement {
+        BitGraph::complete(n)
+    } else {
+        BitGraph::with_capacity(n)
+    };
+    let mut remaining = if complement { pairs - m } else { m };
+    while remaining > 0 {
+        let u = rng.below(n as u64) as u32;
+        let v = rng.below(n as u64) as u32;
+        if u == v || c.row(u).contains(v) != complement {
+            continue;
+        }
+        if complement {
+            c.remove_edge(u, v);
+        } else {
+            c.add_edge(u, v);
+        }
+        remaining -= 1;
+    }
+    c
+}
+
+/// Random `d`-regular graph on `n` vertices, all of them are equally likely. Stubs are paired
+/// at random and the whole pairing is restarted on any loop or parallel edge, which takes about
+/// e^((d² - 1) / 4) attempts. For `d > (n - 1) / 2` the complement of a random
+/// `(n - 1 - d)`-regular graph is returned instead. `n * d` must be even and `d < n`
+pub fn random_regular<R: RandomSource>(n: u32, d: u32, rng: &mut R) -> BitGraph {
+    assert!(
+        (n as u64 * d as u64).is_multiple_of(2),
+        "n * d must be even, given: {} {}",
+        n,
+        d
+    );
+    assert!(d == 0 || d < n, "Degree too large, given: {}", d);
+    if d > 0 && d > (n - 1) / 2 {
+        let c = random_regular(n, n - 1 - d, rng);
+        let mut rows = Rows::new(n);
+        for u in 0..n {
+            for v in u + 1..n {
+                if !c.row(u).contains(v) {
+                    rows.link(u, v);
+                }
+            }
+        }
+        return rows.build();
+    }
+    let mut stubs: Vec<u32> = (0..n)
+        .flat_map(|v| core::iter::repeat_n(v, d as usize))
+        .collect();
+    loop {
+        if let Some(c) = try_random_regular(n, &mut stubs, rng) {
+            return c;
+        }
+    }
+}
+
+/// One random pairing of the `stubs`, `None` as soon as it produces a loop or a parallel edge
+fn try_random_regular<R: RandomSource>(n: u32, stubs: &mut [u32], rng: &mut R) -> Option<BitGraph> {
+    for i in (1..stubs.len()).rev() {
+        stubs.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    let mut c = BitGraph::with_capacity(n);
+    for pair in stubs.chunks(2) {
+        let (u, v) = (pair[0], pair[1]);
+        if u == v || c.row(u).contains(v) {
+            return None;
+        }
+        c.add_edge(u, v);
+    }
+    Some(c)
+}
+
+/// Barabási–Albert preferential attachment graph on `n` vertices. Starting from `m` isolated
+/// vertices, every new vertex is joined to `m` distinct existing vertices chosen with
+/// probability proportional to their degree. Requires `1 <= m < n`
+pub fn barabasi_albert<R: RandomSource>(n: u32, m: u32, rng: &mut R) -> BitGraph {
+    assert!(m >= 1 && m < n, "Invalid m, given: {}, n: {}", m, n);
+    let mut c = BitGraph::with_capacity(n);
+    // every vertex appears once per incident edge
+    let mut repeated: Vec<u32> = Vec::with_capacity(2 * (n - m) as usize * m as usize);
+    let mut targets: Vec<u32> = (0..m).collect();
+    for source in m..n {
+        for &t in targets.iter() {
+            c.add_edge(source, t);
+            repeated.push(t);
+            repeated.push(source);
+        }
+        targets.clear();
+        while targets.len() < m as usize {
+            let t = repeated[rng.below(repeated.len() as u64) as usize];
+            if !targets.contains(&t) {
+                targets.push(t);
+            }
+        }
+    }
+    c
+}
+
+/// Watts–Strogatz small-world graph. Starts from a ring on `n` vertices where every vertex is
+/// adjacent to its `k` nearest neighbors, then rewires the far endpoint of every lattice edge
+/// with probability `beta` to a uniformly chosen vertex, avoiding loops and parallel edges.
+/// `k` must be even and smaller than `n`
+pub fn watts_strogatz<R: RandomSource>(n: u32, k: u32, beta: f64, rng: &mut R) -> BitGraph {
+    assert!(
+        k.is_multiple_of(2) && (k == 0 || k < n),
+        "Invalid k, given: {}, n: {}",
+        k,
+        n
+    );
+    let mut rows = Rows::new(n);
+    for j in 1..=k / 2 {
+        for u in 0..n {
+            rows.link(u, (u + j) % n);
+        }
+    }
+    let mut c = rows.build();
+    for j in 1..=k / 2 {
+        for u in 0..n {
+            if rng.next_f64() >= beta || c.degree(u) >= n - 1 {
+                continue;
+            }
+            let v = (u + j) % n;
+            let w = loop {
+                let w = rng.below(n as u64) as u32;
+                if w != u && !c.row(u).contains(w) {
+                    break w;
+                }
+            };
+            c.remove_edge(u, v);
+            c.add_edge(u, w);
+        }
+    }
+    c
+}
+
+/// Returns `true` if some simple graph has the degree sequence `degrees` (Erdős–Gallai)
+pub fn is_graphical(degrees: &[u32]) -> bool {
+    let n = degrees.len() as u64;
+    let mut sorted: Vec<u64> = degrees.iter().map(|&d| d as u64).collect();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    if sorted.iter().sum::<u64>() % 2 != 0 || sorted.first().is_some_and(|&d| d >= n) {
+        return false;
+    }
+    let mut prefix = 0;
+    for k in 1..=n {
+        prefix += sorted[k as usize - 1];
+        let tail: u64 = sorted[k as usize..].iter().map(|&d| d.min(k)).sum();
+        if prefix > k * (k - 1) + tail {
+            return false;
+        }
+    }
+    true
+}
+
+/// Builds a graph whose vertex `v` has degree `degrees[v]` with the Havel–Hakimi algorithm,
+/// `None` if the sequence is not graphical
+pub fn from_degree_sequence(degrees: &[u32]) -> Option<BitGraph> {
+    if !is_graphical(degrees) {
+        return None;
+    }
+    let mut rows = Rows::new(degrees.len() as u32);
+    let mut remaining: Vec<(u32, u32)> = degrees
+        .iter()
+        .enumerate()
+        .map(|(v, &d)| (d, v as u32))
+        .collect();
+    loop {
+        remaining.sort_unstable_by(|a, b| b.cmp(a));
+        let (d, v) = match remaining.first() {
+            Some(&(d, v)) if d > 0 => (d, v),
+            _ => break,
+        };
+        remaining[0].0 = 0;
+        for entry in remaining[1..=d as usize].iter_mut() {
+            entry.0 -= 1;
+            rows.link(v, entry.1);
+        }
+    }
+    Some(rows.build())
+}
+
+impl BitGraph {
+    /// Randomizes the graph with `num_swaps` double edge swaps that keep every degree. A swap
+    /// replaces edges `(a, b)` and `(c, d)` by `(a, d)` and `(c, b)` and is rejected if it
+    /// would create a loop or a parallel edge. Gives up after `100 * num_swaps` attempts and
+    /// returns the number of swaps performed
+    pub fn randomize_edges<R: RandomSource>(&mut self, rng: &mut R, num_swaps: u64) -> u64 {
+        let mut edges: Vec<(u32, u32)> = (0..self.m_data.len() as u32)
+            .flat_map(|u| {
+                self.neighbors(u)
+                    .filter(move |&v| u < v)
+                    .map(move |v| (u, v))
+            })
+            .collect();
+        if edges.len() < 2 {
+            return 0;
+        }
+        let mut done = 0;
+        let mut attempts = num_swaps.saturating_mul(100);
+        while done < num_swaps && attempts > 0 {
+            attempts -= 1;
+            let i = rng.below(edges.len() as u64) as usize;
+            let j = rng.below(edges.len() as u64) as usize;
+            let (a, b) = edges[i];
+            // pick one of the two orientations of the second edge
+            let (c, d) = if rng.next_u64() & 1 == 0 {
+                edges[j]
+            } else {
+                (edges[j].1, edges[j].0)
+            };
+            if a == c || a == d || b == c || b == d {
+                continue;
+            }
+            if self.row(a).contains(d) || self.row(c).contains(b) {
+                continue;
+            }
+            self.remove_edge(a, b);
+            self.remove_edge(c, d);
+            self.add_edge(a, d);
+            self.add_edge(c, b);
+            edges[i] = (a, d);
+            edges[j] = (c, b);
+            done += 1;
+        }
+        done
+    }
+}
+
+/// Tree on `sequence.len() + 2` vertices encoded by the Prüfer `sequence`,
+/// see `BitGraph::to_prufer` for the inverse
+pub fn from_prufer(sequence: &[u32]) -> BitGraph {
+    let n = sequence.len() as u32 + 2;
+    let mut rows = Rows::new(n);
+    let mut degree = vec![1u32; n as usize];
+    for &v in sequence {
+        assert!(v < n, "Out of bounds. Given: {}, Allowed: {}", v, n);
+        degree[v as usize] += 1;
+    }
+    // `next` scans forward for leaves, a vertex that becomes a leaf below `next` is taken at once
+    let mut next = (0..n).find(|&v| degree[v as usize] == 1).unwrap();
+    let mut leaf = next;
+    for &v in sequence {
+        rows.link(leaf, v);
+        degree[v as usize] -= 1;
+        if v < next && degree[v as usize] == 1 {
+            leaf = v;
+        } else {
+            next = (next + 1..n).find(|&w| degree[w as usize] == 1).unwrap();
+            leaf = next;
+        }
+    }
+    rows.link(leaf, n - 1);
+    rows.build()
+}
+
+/// Uniformly random labeled tree on `n` vertices, decoded from a random Prüfer sequence
+pub fn random_tree<R: RandomSource>(n: u32, rng: &mut R) -> BitGraph {
+    if n < 2 {
+        return BitGraph::with_capacity(n);
+    }
+    let sequence: Vec<u32> = (0..n - 2).map(|_| rng.below(n as u64) as u32).collect();
+    from_prufer(&sequence)
+}
+
+fn lattice(rows: u32, cols: u32, diagonals: bool, wrap: bool) -> BitGraph {
+    let n = rows.checked_mul(cols).expect("Grid too large");
+    let mut out = Rows::new(n);
+    let mut steps = vec![(0, 1), (1, 0)];
+    if diagonals {
+        steps.push((1, 1));
+        steps.push((1, -1));
+    }
+    for r in 0..rows as i64 {
+        for c in 0..cols as i64 {
+            for &(dr, dc) in steps.iter() {
+                let (mut r2, mut c2) = (r + dr, c + dc);
+                if wrap {
+                    r2 = r2.rem_euclid(rows as i64);
+                    c2 = c2.rem_euclid(cols as i64);
+                } else if r2 >= rows as i64 || c2 < 0 || c2 >= cols as i64 {
+                    continue;
+                }
+                let u = (r * cols as i64 + c) as u32;
+                let v = (r2 * cols as i64 + c2) as u32;
+                // wrapping around a dimension of size 1 or 2 leads back to known vertices
+                if u != v {
+                    out.link(u, v);
+                }
+            }
+        }
+    }
+    out.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::SplitMix64;
+
+    fn degrees(c: &BitGraph) -> Vec<u32> {
+        (0..c.m_degrees.len() as u32).map(|v| c.degree(v)).collect()
+    }
+
+    #[test]
+    fn small_families() {
+        let c = path(5);
+        assert_eq!((c.order(), c.size()), (5, 4));
+        assert_eq!(degrees(&c), vec![1, 2, 2, 2, 1]);
+        assert!(c.is_tree());
+        assert_eq!(path(1).order(), 0);
+        assert_eq!(path(0).size(), 0);
+
+        let c = cycle(6);
+        assert_eq!((c.order(), c.size()), (6, 6));
+        assert_eq!(c.girth(), Some(6));
+        assert_eq!(degrees(&c), vec![2; 6]);
+
+        let c = star(5);
+        assert_eq!(degrees(&c), vec![4, 1, 1, 1, 1]);
+        assert!(c.is_tree());
+
+        let c = wheel(6);
+        assert_eq!((c.order(), c.size()), (6, 10));
+        assert_eq!(degrees(&c), vec![5, 3, 3, 3, 3, 3]);
+        let tmp: Vec<u32> = c.neighbors(5).collect();
+        assert_eq!(tmp, vec![0, 1, 4]);
+        assert_eq!(wheel(4).triangle_count(), 4);
+    }
+
+    #[test]
+    fn grids() {
+        let c = grid(3, 4, false);
+        assert_eq!((c.order(), c.size()), (12, 17));
+        let tmp: Vec<u32> = c.neighbors(5).collect();
+        assert_eq!(tmp, vec![1, 4, 6, 9]);
+        assert_eq!(c.girth(), Some(4));
+        let c = grid(3, 4, true);
+        assert_eq!(c.size(), 17 + 2 * 6);
+        let tmp: Vec<u32> = c.neighbors(4).collect();
+        assert_eq!(tmp, vec![0, 1, 5, 8, 9]);
+        assert_eq!(grid(1, 5, false).size(), 4);
+
+        let c = torus(3, 4, false);
+        assert_eq!(degrees(&c), vec![4; 12]);
+        assert_eq!(c.size(), 24);
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 3, 4, 8]);
+        assert_eq!(degrees(&torus(4, 5, true)), vec![8; 20]);
+        assert_eq!(degrees(&torus(2, 5, false)), vec![3; 10]);
+        assert_eq!(torus(1, 1, true).size(), 0);
+    }
+
+    #[test]
+    fn hypercubes() {
+        assert_eq!(hypercube(0).m_degrees.len(), 1);
+        assert_eq!(hypercube(1).size(), 1);
+        let c = hypercube(4);
+        assert_eq!((c.order(), c.size()), (16, 32));
+        assert_eq!(degrees(&c), vec![4; 16]);
+        let tmp: Vec<u32> = c.neighbors(5).collect();
+        assert_eq!(tmp, vec![1, 4, 7, 13]);
+        assert_eq!(c.girth(), Some(4));
+        assert_eq!(c.triangle_count(), 0);
+    }
+
+    #[test]
+    fn complete_multipartite_graphs() {
+        let c = complete_bipartite(3, 3);
+        assert_eq!((c.order(), c.size()), (6, 9));
+        assert_eq!(c.girth(), Some(4));
+        assert!(!c.is_planar());
+        let tmp: Vec<u32> = c.neighbors(1).collect();
+        assert_eq!(tmp, vec![3, 4, 5]);
+        let c = complete_bipartite(1, 4);
+        assert_eq!(degrees(&c), degrees(&star(5)));
+        assert_eq!(complete_bipartite(0, 3).size(), 0);
+
+        let c = complete_multipartite(&[2, 0, 1, 3]);
+        assert_eq!(degrees(&c), vec![4, 4, 5, 3, 3, 3]);
+        let tmp: Vec<u32> = c.neighbors(4).collect();
+        assert_eq!(tmp, vec![0, 1, 2]);
+        assert_eq!(complete_multipartite(&[1; 5]).size(), 10);
+        assert_eq!(complete_multipartite(&[]).m_degrees.len(), 0);
+    }
+
+    #[test]
+    fn rows_grow_with_their_neighbors() {
+        let capacity: usize = 100_000;
+        let c = star(capacity as u32);
+        assert!(c.memory_usage() < 256 * capacity, "{}", c.memory_usage());
+        // a row spans up to its largest neighbor, not the whole capacity
+        let c = path(capacity as u32 / 10);
+        assert!((0..capacity as u32 / 10).all(|v| c.row_span(v) <= v as u64 + 128));
+    }
+
+    #[test]
+    fn gnp_graphs() {
+        let mut rng = SplitMix64::new(7);
+        assert_eq!(gnp(50, 0.0, &mut rng).size(), 0);
+        assert_eq!(gnp(50, 1.0, &mut rng).size(), 50 * 49 / 2);
+        assert_eq!(gnp(0, 0.5, &mut rng).size(), 0);
+        for &p in [0.01, 0.05, 0.2, 0.5, 0.9].iter() {
+            let mut total = 0;
+            for _ in 0..20 {
+                total += gnp(200, p, &mut rng).size();
+            }
+            let expected = 20.0 * p * (200.0 * 199.0 / 2.0);
+            let deviation = (total as f64 - expected).abs();
+            assert!(deviation < 0.05 * expected + 50.0, "p = {}: {}", p, total);
+        }
+    }
+
+    #[test]
+    fn gnp_dense_rows() {
+        let mut rng = SplitMix64::new(9);
+        for &(n, p) in [(1, 0.5), (64, 0.5), (130, 0.5), (130, 0.7), (130, 0.3)].iter() {
+            let c = gnp(n, p, &mut rng);
+            for u in 0..n {
+                assert!(!c.neighbors_set(u).contains(u));
+                assert!(c
+                    .neighbors(u)
+                    .all(|v| v < n && c.neighbors_set(v).contains(u)));
+            }
+            // rows with a partial last word keep the density
+            let pairs = n as f64 * (n as f64 - 1.0) / 2.0;
+            let deviation = (c.size() as f64 - p * pairs).abs();
+            assert!(deviation < 0.1 * p * pairs + 5.0, "n = {}: {}", n, c.size());
+        }
+
+        // with 64 vertices every row but the last has one word above the diagonal, a word takes
+        // one draw per binary digit of p from its lowest one, 30 for 0.3
+        for &(p, per_word) in [(0.5, 1), (0.75, 2), (0.3, 30)].iter() {
+            let mut source = SplitMix64::new(3);
+            let mut draws = 0;
+            let mut rng = || {
+                draws += 1;
+                source.next_u64()
+            };
+            gnp(64, p, &mut rng);
+            assert_eq!(draws, 63 * per_word, "p = {}", p);
+        }
+    }
+
+    #[test]
+    fn gnm_graphs() {
+        let mut rng = SplitMix64::new(3);
+        for &m in [0, 1, 30, 100, 189, 190].iter() {
+            let c = gnm(20, m, &mut rng);
+            assert_eq!(c.size(), m);
+            assert_eq!(c.m_degrees.len(), 20);
+        }
+        assert_eq!(gnm(1, 0, &mut rng).size(), 0);
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            let c = gnm(3, 1, &mut rng);
+            counts[(0..3).find(|&v| c.degree(v) == 0).unwrap() as usize] += 1;
+        }
+        assert!(counts.iter().all(|&k| k > 850 && k < 1150), "{:?}", counts);
+    }
+
+    #[test]
+    fn random_regular_graphs() {
+        let mut rng = SplitMix64::new(11);
+        for &(n, d) in [(10, 3), (20, 4), (21, 16), (7, 6), (30, 1), (5, 0), (0, 0)].iter() {
+            for _ in 0..10 {
+                let c = random_regular(n, d, &mut rng);
+                assert_eq!(degrees(&c), vec![d; n as usize]);
+            }
+        }
+
+        // the 70 labeled 2-regular graphs on 6 vertices are 60 hexagons and 10 pairs of
+        // triangles, keeping any pair of a vertex stub pairing after a collision favours one
+        let mut triangles = 0;
+        for _ in 0..7000 {
+            if random_regular(6, 2, &mut rng).triangle_count() > 0 {
+                triangles += 1;
+            }
+        }
+        assert!(triangles > 850 && triangles < 1150, "{}", triangles);
+    }
+
+    #[test]
+    fn barabasi_albert_graphs() {
+        let mut rng = SplitMix64::new(5);
+        let c = barabasi_albert(500, 3, &mut rng);
+        assert_eq!(c.size(), 497 * 3);
+        assert_eq!(c.component_count(), 1);
+        assert!((3..500).all(|v| c.degree(v) >= 3));
+        let max = (0..500).map(|v| c.degree(v)).max().unwrap();
+        assert!(max > 30, "{}", max);
+        let c = barabasi_albert(2, 1, &mut rng);
+        assert_eq!(c.size(), 1);
+    }
+
+    #[test]
+    fn watts_strogatz_graphs() {
+        let mut rng = SplitMix64::new(9);
+        let c = watts_strogatz(20, 4, 0.0, &mut rng);
+        assert_eq!(degrees(&c), vec![4; 20]);
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 2, 18, 19]);
+        assert_eq!(c.triangle_count(), 20);
+        for &beta in [0.1, 0.5, 1.0].iter() {
+            let c = watts_strogatz(100, 6, beta, &mut rng);
+            assert_eq!(c.size(), 300);
+            assert!(c.triangle_count() < 300);
+        }
+        assert_eq!(watts_strogatz(5, 4, 1.0, &mut rng).size(), 10);
+        assert_eq!(watts_strogatz(5, 0, 1.0, &mut rng).size(), 0);
+    }
+
+    #[test]
+    fn degree_sequences() {
+        assert!(is_graphical(&[]));
+        assert!(is_graphical(&[0, 0]));
+        assert!(is_graphical(&[3, 3, 3, 3]));
+        assert!(is_graphical(&[1, 2, 2, 1]));
+        assert!(!is_graphical(&[1, 1, 1]));
+        assert!(!is_graphical(&[3, 3, 1, 1]));
+        assert!(!is_graphical(&[4, 1, 1, 1]));
+        assert!(from_degree_sequence(&[3, 3, 1, 1]).is_none());
+
+        let mut rng = SplitMix64::new(2);
+        for _ in 0..50 {
+            let c = gnp(30, 0.2, &mut rng);
+            let sequence = degrees(&c);
+            assert!(is_graphical(&sequence));
+            let realized = from_degree_sequence(&sequence).unwrap();
+            assert_eq!(degrees(&realized), sequence);
+        }
+        let c = from_degree_sequence(&[2, 1, 1, 0]).unwrap();
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 2]);
+        let c = from_degree_sequence(&[]).unwrap();
+        assert_eq!(c.capacity(), 0);
+        assert_eq!(from_degree_sequence(&[0, 0]).unwrap().size(), 0);
+    }
+
+    #[test]
+    fn randomize_edges() {
+        let mut rng = SplitMix64::new(4);
+        let mut c = grid(6, 6, false);
+        let before = degrees(&c);
+        assert_eq!(c.randomize_edges(&mut rng, 200), 200);
+        assert_eq!(degrees(&c), before);
+        assert_eq!(c.size(), 60);
+        let original = grid(6, 6, false);
+        assert!((0..36).any(|v| !c.neighbors(v).eq(original.neighbors(v))));
+
+        let mut c = star(6);
+        assert_eq!(c.randomize_edges(&mut rng, 10), 0);
+        assert_eq!(degrees(&c), vec![5, 1, 1, 1, 1, 1]);
+        let mut c = path(2);
+        assert_eq!(c.randomize_edges(&mut rng, 10), 0);
+    }
+
+    #[test]
+    fn prufer() {
+        let c = from_prufer(&[3, 3, 3, 4]);
+        assert_eq!(degrees(&c), vec![1, 1, 1, 4, 2, 1]);
+        let tmp: Vec<u32> = c.neighbors(4).collect();
+        assert_eq!(tmp, vec![3, 5]);
+        assert_eq!(c.to_prufer(), Some(vec![3, 3, 3, 4]));
+        assert_eq!(from_prufer(&[]).size(), 1);
+        assert_eq!(from_prufer(&[]).to_prufer(), Some(vec![]));
+
+        let mut rng = SplitMix64::new(6);
+        for n in 2..30 {
+            let c = random_tree(n, &mut rng);
+            assert!(c.is_tree());
+            assert_eq!(c.order(), n);
+            assert_eq!(
+                from_prufer(&c.to_prufer().unwrap()).to_prufer(),
+                c.to_prufer()
+            );
+        }
+        assert_eq!(random_tree(1, &mut rng).m_degrees.len(), 1);
+        assert_eq!(cycle(5).to_prufer(), None);
+        assert_eq!(path(1).to_prufer(), None);
+        let mut c = path(4);
+        c.remove_edge(2, 3);
+        assert_eq!(c.to_prufer(), None);
+
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            let c = random_tree(3, &mut rng);
+            counts[(0..3).find(|&v| c.degree(v) == 2).unwrap() as usize] += 1;
+        }
+        assert!(counts.iter().all(|&k| k > 850 && k < 1150), "{:?}", counts);
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_regular_odd() {
+        random_regular(5, 3, &mut SplitMix64::new(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn cycle_too_small() {
+        cycle(2);
+    }
+}
@@ -0,0 +1,140 @@
+//! Well known graphs, mostly used as counterexamples and sanity checks
+
+use super::Rows;
+use crate::BitGraph;
+use alloc::vec::Vec;
+
+fn from_edges(n: u32, edges: &[(u32, u32)]) -> BitGraph {
+    let mut rows = Rows::new(n);
+    for &(u, v) in edges {
+        rows.link(u, v);
+    }
+    rows.build()
+}
+
+/// Petersen graph, the outer cycle `0..5` is joined by spokes `i - i+5` to the
+/// pentagram on `5..10`
+pub fn petersen() -> BitGraph {
+    let mut rows = Rows::new(10);
+    for i in 0..5 {
+        rows.link(i, (i + 1) % 5);
+        rows.link(i, i + 5);
+        rows.link(i + 5, (i + 2) % 5 + 5);
+    }
+    rows.build()
+}
+
+/// Kneser graph `K(n, k)`, its vertices are the `k`-element subsets of `0..n` in lexicographic
+/// order and two subsets are adjacent if they are disjoint. `n` may be at most 64
+pub fn kneser(n: u32, k: u32) -> BitGraph {
+    assert!(n <= 64, "Ground set too large, given: {}", n);
+    let mut subsets: Vec<u64> = Vec::new();
+    if k <= n {
+        collect_subsets(n, k, 0, &mut Vec::new(), &mut subsets);
+    }
+    assert!(
+        subsets.len() <= u32::MAX as usize,
+        "Too many subsets: {}",
+        subsets.len()
+    );
+    let mut rows = Rows::new(subsets.len() as u32);
+    for (u, a) in subsets.iter().enumerate() {
+        for (v, b) in subsets.iter().enumerate().skip(u + 1) {
+            if a & b == 0 {
+                rows.link(u as u32, v as u32);
+            }
+        }
+    }
+    rows.build()
+}
+
+fn collect_subsets(n: u32, k: u32, start: u32, current: &mut Vec<u32>, out: &mut Vec<u64>) {
+    if current.len() as u32 == k {
+        out.push(current.iter().fold(0, |mask, &i| mask | 1 << i));
+        return;
+    }
+    let missing = k - current.len() as u32;
+    for i in start..=n - missing {
+        current.push(i);
+        collect_subsets(n, k, i + 1, current, out);
+        current.pop();
+    }
+}
+
+/// Heawood graph, the cubic graph of girth 6 on 14 vertices
+#[rustfmt::skip]
+pub fn heawood() -> BitGraph {
+    from_edges(
+        14,
+        &[
+            (0, 1), (0, 5), (0, 13), (1, 2), (1, 10), (2, 3), (2, 7), (3, 4), (3, 12), (4, 5),
+            (4, 9), (5, 6), (6, 7), (6, 11), (7, 8), (8, 9), (8, 13), (9, 10), (10, 11), (11, 12),
+            (12, 13),
+        ],
+    )
+}
+
+/// Chvátal graph, the smallest triangle-free 4-regular graph with chromatic number 4
+#[rustfmt::skip]
+pub fn chvatal() -> BitGraph {
+    from_edges(
+        12,
+        &[
+            (0, 1), (0, 4), (0, 6), (0, 9), (1, 2), (1, 5), (1, 7), (2, 3), (2, 6), (2, 8), (3, 4),
+            (3, 7), (3, 9), (4, 5), (4, 8), (5, 10), (5, 11), (6, 10), (6, 11), (7, 8), (7, 11),
+            (8, 10), (9, 10), (9, 11),
+        ],
+    )
+}
+
+/// Grötzsch graph, the smallest triangle-free graph with chromatic number 4
+#[rustfmt::skip]
+pub fn grotzsch() -> BitGraph {
+    from_edges(
+        11,
+        &[
+            (0, 1), (0, 3), (0, 6), (0, 8), (1, 2), (1, 5), (1, 7), (2, 4), (2, 6), (2, 9), (3, 4),
+            (3, 5), (3, 9), (4, 7), (4, 8), (5, 10), (6, 10), (7, 10), (8, 10), (9, 10),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn degrees(c: &BitGraph) -> Vec<u32> {
+        (0..c.m_degrees.len() as u32).map(|v| c.degree(v)).collect()
+    }
+
+    #[test]
+    fn named_graphs() {
+        let c = petersen();
+        assert_eq!(degrees(&c), vec![3; 10]);
+        assert_eq!((c.size(), c.girth()), (15, Some(5)));
+        assert!(!c.is_planar());
+        assert_eq!(c.hamiltonian_cycle(), None);
+
+        let c = kneser(5, 2);
+        assert_eq!(degrees(&c), vec![3; 10]);
+        assert_eq!((c.size(), c.girth()), (15, Some(5)));
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![7, 8, 9]);
+        assert_eq!(kneser(5, 1).size(), 10);
+        assert_eq!(kneser(5, 3).size(), 0);
+        assert_eq!(kneser(6, 0).m_degrees.len(), 1);
+        assert_eq!(kneser(3, 4).m_degrees.len(), 0);
+
+        let c = heawood();
+        assert_eq!(degrees(&c), vec![3; 14]);
+        assert_eq!(c.girth(), Some(6));
+
+        let c = chvatal();
+        assert_eq!(degrees(&c), vec![4; 12]);
+        assert_eq!(c.girth(), Some(4));
+
+        let c = grotzsch();
+        assert_eq!((c.order(), c.size()), (11, 20));
+        assert_eq!(c.triangle_count(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+//! A common interface of the undirected graph types
+
+use crate::{BitGraph, BitGraphView, FrozenBitGraph};
+use hibitset::BitIter;
+
+/// Read access to an undirected graph without self-loops on the vertices `0..capacity()`.
+/// The functions in `algorithms` accept any implementation, so they run on views and on the
+/// underlying graph of a `BitDiGraph` without copying them into a `BitGraph` first
+pub trait UndirectedGraph {
+    /// Iterator over the neighbors of a vertex
+    type Neighbors<'a>: Iterator<Item = u32>
+    where
+        Self: 'a;
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    fn capacity(&self) -> u32;
+
+    /// Returns an iterator over the neighbors of `v`
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_>;
+
+    /// Number of neighbors of `v`
+    fn degree(&self, v: u32) -> u32 {
+        self.neighbors(v).count() as u32
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.neighbors(u).any(|w| w == v)
+    }
+
+    /// Number of edges
+    fn size(&self) -> u64 {
+        (0..self.capacity())
+            .map(|v| self.degree(v) as u64)
+            .sum::<u64>()
+            / 2
+    }
+}
+
+impl UndirectedGraph for BitGraph {
+    type Neighbors<'a> = BitIter<&'a hibitset::BitSet>;
+
+    fn capacity(&self) -> u32 {
+        BitGraph::capacity(self)
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        self.check_bounds(v);
+        BitGraph::neighbors(self, v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        self.check_bounds(v);
+        BitGraph::degree(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.row(u).contains(v)
+    }
+
+    fn size(&self) -> u64 {
+        BitGraph::size(self)
+    }
+}
+
+impl<'g> UndirectedGraph for BitGraphView<'g> {
+    type Neighbors<'a>
+        = BitIter<&'a hibitset::BitSet>
+    where
+        Self: 'a;
+
+    fn capacity(&self) -> u32 {
+        self.graph().capacity()
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        UndirectedGraph::neighbors(self.graph(), v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        UndirectedGraph::degree(self.graph(), v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.graph().has_edge(u, v)
+    }
+
+    fn size(&self) -> u64 {
+        self.graph().size()
+    }
+}
+
+impl UndirectedGraph for FrozenBitGraph {
+    type Neighbors<'a> = BitIter<&'a hibitset::BitSet>;
+
+    fn capacity(&self) -> u32 {
+        BitGraph::capacity(self)
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        UndirectedGraph::neighbors(&**self, v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        UndirectedGraph::degree(&**self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        UndirectedGraph::has_edge(&**self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        BitGraph::size(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SplitMix64;
+    use crate::{generators, UndirectedGraph};
+
+    /// Sum of the neighbors of every vertex, only through the trait
+    fn checksum<G: UndirectedGraph>(graph: &G) -> u64 {
+        (0..graph.capacity())
+            .flat_map(|v| graph.neighbors(v).map(move |w| (v as u64 + 1) * w as u64))
+            .sum()
+    }
+
+    #[test]
+    fn implementations_agree() {
+        let capacity: usize = 40;
+        let mut rng = SplitMix64::new(74);
+        let c = generators::gnp(capacity as u32, 0.2, &mut rng);
+        let expected = checksum(&c);
+        assert_eq!(checksum(&c.view()), expected);
+        let frozen = c.clone().freeze();
+        assert_eq!(checksum(&frozen), expected);
+        assert_eq!(UndirectedGraph::size(&frozen), c.size());
+        for u in 0..capacity as u32 {
+            assert_eq!(UndirectedGraph::degree(&c.view(), u), c.degree(u));
+            for v in 0..capacity as u32 {
+                let row = c.neighbors(u).any(|w| w == v);
+                assert_eq!(c.has_edge(u, v), row);
+                assert_eq!(frozen.has_edge(u, v), row);
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::BitGraph;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use hibitset::{BitIter, BitSetAnd, BitSetLike, BitSetNot};
+
+/// Owned snapshot of the neighborhood of a vertex, returned by `BitGraph::neighbors_guard`
+///
+/// The snapshot shares its storage with the graph. If the graph is mutated while the
+/// guard is alive, the graph copies the affected row and the guard keeps the old state,
+/// so iterating a guard is never invalidated by contractions or edge updates
+#[derive(Debug, Clone)]
+pub struct NeighborsGuard {
+    m_row: Arc<hibitset::BitSet>,
+}
+
+impl NeighborsGuard {
+    pub(crate) fn new(row: Arc<hibitset::BitSet>) -> NeighborsGuard {
+        NeighborsGuard { m_row: row }
+    }
+
+    /// Returns an iterator over the neighbors in the snapshot
+    pub fn iter(&self) -> BitIter<&hibitset::BitSet> {
+        (&*self.m_row).iter()
+    }
+
+    /// Returns `true` if `v` was a neighbor when the snapshot was taken
+    pub fn contains(&self, v: u32) -> bool {
+        self.m_row.contains(v)
+    }
+
+    /// Returns the snapshot as a bitset, e.g. to combine it with other sets
+    pub fn as_bitset(&self) -> &hibitset::BitSet {
+        &self.m_row
+    }
+}
+
+impl BitSetLike for NeighborsGuard {
+    #[inline]
+    fn layer3(&self) -> usize {
+        self.m_row.layer3()
+    }
+
+    #[inline]
+    fn layer2(&self, i: usize) -> usize {
+        self.m_row.layer2(i)
+    }
+
+    #[inline]
+    fn layer1(&self, i: usize) -> usize {
+        self.m_row.layer1(i)
+    }
+
+    #[inline]
+    fn layer0(&self, i: usize) -> usize {
+        self.m_row.layer0(i)
+    }
+
+    #[inline]
+    fn contains(&self, i: u32) -> bool {
+        self.m_row.contains(i)
+    }
+}
+
+impl IntoIterator for NeighborsGuard {
+    type Item = u32;
+    type IntoIter = BitIter<NeighborsGuard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BitSetLike::iter(self)
+    }
+}
+
+impl<'a> IntoIterator for &'a NeighborsGuard {
+    type Item = u32;
+    type IntoIter = BitIter<&'a hibitset::BitSet>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        NeighborsGuard::iter(self)
+    }
+}
+
+/// Mutable access to the neighborhood of a vertex, returned by `BitGraph::neighbors_set_mut`
+///
+/// The row can be edited like any bitset. When the guard is dropped, the edits are mirrored into
+/// the rows of the affected neighbors and the degrees are recounted, so the graph stays
+/// symmetric. Bits of the vertex itself and of vertices beyond the capacity are discarded
+pub struct NeighborsMut<'a> {
+    m_graph: &'a mut BitGraph,
+    m_vertex: u32,
+    m_before: hibitset::BitSet,
+    m_row: hibitset::BitSet,
+}
+
+impl<'a> NeighborsMut<'a> {
+    pub(crate) fn new(graph: &'a mut BitGraph, v: u32) -> NeighborsMut<'a> {
+        let row = graph.row(v).clone();
+        NeighborsMut {
+            m_graph: graph,
+            m_vertex: v,
+            m_before: row.clone(),
+            m_row: row,
+        }
+    }
+}
+
+impl<'a> Deref for NeighborsMut<'a> {
+    type Target = hibitset::BitSet;
+
+    fn deref(&self) -> &hibitset::BitSet {
+        &self.m_row
+    }
+}
+
+impl<'a> DerefMut for NeighborsMut<'a> {
+    fn deref_mut(&mut self) -> &mut hibitset::BitSet {
+        &mut self.m_row
+    }
+}
+
+impl<'a> Drop for NeighborsMut<'a> {
+    fn drop(&mut self) {
+        let v = self.m_vertex;
+        let n = self.m_graph.m_data.len() as u32;
+        self.m_row.remove(v);
+        let beyond: Vec<u32> = (&self.m_row).iter().filter(|&w| w >= n).collect();
+        for w in beyond {
+            self.m_row.remove(w);
+        }
+        let added: Vec<u32> = BitSetAnd(&self.m_row, BitSetNot(&self.m_before))
+            .iter()
+            .collect();
+        let removed: Vec<u32> = BitSetAnd(&self.m_before, BitSetNot(&self.m_row))
+            .iter()
+            .collect();
+        if added.is_empty() && removed.is_empty() {
+            return;
+        }
+        self.m_graph.journal_begin();
+        let mut touched = hibitset::BitSet::with_capacity(n);
+        touched.add(v);
+        for &w in added.iter() {
+            self.m_graph.row_mut(w).add(v);
+            touched.add(w);
+        }
+        for &w in removed.iter() {
+            self.m_graph.row_mut(w).remove(v);
+            touched.add(w);
+        }
+        *self.m_graph.row_mut(v) = core::mem::take(&mut self.m_row);
+        self.m_graph.recount(&touched);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BitGraph;
+
+    #[test]
+    fn neighbors_guard() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 1..4 {
+            c.add_edge(0, i);
+        }
+        c.add_edge(3, 4);
+
+        let guard = c.neighbors_guard(0);
+        for v in guard.clone() {
+            if v == 3 {
+                c.contract_edge(0, v);
+            }
+        }
+        let tmp: Vec<u32> = guard.iter().collect();
+        assert_eq!(tmp, vec![1, 2, 3]);
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 2, 4]);
+        assert!(!c.neighbors_guard(0).contains(3));
+    }
+
+    #[test]
+    fn neighbors_set() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 1..5 {
+            c.add_edge(0, i);
+        }
+        c.add_edge(5, 6);
+        let mut mask = hibitset::BitSet::new();
+        mask.add(2);
+        mask.add(4);
+        mask.add(6);
+        use hibitset::BitSetLike;
+        let tmp: Vec<u32> = hibitset::BitSetAnd(c.neighbors_set(0), &mask)
+            .iter()
+            .collect();
+        assert_eq!(tmp, vec![2, 4]);
+        c.enable_invariant_cache();
+        assert_eq!(c.component_count(), 2);
+        {
+            let mut row = c.neighbors_set_mut(0);
+            row.remove(1);
+            row.add(6);
+            row.add(0);
+            row.add(capacity as u32 + 3);
+        }
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![2, 3, 4, 6]);
+        assert!(c.row(6).contains(0));
+        assert!(!c.row(1).contains(0));
+        assert_eq!(c.degree(1), 0);
+        assert_eq!(c.degree(6), 2);
+        assert_eq!(c.order(), 6);
+        assert_eq!(c.component_count(), 1);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(0, 2), (0, 3), (0, 4), (0, 6), (5, 6)].iter() {
+            d.add_edge(u, v);
+        }
+        assert_eq!(c, d);
+    }
+}
@@ -0,0 +1,466 @@
+//! A graph backend for large sparse graphs with rows that are lists or bitsets
+
+use crate::{BitGraph, UndirectedGraph, MAX_CAPACITY};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::{mem, slice};
+use hibitset::{BitIter, BitSetLike};
+
+/// Neighbors of a vertex, a sorted list up to the degree threshold and a bitset beyond it
+#[derive(Debug, Clone)]
+enum Row {
+    Sparse(Vec<u32>),
+    Dense(Box<hibitset::BitSet>),
+}
+
+/// An undirected graph whose low-degree vertices keep their neighbors in a small sorted vector
+/// and only switch to a bitset row once their degree passes a threshold. A bitset row holds
+/// one bit per vertex up to its largest neighbor, so with a million vertices most rows of a
+/// `BitGraph` are far larger than their few neighbors. Here a sparse graph costs a few words
+/// per vertex and per edge, while the hubs keep constant time lookups.
+/// A dense row falls back to a list once its degree drops below half of the threshold
+#[derive(Debug, Clone)]
+pub struct HybridBitGraph {
+    m_rows: Vec<Row>,
+    m_degrees: Vec<u32>,
+    m_threshold: u32,
+    m_order: u32,
+    m_size: u64,
+}
+
+impl HybridBitGraph {
+    /// Creates a new HybridBitGraph with `capacity` vertices and no edges. Rows become bitsets
+    /// once their list would take more memory than a bitset over all vertices, at
+    /// `capacity / 32` neighbors and at least 8
+    pub fn with_capacity(capacity: u32) -> HybridBitGraph {
+        Self::with_threshold(capacity, (capacity / 32).max(8))
+    }
+
+    /// Creates a new HybridBitGraph with `capacity` vertices whose rows become bitsets once
+    /// their degree exceeds `threshold`
+    pub fn with_threshold(capacity: u32, threshold: u32) -> HybridBitGraph {
+        if capacity > MAX_CAPACITY as u32 {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                capacity, MAX_CAPACITY
+            )
+        }
+        HybridBitGraph {
+            m_rows: vec![Row::Sparse(Vec::new()); capacity as usize],
+            m_degrees: vec![0; capacity as usize],
+            m_threshold: threshold.max(1),
+            m_order: 0,
+            m_size: 0,
+        }
+    }
+
+    /// Returns `graph` with hybrid rows
+    pub fn from_bit_graph(graph: &BitGraph) -> HybridBitGraph {
+        let mut hybrid = Self::with_capacity(graph.capacity());
+        for (u, v) in graph.edges() {
+            hybrid.add_edge(u, v);
+        }
+        hybrid
+    }
+
+    /// Returns the graph as a `BitGraph` of the same capacity
+    pub fn to_bit_graph(&self) -> BitGraph {
+        BitGraph::from_rows(
+            (0..self.capacity())
+                .map(|v| self.neighbors(v).collect())
+                .collect(),
+        )
+    }
+
+    #[inline]
+    fn check_bounds(&self, idx: u32) {
+        if idx as usize >= self.m_rows.len() {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                idx,
+                self.m_rows.len()
+            )
+        }
+    }
+
+    #[inline]
+    fn check_is_same(&self, u: u32, v: u32) {
+        if u == v {
+            panic!("Edge needs two distinct endpoints, given: {} {}", u, v)
+        }
+    }
+
+    /// Degree past which a row is stored as a bitset
+    pub fn threshold(&self) -> u32 {
+        self.m_threshold
+    }
+
+    /// Returns `true` if the neighbors of `v` are stored as a bitset
+    pub fn is_dense(&self, v: u32) -> bool {
+        self.check_bounds(v);
+        matches!(self.m_rows[v as usize], Row::Dense(_))
+    }
+
+    /// Adds a new undirected edge from `u` to `v`
+    /// If the edge already exists, the graph is not updated
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.check_is_same(u, v);
+        if self.insert(u, v) {
+            self.insert(v, u);
+            self.m_size += 1;
+        }
+    }
+
+    /// Removes the edge from `u` to `v` after performing boundary checks.
+    /// If the edge is not present the graph is not updated
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if u != v && self.delete(u, v) {
+            self.delete(v, u);
+            self.m_size -= 1;
+        }
+    }
+
+    fn insert(&mut self, u: u32, v: u32) -> bool {
+        let threshold = self.m_threshold;
+        let row = &mut self.m_rows[u as usize];
+        let added = match row {
+            Row::Sparse(list) => match list.binary_search(&v) {
+                Ok(_) => false,
+                Err(i) => {
+                    list.insert(i, v);
+                    if list.len() as u32 > threshold {
+                        *row = Row::Dense(Box::new(list.iter().copied().collect()));
+                    }
+                    true
+                }
+            },
+            Row::Dense(set) => !set.add(v),
+        };
+        if added {
+            if self.m_degrees[u as usize] == 0 {
+                self.m_order += 1;
+            }
+            self.m_degrees[u as usize] += 1;
+        }
+        added
+    }
+
+    fn delete(&mut self, u: u32, v: u32) -> bool {
+        let row = &mut self.m_rows[u as usize];
+        let removed = match row {
+            Row::Sparse(list) => match list.binary_search(&v) {
+                Ok(i) => {
+                    list.remove(i);
+                    true
+                }
+                Err(_) => false,
+            },
+            Row::Dense(set) => set.remove(v),
+        };
+        if removed {
+            self.m_degrees[u as usize] -= 1;
+            let degree = self.m_degrees[u as usize];
+            if degree == 0 {
+                self.m_order -= 1;
+            }
+            if let Row::Dense(set) = row {
+                if degree < self.m_threshold / 2 {
+                    *row = Row::Sparse((&**set).iter().collect());
+                }
+            }
+        }
+        removed
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent, a binary search in a sparse row
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        match &self.m_rows[u as usize] {
+            Row::Sparse(list) => list.binary_search(&v).is_ok(),
+            Row::Dense(set) => set.contains(v),
+        }
+    }
+
+    /// Removes all edges and keeps the capacity and the threshold
+    pub fn clear(&mut self) {
+        self.m_rows
+            .iter_mut()
+            .for_each(|row| *row = Row::Sparse(Vec::new()));
+        self.m_degrees.iter_mut().for_each(|d| *d = 0);
+        self.m_order = 0;
+        self.m_size = 0;
+    }
+
+    /// Removes all edges incident to `v`
+    pub fn clear_vertex(&mut self, v: u32) {
+        let neighbors: Vec<u32> = self.neighbors(v).collect();
+        for w in neighbors {
+            self.remove_edge(v, w);
+        }
+    }
+
+    /// Contracts the edge (target, source) by adding all neighbors
+    /// of source to `target` and removing `source`
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        if !self.has_edge(target, source) {
+            panic!(
+                "Edge ({}, {}) does not exist. Can't contract!",
+                target, source
+            );
+        }
+        let neighbors: Vec<u32> = self.neighbors(source).collect();
+        for w in neighbors {
+            self.remove_edge(source, w);
+            if w != target {
+                self.add_edge(target, w);
+            }
+        }
+    }
+
+    /// Returns an iterator over the neighborhood of vertex `v` in increasing order
+    pub fn neighbors(&self, v: u32) -> HybridNeighbors<'_> {
+        self.check_bounds(v);
+        HybridNeighbors {
+            m_inner: match &self.m_rows[v as usize] {
+                Row::Sparse(list) => Inner::Sparse(list.iter()),
+                Row::Dense(set) => Inner::Dense((&**set).iter()),
+            },
+        }
+    }
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    pub fn capacity(&self) -> u32 {
+        self.m_rows.len() as u32
+    }
+
+    /// Number of neighbors of `v`
+    pub fn degree(&self, v: u32) -> u32 {
+        self.check_bounds(v);
+        self.m_degrees[v as usize]
+    }
+
+    /// Number of vertices in the graph
+    pub fn order(&self) -> u32 {
+        self.m_order
+    }
+
+    /// Number of edges in the graph
+    pub fn size(&self) -> u64 {
+        self.m_size
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u < v`, ordered by `u` and then `v`
+    pub fn edges(&self) -> HybridEdges<'_> {
+        HybridEdges {
+            m_graph: self,
+            m_vertex: 0,
+            m_neighbors: None,
+        }
+    }
+
+    /// Estimated number of bytes held by the graph: the rows, lists and bitsets and the
+    /// degree vector
+    pub fn memory_usage(&self) -> usize {
+        let word = mem::size_of::<usize>();
+        let row_bytes: usize = self
+            .m_rows
+            .iter()
+            .map(|row| match row {
+                Row::Sparse(list) => list.capacity() * mem::size_of::<u32>(),
+                Row::Dense(set) => {
+                    let words = set.layer0_as_slice().len()
+                        + set.layer1_as_slice().len()
+                        + set.layer2_as_slice().len();
+                    mem::size_of::<hibitset::BitSet>() + words * word
+                }
+            })
+            .sum();
+        mem::size_of::<HybridBitGraph>()
+            + self.m_rows.capacity() * mem::size_of::<Row>()
+            + self.m_degrees.capacity() * mem::size_of::<u32>()
+            + row_bytes
+    }
+}
+
+impl UndirectedGraph for HybridBitGraph {
+    type Neighbors<'a> = HybridNeighbors<'a>;
+
+    fn capacity(&self) -> u32 {
+        HybridBitGraph::capacity(self)
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        HybridBitGraph::neighbors(self, v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        HybridBitGraph::degree(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        HybridBitGraph::has_edge(self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        self.m_size
+    }
+}
+
+enum Inner<'a> {
+    Sparse(slice::Iter<'a, u32>),
+    Dense(BitIter<&'a hibitset::BitSet>),
+}
+
+/// Iterator over the neighbors of a vertex of a `HybridBitGraph`, created by
+/// `HybridBitGraph::neighbors`
+pub struct HybridNeighbors<'a> {
+    m_inner: Inner<'a>,
+}
+
+impl<'a> Iterator for HybridNeighbors<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.m_inner {
+            Inner::Sparse(list) => list.next().copied(),
+            Inner::Dense(bits) => bits.next(),
+        }
+    }
+}
+
+impl<'a> FusedIterator for HybridNeighbors<'a> {}
+
+/// Iterator over the edges of a `HybridBitGraph`, created by `HybridBitGraph::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct HybridEdges<'a> {
+    m_graph: &'a HybridBitGraph,
+    m_vertex: u32,
+    m_neighbors: Option<HybridNeighbors<'a>>,
+}
+
+impl<'a> Iterator for HybridEdges<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(neighbors) = self.m_neighbors.as_mut() {
+                let u = self.m_vertex - 1;
+                if let Some(v) = neighbors.find(|&v| v > u) {
+                    return Some((u, v));
+                }
+                self.m_neighbors = None;
+            }
+            if self.m_vertex >= self.m_graph.capacity() {
+                return None;
+            }
+            let u = self.m_vertex;
+            self.m_vertex += 1;
+            if self.m_graph.m_degrees[u as usize] > 0 {
+                self.m_neighbors = Some(self.m_graph.neighbors(u));
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for HybridEdges<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SplitMix64;
+    use crate::{algorithms, generators, HybridBitGraph};
+
+    #[test]
+    fn hybrid_rows() {
+        let capacity: usize = 100;
+        let mut c = HybridBitGraph::with_threshold(capacity as u32, 4);
+        for v in 1..=5 {
+            c.add_edge(0, v);
+        }
+        c.add_edge(5, 0);
+        c.add_edge(70, 3);
+        assert!(c.is_dense(0) && !c.is_dense(3));
+        assert_eq!(c.size(), 6);
+        assert_eq!(c.order(), 7);
+        assert_eq!(c.degree(0), 5);
+        assert!(c.has_edge(5, 0) && c.has_edge(3, 70) && !c.has_edge(3, 4));
+        let tmp: Vec<u32> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 2, 3, 4, 5]);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (3, 70)]);
+        // two neighbors are still above half of the threshold, one is not
+        c.remove_edge(0, 5);
+        c.remove_edge(0, 4);
+        c.remove_edge(0, 3);
+        assert!(c.is_dense(0));
+        c.remove_edge(0, 2);
+        assert!(!c.is_dense(0));
+        assert!(c.neighbors(0).eq(vec![1]));
+        c.contract_edge(3, 70);
+        assert_eq!(c.order(), 2);
+        c.clear_vertex(0);
+        assert_eq!(c.size(), 0);
+        c.add_edge(1, 2);
+        c.clear();
+        assert_eq!(c.order(), 0);
+    }
+
+    #[test]
+    fn million_vertices() {
+        let capacity: usize = 1_000_000;
+        let mut c = HybridBitGraph::with_capacity(capacity as u32);
+        for v in 1..capacity as u32 {
+            c.add_edge(v - 1, v);
+        }
+        for v in (0..capacity as u32).step_by(10) {
+            c.add_edge(0, v.max(1));
+        }
+        assert!(c.is_dense(0) && !c.is_dense(1));
+        assert_eq!(c.size(), capacity as u64 - 1 + 99_999);
+        // a few words per vertex and edge, a lazy `BitGraph` would need one bit per vertex
+        // up to the neighbor in nearly every row
+        assert!(c.memory_usage() < capacity * 64);
+        assert_eq!(
+            algorithms::shortest_path(&c, 999_999, 999_990),
+            Some(vec![
+                999_999, 999_998, 999_997, 999_996, 999_995, 999_994, 999_993, 999_992, 999_991,
+                999_990
+            ])
+        );
+    }
+
+    #[test]
+    fn matches_bit_graph() {
+        let capacity: usize = 80;
+        let mut rng = SplitMix64::new(83);
+        let a = generators::gnp(capacity as u32, 0.15, &mut rng);
+        let c = HybridBitGraph::from_bit_graph(&a);
+        assert!((0..capacity as u32).any(|v| c.is_dense(v)));
+        assert_eq!(c.to_bit_graph(), a);
+        assert_eq!(c.order(), a.order());
+        assert!(c.edges().eq(a.edges()));
+        assert_eq!(
+            algorithms::connected_components(&c),
+            algorithms::connected_components(&a)
+        );
+        let mut d = c.clone();
+        let mut b = a.clone();
+        let (u, w) = a.edges().next().unwrap();
+        d.contract_edge(w, u);
+        b.contract_edge(w, u);
+        assert_eq!(d.to_bit_graph(), b);
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds. Given: 5, Allowed: 5")]
+    fn degree_out_of_bounds() {
+        let capacity: usize = 5;
+        HybridBitGraph::with_capacity(capacity as u32).degree(capacity as u32);
+    }
+}
@@ -0,0 +1,330 @@
+//! Expensive graph invariants and an opt-in cache for them
+//!
+//! Mutations report to the hooks at the bottom of this file. A cached value is only
+//! dropped if the mutation can actually change it, and updated in place where that is cheap.
+
+use crate::{BitGraph, VertexId};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+const UNKNOWN: u64 = u64::MAX;
+
+/// Cached invariants, `UNKNOWN` marks a value that has to be recomputed
+#[derive(Debug)]
+pub(crate) struct InvariantCache {
+    m_components: AtomicU64,
+    m_degeneracy: AtomicU64,
+    m_triangles: AtomicU64,
+}
+
+impl InvariantCache {
+    fn new() -> InvariantCache {
+        InvariantCache {
+            m_components: AtomicU64::new(UNKNOWN),
+            m_degeneracy: AtomicU64::new(UNKNOWN),
+            m_triangles: AtomicU64::new(UNKNOWN),
+        }
+    }
+
+    fn get_or(value: &AtomicU64, compute: impl FnOnce() -> u64) -> u64 {
+        let cached = value.load(Ordering::Relaxed);
+        if cached != UNKNOWN {
+            return cached;
+        }
+        let computed = compute();
+        value.store(computed, Ordering::Relaxed);
+        computed
+    }
+
+    fn update(value: &mut AtomicU64, f: impl FnOnce(u64) -> u64) {
+        let v = value.get_mut();
+        if *v != UNKNOWN {
+            *v = f(*v);
+        }
+    }
+
+    fn invalidate(value: &mut AtomicU64) {
+        *value.get_mut() = UNKNOWN;
+    }
+}
+
+impl Clone for InvariantCache {
+    fn clone(&self) -> Self {
+        InvariantCache {
+            m_components: AtomicU64::new(self.m_components.load(Ordering::Relaxed)),
+            m_degeneracy: AtomicU64::new(self.m_degeneracy.load(Ordering::Relaxed)),
+            m_triangles: AtomicU64::new(self.m_triangles.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl BitGraph {
+    /// Enables caching of `component_count`, `degeneracy` and `triangle_count`.
+    /// Cached values are kept up to date or invalidated by all mutating methods
+    pub fn enable_invariant_cache(&mut self) {
+        if self.m_cache.is_none() {
+            self.m_cache = Some(InvariantCache::new());
+        }
+    }
+
+    /// Disables the invariant cache and drops all cached values
+    pub fn disable_invariant_cache(&mut self) {
+        self.m_cache = None;
+    }
+
+    /// Returns `true` if the invariant cache is enabled
+    pub fn has_invariant_cache(&self) -> bool {
+        self.m_cache.is_some()
+    }
+
+    /// Number of connected components, isolated vertices are not counted
+    pub fn component_count(&self) -> u32 {
+        match &self.m_cache {
+            Some(cache) => {
+                InvariantCache::get_or(&cache.m_components, || self.count_components() as u64)
+                    as u32
+            }
+            None => self.count_components(),
+        }
+    }
+
+    /// Smallest `k` such that every subgraph has a vertex of degree at most `k`
+    pub fn degeneracy(&self) -> u32 {
+        match &self.m_cache {
+            Some(cache) => {
+                InvariantCache::get_or(&cache.m_degeneracy, || self.compute_degeneracy() as u64)
+                    as u32
+            }
+            None => self.compute_degeneracy(),
+        }
+    }
+
+    /// Number of triangles in the graph
+    pub fn triangle_count(&self) -> u64 {
+        match &self.m_cache {
+            Some(cache) => InvariantCache::get_or(&cache.m_triangles, || self.count_triangles()),
+            None => self.count_triangles(),
+        }
+    }
+
+    fn count_components(&self) -> u32 {
+        let mut visited = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        let mut stack: Vec<u32> = Vec::new();
+        let mut components = 0;
+        for v in 0..self.m_data.len() as u32 {
+            if self.degree(v) == 0 || visited.contains(v) {
+                continue;
+            }
+            components += 1;
+            visited.add(v);
+            stack.push(v);
+            while let Some(u) = stack.pop() {
+                for w in self.neighbors(u) {
+                    if !visited.add(w) {
+                        stack.push(w);
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    /// Bucket based peeling of minimum degree vertices (Matula and Beck)
+    fn compute_degeneracy(&self) -> u32 {
+        let n = self.m_data.len();
+        let max_degree = self.max_degree() as usize;
+        let mut degrees = self.m_degrees.clone();
+        let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); max_degree + 1];
+        for v in 0..n {
+            buckets[degrees[v] as usize].push(v as u32);
+        }
+        let mut removed = hibitset::BitSet::with_capacity(n as u32);
+        let mut degeneracy = 0;
+        let mut d = 0;
+        for _ in 0..n {
+            let v = loop {
+                // buckets contain stale entries, skip removed vertices and outdated degrees
+                match buckets[d].pop() {
+                    Some(v) if !removed.contains(v) && degrees[v as usize] as usize == d => {
+                        break v
+                    }
+                    Some(_) => {}
+                    None => d += 1,
+                }
+            };
+            degeneracy = degeneracy.max(d as u32);
+            removed.add(v);
+            for w in self.neighbors(v) {
+                if !removed.contains(w) {
+                    degrees[w as usize] -= 1;
+                    buckets[degrees[w as usize] as usize].push(w);
+                }
+            }
+            d = d.saturating_sub(1);
+        }
+        degeneracy
+    }
+
+    fn count_triangles(&self) -> u64 {
+        let mut triangles = 0;
+        for u in 0..self.m_data.len() as u32 {
+            for v in self.neighbors(u).filter(|&v| v > u) {
+                triangles += self.common_count(u, v) as u64;
+            }
+        }
+        triangles / 3
+    }
+}
+
+impl<I: VertexId> BitGraph<I> {
+    /// Number of common neighbors of `u` and `v`, computed on the bottom layer words
+    pub(crate) fn common_count(&self, u: u32, v: u32) -> u32 {
+        self.row(u)
+            .layer0_as_slice()
+            .iter()
+            .zip(self.row(v).layer0_as_slice())
+            .map(|(a, b)| (a & b).count_ones())
+            .sum()
+    }
+
+    /// Hook called after the new edge `(u, v)` was added
+    pub(crate) fn edge_added(&mut self, u: u32, v: u32) {
+        if self.m_cache.is_none() {
+            return;
+        }
+        let common = self.common_count(u, v) as u64;
+        let (du, dv) = (
+            self.m_degrees[u as usize].index(),
+            self.m_degrees[v as usize].index(),
+        );
+        let cache = self.m_cache.as_mut().unwrap();
+        InvariantCache::update(&mut cache.m_triangles, |t| t + common);
+        match (du, dv) {
+            (1, 1) => InvariantCache::update(&mut cache.m_components, |c| c + 1),
+            (1, _) | (_, 1) => {}
+            _ => InvariantCache::invalidate(&mut cache.m_components),
+        }
+        InvariantCache::invalidate(&mut cache.m_degeneracy);
+    }
+
+    /// Hook called after the existing edge `(u, v)` was removed
+    pub(crate) fn edge_removed(&mut self, u: u32, v: u32) {
+        if self.m_cache.is_none() {
+            return;
+        }
+        let common = self.common_count(u, v) as u64;
+        let (du, dv) = (
+            self.m_degrees[u as usize].index(),
+            self.m_degrees[v as usize].index(),
+        );
+        let cache = self.m_cache.as_mut().unwrap();
+        InvariantCache::update(&mut cache.m_triangles, |t| t - common);
+        match (du, dv) {
+            (0, 0) => InvariantCache::update(&mut cache.m_components, |c| c - 1),
+            (0, _) | (_, 0) => {}
+            _ => InvariantCache::invalidate(&mut cache.m_components),
+        }
+        InvariantCache::invalidate(&mut cache.m_degeneracy);
+    }
+
+    /// Hook called after an edge was contracted into `target`
+    pub(crate) fn contracted(&mut self, target: u32) {
+        let isolated = self.m_degrees[target as usize] == I::default();
+        if let Some(cache) = self.m_cache.as_mut() {
+            if isolated {
+                InvariantCache::update(&mut cache.m_components, |c| c - 1);
+            }
+            InvariantCache::invalidate(&mut cache.m_triangles);
+            InvariantCache::invalidate(&mut cache.m_degeneracy);
+        }
+    }
+
+    /// Hook called by mutations that can't report precisely what changed
+    pub(crate) fn invalidate_cache(&mut self) {
+        if let Some(cache) = self.m_cache.as_mut() {
+            InvariantCache::invalidate(&mut cache.m_components);
+            InvariantCache::invalidate(&mut cache.m_degeneracy);
+            InvariantCache::invalidate(&mut cache.m_triangles);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BitGraph;
+
+    #[test]
+    fn invariants() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.component_count(), 0);
+        assert_eq!(c.degeneracy(), 0);
+        assert_eq!(c.triangle_count(), 0);
+
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(2, 0);
+        c.add_edge(5, 6);
+        assert_eq!(c.component_count(), 2);
+        assert_eq!(c.degeneracy(), 2);
+        assert_eq!(c.triangle_count(), 1);
+
+        let k = BitGraph::complete(6);
+        assert_eq!(k.component_count(), 1);
+        assert_eq!(k.degeneracy(), 5);
+        assert_eq!(k.triangle_count(), 20);
+    }
+
+    #[test]
+    fn invariant_cache() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        c.enable_invariant_cache();
+        assert!(c.has_invariant_cache());
+        assert!(!d.has_invariant_cache());
+
+        let check = |c: &BitGraph, d: &BitGraph| {
+            assert_eq!(c.component_count(), d.component_count());
+            assert_eq!(c.degeneracy(), d.degeneracy());
+            assert_eq!(c.triangle_count(), d.triangle_count());
+        };
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (2, 3),
+            (0, 3),
+        ];
+        for &(u, v) in edges.iter() {
+            c.add_edge(u, v);
+            d.add_edge(u, v);
+            check(&c, &d);
+        }
+        c.add_edge(0, 1);
+        check(&c, &d);
+        for &(u, v) in edges.iter().rev().take(3) {
+            c.remove_edge(u, v);
+            d.remove_edge(u, v);
+            check(&c, &d);
+        }
+        c.contract_edge(0, 1);
+        d.contract_edge(0, 1);
+        check(&c, &d);
+        c.contract_edge(3, 4);
+        d.contract_edge(3, 4);
+        check(&c, &d);
+        c.add_edge_unchecked(7, 8);
+        d.add_edge_unchecked(7, 8);
+        check(&c, &d);
+
+        let e = c.clone();
+        check(&e, &d);
+        c.disable_invariant_cache();
+        check(&c, &d);
+    }
+}
@@ -0,0 +1,570 @@
+//! Readers for graph file formats
+//!
+//! Input is treated as untrusted: all sizes are checked against `ReadLimits` before
+//! anything is allocated, numbers are parsed with overflow checks and malformed input
+//! is reported as a `ParseError` carrying the line and column of the problem.
+
+use crate::{BitGraph, MAX_CAPACITY};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read};
+use std::mem;
+
+/// Upper bounds enforced while reading a graph file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLimits {
+    /// Maximum number of vertices a header may declare
+    pub max_vertices: u32,
+    /// Maximum number of edges a header may declare
+    pub max_edges: u64,
+    /// Maximum length of a single line in bytes, excluding the line terminator
+    pub max_line_length: usize,
+    /// Maximum number of bits all rows may span together. A row spans up to its largest
+    /// neighbor, so a single edge `{u, v}` with `u < v` can cost up to `v` bits
+    pub max_row_bits: u64,
+}
+
+impl Default for ReadLimits {
+    /// 2^18 vertices and 2^30 row bits, about 3MB for the vertices and 128MB for the rows
+    fn default() -> Self {
+        let n: u64 = 1 << 18;
+        ReadLimits {
+            max_vertices: n as u32,
+            max_edges: n * (n - 1) / 2,
+            max_line_length: 1024,
+            max_row_bits: 1 << 30,
+        }
+    }
+}
+
+/// Reason a graph file was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The underlying reader failed
+    Io(io::ErrorKind),
+    /// A line exceeded `ReadLimits::max_line_length`
+    LineTooLong,
+    /// A line is not valid UTF-8
+    InvalidUtf8,
+    /// An edge was given before the problem line
+    MissingHeader,
+    /// More than one problem line was given
+    DuplicateHeader,
+    /// The problem line does not name the expected problem descriptor
+    InvalidHeader,
+    /// A token was found where the line should have ended
+    UnexpectedToken,
+    /// The line ended before all required tokens were read
+    MissingToken,
+    /// A token is not an unsigned decimal number
+    InvalidNumber,
+    /// A number does not fit into the target integer type
+    NumberOverflow,
+    /// The header declares more vertices than allowed
+    TooManyVertices { given: u64, allowed: u32 },
+    /// The header declares more edges than allowed, or more edges than declared were given
+    TooManyEdges { allowed: u64 },
+    /// An edge would grow the rows beyond `ReadLimits::max_row_bits`
+    TooManyRowBits { allowed: u64 },
+    /// An edge endpoint is not in `1..=order`
+    VertexOutOfRange { vertex: u32, order: u32 },
+    /// An edge with two identical endpoints
+    SelfLoop(u32),
+    /// Fewer edges than declared in the header were given
+    EdgeCountMismatch { expected: u64, found: u64 },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::Io(kind) => write!(f, "I/O error: {:?}", kind),
+            ParseErrorKind::LineTooLong => write!(f, "line too long"),
+            ParseErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8"),
+            ParseErrorKind::MissingHeader => write!(f, "edge before problem line"),
+            ParseErrorKind::DuplicateHeader => write!(f, "duplicate problem line"),
+            ParseErrorKind::InvalidHeader => write!(f, "invalid problem line"),
+            ParseErrorKind::UnexpectedToken => write!(f, "unexpected token"),
+            ParseErrorKind::MissingToken => write!(f, "missing token"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::NumberOverflow => write!(f, "number too large"),
+            ParseErrorKind::TooManyVertices { given, allowed } => write!(
+                f,
+                "too many vertices. Given: {}, Allowed: {}",
+                given, allowed
+            ),
+            ParseErrorKind::TooManyEdges { allowed } => {
+                write!(f, "too many edges. Allowed: {}", allowed)
+            }
+            ParseErrorKind::TooManyRowBits { allowed } => {
+                write!(f, "too many row bits. Allowed: {}", allowed)
+            }
+            ParseErrorKind::VertexOutOfRange { vertex, order } => write!(
+                f,
+                "vertex out of range. Given: {}, Allowed: 1..={}",
+                vertex, order
+            ),
+            ParseErrorKind::SelfLoop(v) => write!(f, "self loop at vertex {}", v),
+            ParseErrorKind::EdgeCountMismatch { expected, found } => write!(
+                f,
+                "edge count mismatch. Expected: {}, Found: {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+/// Error returned by the readers in this module
+///
+/// Lines and columns are 1-based, the column points at the first byte of the offending token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { line, column, kind }
+    }
+
+    /// Line on which the error occurred
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Column on which the error occurred
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Reason of the error
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Splits a line into whitespace separated tokens, keeping track of their 1-based column
+struct Tokens<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str) -> Tokens<'a> {
+        Tokens { line, pos: 0 }
+    }
+
+    /// Column directly behind the last consumed token
+    fn end(&self) -> usize {
+        self.pos + 1
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.line.as_bytes();
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        if self.pos == bytes.len() {
+            return None;
+        }
+        let start = self.pos;
+        while self.pos < bytes.len() && !bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        Some((start + 1, &self.line[start..self.pos]))
+    }
+}
+
+fn parse_u64(token: &str) -> Result<u64, ParseErrorKind> {
+    if token.is_empty() {
+        return Err(ParseErrorKind::InvalidNumber);
+    }
+    let mut value: u64 = 0;
+    for b in token.bytes() {
+        if !b.is_ascii_digit() {
+            return Err(ParseErrorKind::InvalidNumber);
+        }
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_add((b - b'0') as u64))
+            .ok_or(ParseErrorKind::NumberOverflow)?;
+    }
+    Ok(value)
+}
+
+fn parse_u32(token: &str) -> Result<u32, ParseErrorKind> {
+    let value = parse_u64(token)?;
+    if value > u32::MAX as u64 {
+        return Err(ParseErrorKind::NumberOverflow);
+    }
+    Ok(value as u32)
+}
+
+fn expect_number<'a, T>(
+    tokens: &mut Tokens<'a>,
+    line: usize,
+    parse: fn(&str) -> Result<T, ParseErrorKind>,
+) -> Result<(usize, T), ParseError> {
+    match tokens.next() {
+        Some((column, token)) => parse(token)
+            .map(|v| (column, v))
+            .map_err(|kind| ParseError::new(line, column, kind)),
+        None => Err(ParseError::new(
+            line,
+            tokens.end(),
+            ParseErrorKind::MissingToken,
+        )),
+    }
+}
+
+fn expect_end(tokens: &mut Tokens<'_>, line: usize) -> Result<(), ParseError> {
+    match tokens.next() {
+        Some((column, _)) => Err(ParseError::new(
+            line,
+            column,
+            ParseErrorKind::UnexpectedToken,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Reads one line into `buf` without ever buffering more than `limit + 1` bytes of it.
+/// Returns `Ok(false)` at the end of the input
+fn read_line<R: BufRead>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    limit: usize,
+    line: usize,
+) -> Result<bool, ParseError> {
+    buf.clear();
+    let read = reader
+        .by_ref()
+        .take(limit as u64 + 2)
+        .read_until(b'\n', buf)
+        .map_err(|e| ParseError::new(line, 1, ParseErrorKind::Io(e.kind())))?;
+    if read == 0 {
+        return Ok(false);
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+    }
+    if buf.len() > limit {
+        return Err(ParseError::new(
+            line,
+            limit + 1,
+            ParseErrorKind::LineTooLong,
+        ));
+    }
+    Ok(true)
+}
+
+/// Bits the row of `u` grows by when `v` is added to it
+fn row_growth(graph: &BitGraph, u: u32, v: u32) -> u64 {
+    let bits = mem::size_of::<usize>() as u64 * 8;
+    let needed = (v as u64 / bits + 1) * bits;
+    needed.saturating_sub(graph.row_span(u))
+}
+
+/// Reads a graph in the PACE `.gr` format using the default `ReadLimits`
+///
+/// The file starts with a problem line `p tw <n> <m>` followed by `m` edge lines `<u> <v>`
+/// with 1-based vertex ids. Vertex `i` of the file becomes vertex `i - 1` of the graph.
+/// Lines starting with `c` are comments, empty lines are ignored.
+pub fn read_pace<R: BufRead>(reader: R) -> Result<BitGraph, ParseError> {
+    read_pace_with_limits(reader, &ReadLimits::default())
+}
+
+/// Same as `read_pace` but with user supplied `ReadLimits`
+pub fn read_pace_with_limits<R: BufRead>(
+    mut reader: R,
+    limits: &ReadLimits,
+) -> Result<BitGraph, ParseError> {
+    let max_vertices = limits.max_vertices.min(MAX_CAPACITY as u32);
+    let mut buf = Vec::new();
+    let mut line = 0;
+    let mut header: Option<(u32, u64)> = None;
+    let mut graph = BitGraph::with_capacity(0);
+    let mut edges: u64 = 0;
+    let mut row_bits: u64 = 0;
+
+    loop {
+        line += 1;
+        if !read_line(&mut reader, &mut buf, limits.max_line_length, line)? {
+            break;
+        }
+        let text = std::str::from_utf8(&buf)
+            .map_err(|e| ParseError::new(line, e.valid_up_to() + 1, ParseErrorKind::InvalidUtf8))?;
+        let mut tokens = Tokens::new(text);
+        let (column, first) = match tokens.next() {
+            Some(token) => token,
+            None => continue,
+        };
+        match first {
+            "c" => continue,
+            "p" => {
+                if header.is_some() {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        ParseErrorKind::DuplicateHeader,
+                    ));
+                }
+                match tokens.next() {
+                    Some((_, "tw")) => {}
+                    Some((column, _)) => {
+                        return Err(ParseError::new(line, column, ParseErrorKind::InvalidHeader))
+                    }
+                    None => {
+                        return Err(ParseError::new(
+                            line,
+                            tokens.end(),
+                            ParseErrorKind::MissingToken,
+                        ))
+                    }
+                }
+                let (column, n) = expect_number(&mut tokens, line, parse_u64)?;
+                if n > max_vertices as u64 {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        ParseErrorKind::TooManyVertices {
+                            given: n,
+                            allowed: max_vertices,
+                        },
+                    ));
+                }
+                let (column, m) = expect_number(&mut tokens, line, parse_u64)?;
+                if m > limits.max_edges {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        ParseErrorKind::TooManyEdges {
+                            allowed: limits.max_edges,
+                        },
+                    ));
+                }
+                expect_end(&mut tokens, line)?;
+                graph = BitGraph::with_capacity(n as u32);
+                header = Some((n as u32, m));
+            }
+            _ => {
+                let (n, m) = match header {
+                    Some(h) => h,
+                    None => {
+                        return Err(ParseError::new(line, column, ParseErrorKind::MissingHeader))
+                    }
+                };
+                let u = parse_u32(first).map_err(|kind| ParseError::new(line, column, kind))?;
+                let (v_column, v) = expect_number(&mut tokens, line, parse_u32)?;
+                expect_end(&mut tokens, line)?;
+                for &(c, w) in [(column, u), (v_column, v)].iter() {
+                    if w == 0 || w > n {
+                        return Err(ParseError::new(
+                            line,
+                            c,
+                            ParseErrorKind::VertexOutOfRange {
+                                vertex: w,
+                                order: n,
+                            },
+                        ));
+                    }
+                }
+                if u == v {
+                    return Err(ParseError::new(line, v_column, ParseErrorKind::SelfLoop(u)));
+                }
+                if edges == m {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        ParseErrorKind::TooManyEdges { allowed: m },
+                    ));
+                }
+                let grown = row_growth(&graph, u - 1, v - 1) + row_growth(&graph, v - 1, u - 1);
+                if row_bits + grown > limits.max_row_bits {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        ParseErrorKind::TooManyRowBits {
+                            allowed: limits.max_row_bits,
+                        },
+                    ));
+                }
+                row_bits += grown;
+                edges += 1;
+                graph.add_edge(u - 1, v - 1);
+            }
+        }
+    }
+
+    match header {
+        Some((_, m)) if edges != m => Err(ParseError::new(
+            line,
+            1,
+            ParseErrorKind::EdgeCountMismatch {
+                expected: m,
+                found: edges,
+            },
+        )),
+        Some(_) => Ok(graph),
+        None => Err(ParseError::new(line, 1, ParseErrorKind::MissingHeader)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_pace, read_pace_with_limits, ParseErrorKind, ReadLimits};
+
+    #[test]
+    fn read_pace_ok() {
+        let input = "c example\np tw 4 3\n1 2\n2 3\r\n\n3 4\n";
+        let c = read_pace(input.as_bytes()).unwrap();
+        assert_eq!(c.order(), 4);
+        assert_eq!(c.degree(0), 1);
+        assert_eq!(c.degree(1), 2);
+        assert_eq!(c.degree(3), 1);
+    }
+
+    #[test]
+    fn read_pace_errors() {
+        let cases: Vec<(&str, usize, usize, ParseErrorKind)> = vec![
+            ("1 2\n", 1, 1, ParseErrorKind::MissingHeader),
+            (
+                "p tw 2 1\np tw 2 1\n",
+                2,
+                1,
+                ParseErrorKind::DuplicateHeader,
+            ),
+            ("p td 2 1\n", 1, 3, ParseErrorKind::InvalidHeader),
+            ("p tw 2\n", 1, 7, ParseErrorKind::MissingToken),
+            ("p tw 2 1\n1 -2\n", 2, 3, ParseErrorKind::InvalidNumber),
+            (
+                "p tw 2 1\n1 99999999999\n",
+                2,
+                3,
+                ParseErrorKind::NumberOverflow,
+            ),
+            (
+                "p tw 999999999999999999999 1\n",
+                1,
+                6,
+                ParseErrorKind::NumberOverflow,
+            ),
+            (
+                "p tw 20000000 1\n",
+                1,
+                6,
+                ParseErrorKind::TooManyVertices {
+                    given: 20000000,
+                    allowed: 262144,
+                },
+            ),
+            (
+                "p tw 2 1\n1 3\n",
+                2,
+                3,
+                ParseErrorKind::VertexOutOfRange {
+                    vertex: 3,
+                    order: 2,
+                },
+            ),
+            ("p tw 2 1\n1 1\n", 2, 3, ParseErrorKind::SelfLoop(1)),
+            ("p tw 2 1\n1 2 3\n", 2, 5, ParseErrorKind::UnexpectedToken),
+            (
+                "p tw 3 1\n1 2\n2 3\n",
+                3,
+                1,
+                ParseErrorKind::TooManyEdges { allowed: 1 },
+            ),
+            (
+                "p tw 3 2\n1 2\n",
+                3,
+                1,
+                ParseErrorKind::EdgeCountMismatch {
+                    expected: 2,
+                    found: 1,
+                },
+            ),
+        ];
+        for (input, line, column, kind) in cases {
+            let err = read_pace(input.as_bytes()).unwrap_err();
+            assert_eq!(
+                (err.line(), err.column(), err.kind()),
+                (line, column, &kind)
+            );
+        }
+    }
+
+    #[test]
+    fn read_pace_limits() {
+        let limits = ReadLimits {
+            max_vertices: 10,
+            max_edges: 2,
+            max_line_length: 12,
+            max_row_bits: 1 << 10,
+        };
+        let err = read_pace_with_limits("c a long comment\n".as_bytes(), &limits).unwrap_err();
+        assert_eq!(err.kind(), &ParseErrorKind::LineTooLong);
+        let err = read_pace_with_limits("p tw 11 1\n".as_bytes(), &limits).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseErrorKind::TooManyVertices {
+                given: 11,
+                allowed: 10
+            }
+        );
+        let err = read_pace_with_limits("p tw 9 3\n".as_bytes(), &limits).unwrap_err();
+        assert_eq!(err.kind(), &ParseErrorKind::TooManyEdges { allowed: 2 });
+    }
+
+    #[test]
+    fn read_pace_hostile() {
+        // a 27 byte file asking for the largest capacity
+        let err = read_pace("p tw 16777216 1\n1 16777216\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseErrorKind::TooManyVertices {
+                given: 16777216,
+                allowed: 262144
+            }
+        );
+
+        // every edge to the last vertex grows a row across the whole capacity
+        let n = 262144;
+        let m = 5000;
+        let mut input = format!("p tw {} {}\n", n, m);
+        for u in 1..=m {
+            input.push_str(&format!("{} {}\n", u, n));
+        }
+        let err = read_pace(input.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseErrorKind::TooManyRowBits { allowed: 1 << 30 }
+        );
+        assert_eq!(err.line(), 4097);
+
+        let limits = ReadLimits {
+            max_row_bits: 128,
+            ..ReadLimits::default()
+        };
+        let c = read_pace_with_limits("p tw 64 1\n1 64\n".as_bytes(), &limits).unwrap();
+        assert_eq!(c.size(), 1);
+        let err = read_pace_with_limits("p tw 65 1\n1 65\n".as_bytes(), &limits).unwrap_err();
+        assert_eq!(err.kind(), &ParseErrorKind::TooManyRowBits { allowed: 128 });
+    }
+}
@@ -0,0 +1,647 @@
+//! Isomorphism invariants and tests
+
+use crate::BitGraph;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::{BitSetAnd, BitSetLike, BitSetNot};
+
+/// Generators and vertex orbits of the automorphism group of a graph
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Automorphisms {
+    /// Permutations generating the group, each maps vertex `v` onto `g[v]`
+    pub generators: Vec<Vec<u32>>,
+    /// The smallest vertex of the orbit of every vertex
+    pub orbits: Vec<u32>,
+}
+
+/// Combines `h` and `x` into a new 64 bit hash, independent of platform and Rust version
+fn mix(h: u64, x: u64) -> u64 {
+    let mut z = (h ^ x.rotate_left(23))
+        .wrapping_add(0x9e37_79b9_7f4a_7c15)
+        .wrapping_add(h << 6);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Hashes a multiset of colors
+fn hash_multiset(colors: &mut [u64]) -> u64 {
+    colors.sort_unstable();
+    colors.iter().fold(colors.len() as u64, |h, &c| mix(h, c))
+}
+
+impl BitGraph {
+    /// Colors after one round of color refinement: the old color of a vertex
+    /// combined with the multiset of the old colors of its neighbors
+    pub(crate) fn refine_colors(&self, colors: &[u64]) -> Vec<u64> {
+        let mut neighborhood = Vec::new();
+        (0..self.m_data.len() as u32)
+            .map(|v| {
+                neighborhood.clear();
+                neighborhood.extend(self.neighbors(v).map(|w| colors[w as usize]));
+                mix(colors[v as usize], hash_multiset(&mut neighborhood))
+            })
+            .collect()
+    }
+
+    /// Weisfeiler–Lehman hash, isomorphic graphs with the same capacity get the same value.
+    /// Starts with the degrees as colors, runs `iterations` rounds of color refinement and
+    /// hashes the multisets of colors of all rounds. Different graphs may collide, most
+    /// notably regular graphs of the same degree and size
+    pub fn wl_hash(&self, iterations: u32) -> u64 {
+        let mut colors: Vec<u64> = self.m_degrees.iter().map(|&d| d as u64).collect();
+        let mut hash = hash_multiset(&mut colors.clone());
+        for _ in 0..iterations {
+            colors = self.refine_colors(&colors);
+            hash = mix(hash, hash_multiset(&mut colors.clone()));
+        }
+        hash
+    }
+}
+
+impl BitGraph {
+    /// Returns `true` if `other` has the same capacity and there is a bijection of the
+    /// vertices that maps the edges of `self` exactly onto the edges of `other`
+    pub fn is_isomorphic(&self, other: &BitGraph) -> bool {
+        self.isomorphism(other).is_some()
+    }
+
+    /// Returns an isomorphism from `self` to `other` as the image of every vertex, `None` if
+    /// the graphs are not isomorphic. Degree sequences and color refinement reject most
+    /// non-isomorphic pairs quickly. Otherwise vertices are matched by backtracking, where the
+    /// candidates of a vertex are its color class in `other`, intersected with the row of the
+    /// image of an already matched neighbor
+    pub fn isomorphism(&self, other: &BitGraph) -> Option<Vec<u32>> {
+        let n = self.m_data.len();
+        if n != other.m_data.len() || self.size() != other.size() {
+            return None;
+        }
+        let mut colors: Vec<u64> = self.m_degrees.iter().map(|&d| d as u64).collect();
+        let mut other_colors: Vec<u64> = other.m_degrees.iter().map(|&d| d as u64).collect();
+        let mut classes = 0;
+        loop {
+            let mut a = colors.clone();
+            let mut b = other_colors.clone();
+            a.sort_unstable();
+            b.sort_unstable();
+            if a != b {
+                return None;
+            }
+            a.dedup();
+            if a.len() == classes {
+                break;
+            }
+            classes = a.len();
+            colors = self.refine_colors(&colors);
+            other_colors = other.refine_colors(&other_colors);
+        }
+
+        let mut class_members: BTreeMap<u64, hibitset::BitSet> = BTreeMap::new();
+        let mut class_size: BTreeMap<u64, u32> = BTreeMap::new();
+        for (v, &c) in other_colors.iter().enumerate() {
+            class_members
+                .entry(c)
+                .or_insert_with(|| hibitset::BitSet::with_capacity(n as u32))
+                .add(v as u32);
+            *class_size.entry(c).or_insert(0) += 1;
+        }
+        // match vertices with many matched neighbors and small classes first
+        let mut order: Vec<u32> = Vec::with_capacity(n);
+        let mut placed = vec![false; n];
+        let mut links = vec![0u32; n];
+        for _ in 0..n {
+            let v = (0..n)
+                .filter(|&v| !placed[v])
+                .min_by_key(|&v| (core::cmp::Reverse(links[v]), class_size[&colors[v]], v))
+                .unwrap();
+            placed[v] = true;
+            order.push(v as u32);
+            for w in self.neighbors(v as u32) {
+                links[w as usize] += 1;
+            }
+        }
+
+        let mut matcher = Matcher {
+            m_graph: self,
+            m_other: other,
+            m_colors: &colors,
+            m_classes: &class_members,
+            m_order: &order,
+            m_map: vec![u32::MAX; n],
+            m_mapped: hibitset::BitSet::with_capacity(n as u32),
+            m_used: hibitset::BitSet::with_capacity(n as u32),
+        };
+        if matcher.extend(0) {
+            Some(matcher.m_map)
+        } else {
+            None
+        }
+    }
+}
+
+impl BitGraph {
+    /// Returns a canonically relabeled copy of the graph and the permutation that produced it,
+    /// vertex `v` of `self` is vertex `perm[v]` of the copy. Two graphs of the same capacity are
+    /// isomorphic if and only if their canonical forms are equal, so the form can be used as a
+    /// `BTreeMap` key up to isomorphism.
+    /// Searches the tree of individualized and refined partitions for the leaf with the smallest
+    /// adjacency matrix, skipping branches that are mapped onto explored ones by automorphisms
+    pub fn canonical_form(&self) -> (BitGraph, Vec<u32>) {
+        let search = self.canonical_search();
+        let perm = search.m_best.unwrap().m_labels;
+        (self.relabel(&perm), perm)
+    }
+
+    /// Returns generators of the automorphism group and the orbits of the vertices under it.
+    /// The generators are the automorphisms met by the search behind `canonical_form`, which
+    /// prunes only branches that known automorphisms map onto explored ones
+    pub fn automorphisms(&self) -> Automorphisms {
+        let search = self.canonical_search();
+        let parent = search.orbits_fixing(&[]);
+        let orbits = (0..parent.len() as u32).map(|v| find(&parent, v)).collect();
+        Automorphisms {
+            generators: search.m_generators,
+            orbits,
+        }
+    }
+
+    fn canonical_search(&self) -> CanonicalSearch<'_> {
+        let mut search = CanonicalSearch {
+            m_graph: self,
+            m_first: None,
+            m_best: None,
+            m_generators: Vec::new(),
+        };
+        let isolated: Vec<u32> = (0..self.m_degrees.len() as u32)
+            .filter(|&v| self.m_degrees[v as usize] == 0)
+            .collect();
+        for pair in isolated.windows(2) {
+            let mut swap: Vec<u32> = (0..self.m_degrees.len() as u32).collect();
+            swap.swap(pair[0] as usize, pair[1] as usize);
+            search.m_generators.push(swap);
+        }
+        let degrees: Vec<u32> = self.m_degrees.clone();
+        let root = search.refine(degrees);
+        search.search(root, &mut Vec::new());
+        search
+    }
+}
+
+/// State of the individualization refinement search behind `BitGraph::canonical_form`.
+/// A partition is stored as the rank of the cell of every vertex
+struct CanonicalSearch<'a> {
+    m_graph: &'a BitGraph,
+    m_first: Option<Leaf>,
+    m_best: Option<Leaf>,
+    m_generators: Vec<Vec<u32>>,
+}
+
+/// Leaf of the search tree, with the relabeled adjacency matrix and the individualized vertices
+/// on the way to it
+#[derive(Clone)]
+struct Leaf {
+    m_matrix: Vec<u64>,
+    m_labels: Vec<u32>,
+    m_path: Vec<u32>,
+}
+
+impl<'a> CanonicalSearch<'a> {
+    /// Splits cells by the multiset of neighboring cells until the partition is equitable.
+    /// Cells are ranked by their old rank first, so the order of the cells is invariant
+    fn refine(&self, mut cells: Vec<u32>) -> Vec<u32> {
+        let n = cells.len();
+        let mut count = {
+            let mut distinct = cells.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            distinct.len()
+        };
+        loop {
+            let keys: Vec<(u32, Vec<u32>)> = (0..n as u32)
+                .map(|v| {
+                    let mut around: Vec<u32> = self
+                        .m_graph
+                        .neighbors(v)
+                        .map(|w| cells[w as usize])
+                        .collect();
+                    around.sort_unstable();
+                    (cells[v as usize], around)
+                })
+                .collect();
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+            let mut rank = 0;
+            for (i, &v) in order.iter().enumerate() {
+                if i > 0 && keys[v] != keys[order[i - 1]] {
+                    rank += 1;
+                }
+                cells[v] = rank;
+            }
+            let next = if n == 0 { 0 } else { rank as usize + 1 };
+            if next == count {
+                return cells;
+            }
+            count = next;
+        }
+    }
+
+    /// Explores the subtree below `prefix`. Returns `Some(depth)` when a leaf was mapped onto a
+    /// known leaf by an automorphism, every node between `depth` and that leaf is then equivalent
+    /// to an explored one and the search backs up to `depth`
+    fn search(&mut self, cells: Vec<u32>, prefix: &mut Vec<u32>) -> Option<usize> {
+        let n = cells.len();
+        let mut sizes = vec![0u32; n];
+        for &c in cells.iter() {
+            sizes[c as usize] += 1;
+        }
+        // isolated vertices are interchangeable, their cells are never split
+        let isolated = self.m_graph.m_degrees.iter().position(|&d| d == 0);
+        let isolated = isolated.map(|v| cells[v] as usize);
+        let target = match (0..n).find(|&c| sizes[c] > 1 && Some(c) != isolated) {
+            Some(c) => c as u32,
+            None => {
+                let mut offsets = vec![0u32; n];
+                for c in 1..n {
+                    offsets[c] = offsets[c - 1] + sizes[c - 1];
+                }
+                let labels: Vec<u32> = cells
+                    .iter()
+                    .map(|&c| {
+                        offsets[c as usize] += 1;
+                        offsets[c as usize] - 1
+                    })
+                    .collect();
+                return self.leaf(&labels, prefix);
+            }
+        };
+        let depth = prefix.len();
+        let mut explored: Vec<u32> = Vec::new();
+        let mut orbits: Option<(usize, Vec<u32>)> = None;
+        for v in (0..n as u32).filter(|&v| cells[v as usize] == target) {
+            if !explored.is_empty() {
+                if orbits
+                    .as_ref()
+                    .is_none_or(|(known, _)| *known != self.m_generators.len())
+                {
+                    orbits = Some((self.m_generators.len(), self.orbits_fixing(prefix)));
+                }
+                let parent = &orbits.as_ref().unwrap().1;
+                let root = find(parent, v);
+                if explored.iter().any(|&w| find(parent, w) == root) {
+                    continue;
+                }
+            }
+            explored.push(v);
+            // `v` becomes a cell of its own in front of the rest of its cell
+            let split: Vec<u32> = (0..n)
+                .map(|w| 2 * cells[w] + (cells[w] == target && w as u32 != v) as u32)
+                .collect();
+            prefix.push(v);
+            let refined = self.refine(split);
+            let jump = self.search(refined, prefix);
+            prefix.pop();
+            match jump {
+                Some(level) if level < depth => return jump,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn leaf(&mut self, labels: &[u32], path: &[u32]) -> Option<usize> {
+        let n = labels.len();
+        let words = n.div_ceil(64);
+        let mut matrix = vec![0u64; n * words];
+        for v in 0..n as u32 {
+            let row = labels[v as usize] as usize * words;
+            for w in self.m_graph.neighbors(v) {
+                let l = labels[w as usize] as usize;
+                matrix[row + l / 64] |= 1 << (l % 64);
+            }
+        }
+        for known in [&self.m_first, &self.m_best].iter().copied().flatten() {
+            if known.m_matrix == matrix {
+                // maps every vertex onto the vertex with the same label in the known leaf
+                let mut inverse = vec![0; n];
+                for (v, &x) in known.m_labels.iter().enumerate() {
+                    inverse[x as usize] = v as u32;
+                }
+                let automorphism: Vec<u32> = labels.iter().map(|&x| inverse[x as usize]).collect();
+                let common = path
+                    .iter()
+                    .zip(known.m_path.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if automorphism.iter().enumerate().any(|(v, &w)| v as u32 != w) {
+                    self.m_generators.push(automorphism);
+                }
+                return Some(common);
+            }
+        }
+        let leaf = Leaf {
+            m_matrix: matrix,
+            m_labels: labels.to_vec(),
+            m_path: path.to_vec(),
+        };
+        if self.m_first.is_none() {
+            self.m_first = Some(leaf.clone());
+        }
+        if self
+            .m_best
+            .as_ref()
+            .is_none_or(|best| leaf.m_matrix < best.m_matrix)
+        {
+            self.m_best = Some(leaf);
+        }
+        None
+    }
+
+    /// Orbits of the group generated by the known automorphisms that fix `prefix` pointwise,
+    /// as a union find forest
+    fn orbits_fixing(&self, prefix: &[u32]) -> Vec<u32> {
+        let n = self.m_graph.m_data.len();
+        let mut parent: Vec<u32> = (0..n as u32).collect();
+        for g in self
+            .m_generators
+            .iter()
+            .filter(|g| prefix.iter().all(|&v| g[v as usize] == v))
+        {
+            for (v, &w) in g.iter().enumerate() {
+                let (a, b) = (find(&parent, v as u32), find(&parent, w));
+                if a != b {
+                    parent[a.max(b) as usize] = a.min(b);
+                }
+            }
+        }
+        parent
+    }
+}
+
+fn find(parent: &[u32], mut v: u32) -> u32 {
+    while parent[v as usize] != v {
+        v = parent[v as usize];
+    }
+    v
+}
+
+/// Backtracking state of `BitGraph::isomorphism`
+struct Matcher<'a> {
+    m_graph: &'a BitGraph,
+    m_other: &'a BitGraph,
+    m_colors: &'a [u64],
+    m_classes: &'a BTreeMap<u64, hibitset::BitSet>,
+    m_order: &'a [u32],
+    m_map: Vec<u32>,
+    m_mapped: hibitset::BitSet,
+    m_used: hibitset::BitSet,
+}
+
+impl<'a> Matcher<'a> {
+    fn extend(&mut self, depth: usize) -> bool {
+        if depth == self.m_order.len() {
+            return true;
+        }
+        let u = self.m_order[depth];
+        let row = self.m_graph.row(u);
+        let matched: Vec<u32> = BitSetAnd(row, &self.m_mapped).iter().collect();
+        let class = &self.m_classes[&self.m_colors[u as usize]];
+        let candidates: Vec<u32> = match matched.first() {
+            Some(&p) => BitSetAnd(
+                BitSetAnd(class, self.m_other.row(self.m_map[p as usize])),
+                BitSetNot(&self.m_used),
+            )
+            .iter()
+            .collect(),
+            None => BitSetAnd(class, BitSetNot(&self.m_used)).iter().collect(),
+        };
+        for c in candidates {
+            let other_row = self.m_other.row(c);
+            let consistent = matched
+                .iter()
+                .all(|&p| other_row.contains(self.m_map[p as usize]))
+                && BitSetAnd(other_row, &self.m_used).iter().count() == matched.len();
+            if !consistent {
+                continue;
+            }
+            self.m_map[u as usize] = c;
+            self.m_mapped.add(u);
+            self.m_used.add(c);
+            if self.extend(depth + 1) {
+                return true;
+            }
+            self.m_mapped.remove(u);
+            self.m_used.remove(c);
+        }
+        self.m_map[u as usize] = u32::MAX;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::BitGraph;
+
+    #[test]
+    fn wl_hash() {
+        let c = generators::path(6);
+        let mut d = BitGraph::with_capacity(6);
+        for &(u, v) in [(3, 1), (1, 5), (5, 0), (0, 4), (4, 2)].iter() {
+            d.add_edge(u, v);
+        }
+        assert_eq!(c.wl_hash(3), d.wl_hash(3));
+        assert_ne!(c.wl_hash(3), generators::star(6).wl_hash(3));
+        assert_ne!(c.wl_hash(3), generators::path(7).wl_hash(3));
+        assert_ne!(c.wl_hash(0), c.wl_hash(1));
+
+        // a path with a tail at a second vertex vs. at a third one, same degree sequence
+        let mut x = BitGraph::with_capacity(7);
+        let mut y = BitGraph::with_capacity(7);
+        for v in 0..5 {
+            x.add_edge(v, v + 1);
+            y.add_edge(v, v + 1);
+        }
+        x.add_edge(1, 6);
+        y.add_edge(2, 6);
+        assert_eq!(x.wl_hash(0), y.wl_hash(0));
+        assert_ne!(x.wl_hash(2), y.wl_hash(2));
+
+        // two triangles and a 6-cycle are not told apart by color refinement
+        let mut two_triangles = BitGraph::with_capacity(6);
+        for &(u, v) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)].iter() {
+            two_triangles.add_edge(u, v);
+        }
+        assert_eq!(two_triangles.wl_hash(5), generators::cycle(6).wl_hash(5));
+    }
+
+    fn shuffled(c: &BitGraph, rng: &mut SplitMix64) -> (BitGraph, Vec<u32>) {
+        let n = c.m_degrees.len() as u32;
+        let mut perm: Vec<u32> = (0..n).collect();
+        for i in (1..perm.len()).rev() {
+            perm.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        let mut d = BitGraph::with_capacity(n);
+        for u in 0..n {
+            for v in c.neighbors(u).filter(|&v| u < v) {
+                d.add_edge(perm[u as usize], perm[v as usize]);
+            }
+        }
+        (d, perm)
+    }
+
+    fn check_isomorphism(c: &BitGraph, d: &BitGraph, map: &[u32]) {
+        for u in 0..c.m_degrees.len() as u32 {
+            let mut image: Vec<u32> = c.neighbors(u).map(|w| map[w as usize]).collect();
+            image.sort_unstable();
+            let tmp: Vec<u32> = d.neighbors(map[u as usize]).collect();
+            assert_eq!(image, tmp);
+        }
+    }
+
+    #[test]
+    fn is_isomorphic() {
+        let mut rng = SplitMix64::new(21);
+        let graphs = [
+            generators::gnp(40, 0.1, &mut rng),
+            generators::gnp(30, 0.5, &mut rng),
+            generators::named::petersen(),
+            generators::random_regular(24, 3, &mut rng),
+            generators::torus(5, 6, false),
+            generators::hypercube(5),
+            BitGraph::with_capacity(8),
+        ];
+        for c in graphs.iter() {
+            let (d, _) = shuffled(c, &mut rng);
+            let map = c.isomorphism(&d).unwrap();
+            check_isomorphism(c, &d, &map);
+            assert!(d.is_isomorphic(c));
+        }
+        let mut two_triangles = BitGraph::with_capacity(6);
+        for &(u, v) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)].iter() {
+            two_triangles.add_edge(u, v);
+        }
+        assert!(!two_triangles.is_isomorphic(&generators::cycle(6)));
+        assert!(!generators::path(5).is_isomorphic(&generators::path(6)));
+        assert!(!generators::path(5).is_isomorphic(&generators::star(5)));
+        let mut c = generators::cycle(8);
+        c.remove_edge(0, 1);
+        assert!(c.is_isomorphic(&generators::path(8)));
+        // the 4 x 4 rook's graph and the Shrikhande graph are both strongly regular
+        let mut rook = BitGraph::with_capacity(16);
+        let mut shrikhande = BitGraph::with_capacity(16);
+        for v in 0..16u32 {
+            let (r, c) = (v / 4, v % 4);
+            for w in 0..16u32 {
+                let (r2, c2) = (w / 4, w % 4);
+                if v < w && (r == r2 || c == c2) {
+                    rook.add_edge(v, w);
+                }
+                let (dr, dc) = ((r2 + 4 - r) % 4, (c2 + 4 - c) % 4);
+                if v < w && [(0, 1), (0, 3), (1, 0), (3, 0), (1, 1), (3, 3)].contains(&(dr, dc)) {
+                    shrikhande.add_edge(v, w);
+                }
+            }
+        }
+        assert_eq!(rook.wl_hash(4), shrikhande.wl_hash(4));
+        assert!(!rook.is_isomorphic(&shrikhande));
+        let (d, _) = shuffled(&shrikhande, &mut rng);
+        assert!(d.is_isomorphic(&shrikhande));
+    }
+
+    #[test]
+    // the invariant cache is interior mutable, but it takes no part in `Hash` and `Eq`
+    #[allow(clippy::mutable_key_type)]
+    fn canonical_form() {
+        let mut rng = SplitMix64::new(22);
+        let graphs = [
+            generators::gnp(30, 0.2, &mut rng),
+            generators::named::petersen(),
+            generators::hypercube(4),
+            generators::complete_bipartite(3, 4),
+            generators::grid(3, 3, false),
+            BitGraph::with_capacity(12),
+            BitGraph::complete(9),
+        ];
+        let mut forms = std::collections::HashSet::new();
+        for c in graphs.iter() {
+            let (form, perm) = c.canonical_form();
+            assert_eq!(form, c.relabel(&perm));
+            for _ in 0..3 {
+                let (d, _) = shuffled(c, &mut rng);
+                assert_eq!(d.canonical_form().0, form);
+            }
+            assert!(forms.insert(form));
+        }
+        let mut two_triangles = BitGraph::with_capacity(6);
+        for &(u, v) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)].iter() {
+            two_triangles.add_edge(u, v);
+        }
+        assert_ne!(
+            two_triangles.canonical_form().0,
+            generators::cycle(6).canonical_form().0
+        );
+        assert_eq!(BitGraph::with_capacity(0).canonical_form().1, vec![]);
+    }
+
+    fn group_order(generators: &[Vec<u32>], n: u32) -> usize {
+        let mut group = std::collections::HashSet::new();
+        let mut stack: Vec<Vec<u32>> = vec![(0..n).collect()];
+        while let Some(p) = stack.pop() {
+            if !group.insert(p.clone()) {
+                continue;
+            }
+            for g in generators.iter() {
+                stack.push(p.iter().map(|&v| g[v as usize]).collect());
+            }
+        }
+        group.len()
+    }
+
+    #[test]
+    fn automorphisms() {
+        let graphs = [
+            (generators::named::petersen(), 120),
+            (generators::cycle(7), 14),
+            (generators::hypercube(3), 48),
+            (generators::complete_bipartite(2, 3), 12),
+            (generators::path(6), 2),
+            (BitGraph::with_capacity(5), 120),
+            (BitGraph::complete(5), 120),
+        ];
+        for (c, order) in graphs.iter() {
+            let n = c.m_degrees.len() as u32;
+            let aut = c.automorphisms();
+            for g in aut.generators.iter() {
+                check_isomorphism(c, c, g);
+            }
+            assert_eq!(group_order(&aut.generators, n), *order);
+        }
+        assert_eq!(
+            generators::path(5).automorphisms().orbits,
+            vec![0, 1, 2, 1, 0]
+        );
+        assert_eq!(
+            generators::named::petersen().automorphisms().orbits,
+            vec![0; 10]
+        );
+
+        // a triangle with a pendant path of two edges and two isolated vertices
+        let mut c = BitGraph::with_capacity(7);
+        for &(u, v) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)].iter() {
+            c.add_edge(u, v);
+        }
+        let aut = c.automorphisms();
+        assert_eq!(aut.orbits, vec![0, 0, 2, 3, 4, 5, 5]);
+        assert_eq!(group_order(&aut.generators, 7), 4);
+        // the smallest tree without symmetries, branches of length 1, 2 and 3 at vertex 2
+        let mut rigid = BitGraph::with_capacity(7);
+        for &(u, v) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (2, 6)].iter() {
+            rigid.add_edge(u, v);
+        }
+        let aut = rigid.automorphisms();
+        assert!(aut.generators.is_empty());
+        assert_eq!(aut.orbits, (0..7).collect::<Vec<u32>>());
+    }
+}
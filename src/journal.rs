@@ -0,0 +1,385 @@
+//! Opt-in journal of the mutations, for undo, redo and rollback
+//!
+//! Single edge edits record the bits they flip. Word level edits record the whole row before
+//! its first change in a mutation, which costs no copy beyond the one copy on write already
+//! makes. Undoing a change turns it into the change that redoes it and the other way around,
+//! so both directions replay the same way.
+
+use crate::{BitGraph, VertexId};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::mem;
+
+/// Position in the journal of a graph, returned by `BitGraph::checkpoint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checkpoint(usize);
+
+#[derive(Debug, Clone)]
+enum Change {
+    /// Bit `bit` of the row of `row` was set if `added` and cleared otherwise
+    Bit { row: u32, bit: u32, added: bool },
+    /// The row of `vertex` was `row` before
+    Row {
+        vertex: u32,
+        row: Arc<hibitset::BitSet>,
+    },
+    /// The capacity changed from `from` to `to`
+    Resize { from: u32, to: u32 },
+}
+
+/// Changes of the mutations that can be undone and of the undone ones that can be redone,
+/// the changes of mutation `i` are `m_changes[m_starts[i]..m_starts[i + 1]]`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Journal {
+    m_changes: Vec<Change>,
+    m_starts: Vec<usize>,
+    m_undone: Vec<Change>,
+    m_undone_starts: Vec<usize>,
+    m_sealed: bool,
+    m_saved: hibitset::BitSet,
+}
+
+impl Journal {
+    fn new() -> Journal {
+        Journal {
+            m_sealed: true,
+            ..Default::default()
+        }
+    }
+
+    /// Starts a new mutation with the first change after the journal was sealed
+    fn record(&mut self, change: Change) {
+        if self.m_sealed {
+            self.m_sealed = false;
+            self.m_starts.push(self.m_changes.len());
+            self.m_saved.clear();
+            self.m_undone.clear();
+            self.m_undone_starts.clear();
+        }
+        self.m_changes.push(change);
+    }
+
+    /// Bytes held by the journal, rows shared with the graph included
+    pub(crate) fn memory_usage(&self) -> usize {
+        let rows: usize = self
+            .m_changes
+            .iter()
+            .chain(self.m_undone.iter())
+            .map(|change| match change {
+                Change::Row { row, .. } => {
+                    mem::size_of::<hibitset::BitSet>() + mem::size_of_val(row.layer0_as_slice())
+                }
+                _ => 0,
+            })
+            .sum();
+        mem::size_of::<Journal>()
+            + (self.m_changes.capacity() + self.m_undone.capacity()) * mem::size_of::<Change>()
+            + (self.m_starts.capacity() + self.m_undone_starts.capacity()) * mem::size_of::<usize>()
+            + mem::size_of_val(self.m_saved.layer0_as_slice())
+            + rows
+    }
+}
+
+impl BitGraph {
+    /// Creates a new BitGraph with `capacity` vertices and no edges, which journals all
+    /// mutations. See `enable_journal`
+    pub fn with_journal(capacity: u32) -> BitGraph {
+        let mut graph = BitGraph::with_capacity(capacity);
+        graph.enable_journal();
+        graph
+    }
+
+    /// Enables the journal, from now on every mutation can be undone. A mutation is one call of
+    /// a mutating method that changed the graph, a contraction or a bulk edit count as one
+    pub fn enable_journal(&mut self) {
+        if self.m_journal.is_none() {
+            self.m_journal = Some(Journal::new());
+        }
+    }
+
+    /// Disables the journal and drops it
+    pub fn disable_journal(&mut self) {
+        self.m_journal = None;
+    }
+
+    /// Returns `true` if the journal is enabled
+    pub fn has_journal(&self) -> bool {
+        self.m_journal.is_some()
+    }
+
+    /// Returns the current position in the journal, to go back to with `rollback_to`
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let journal = self.journal_mut();
+        journal.m_sealed = true;
+        Checkpoint(journal.m_starts.len())
+    }
+
+    /// Undoes all mutations since `checkpoint` was taken. Panics if more mutations were undone
+    /// in the meantime
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        let len = self.journal_mut().m_starts.len();
+        if checkpoint.0 > len {
+            panic!(
+                "Checkpoint {} is not in the journal, it has {} mutations",
+                checkpoint.0, len
+            )
+        }
+        self.undo(len - checkpoint.0);
+    }
+
+    /// Undoes the last `n` mutations, or all of them if there are fewer.
+    /// Returns the number of undone mutations
+    pub fn undo(&mut self, n: usize) -> usize {
+        let mut count = 0;
+        while count < n {
+            let journal = self.journal_mut();
+            journal.m_sealed = true;
+            let start = match journal.m_starts.pop() {
+                Some(start) => start,
+                None => break,
+            };
+            let changes = journal.m_changes.split_off(start);
+            journal.m_undone_starts.push(journal.m_undone.len());
+            let redo = self.replay(changes);
+            self.journal_mut().m_undone.extend(redo);
+            count += 1;
+        }
+        count
+    }
+
+    /// Redoes the last `n` undone mutations, or all of them if there are fewer. Mutating the
+    /// graph drops the undone mutations. Returns the number of redone mutations
+    pub fn redo(&mut self, n: usize) -> usize {
+        let mut count = 0;
+        while count < n {
+            let journal = self.journal_mut();
+            journal.m_sealed = true;
+            let start = match journal.m_undone_starts.pop() {
+                Some(start) => start,
+                None => break,
+            };
+            let changes = journal.m_undone.split_off(start);
+            journal.m_starts.push(journal.m_changes.len());
+            let undo = self.replay(changes);
+            self.journal_mut().m_changes.extend(undo);
+            count += 1;
+        }
+        count
+    }
+
+    /// Reverts `changes` from last to first and returns the changes that revert that again
+    fn replay(&mut self, changes: Vec<Change>) -> Vec<Change> {
+        let mut touched = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        let mut reverted = Vec::with_capacity(changes.len());
+        for change in changes.into_iter().rev() {
+            reverted.push(match change {
+                Change::Bit { row, bit, added } => {
+                    let set = Arc::make_mut(&mut self.m_data[row as usize]);
+                    if added {
+                        set.remove(bit);
+                    } else {
+                        set.add(bit);
+                    }
+                    touched.add(row);
+                    Change::Bit {
+                        row,
+                        bit,
+                        added: !added,
+                    }
+                }
+                Change::Row { vertex, row } => {
+                    touched.add(vertex);
+                    Change::Row {
+                        vertex,
+                        row: mem::replace(&mut self.m_data[vertex as usize], row),
+                    }
+                }
+                Change::Resize { from, to } => {
+                    // a resize is a mutation of its own, so no row of it is left to recount
+                    if from < to {
+                        self.m_data.truncate(from as usize);
+                        self.m_degrees.truncate(from as usize);
+                    } else {
+                        let empty = Arc::new(hibitset::BitSet::new());
+                        self.m_data.resize(from as usize, empty);
+                        self.m_degrees.resize(from as usize, 0);
+                    }
+                    if self.has_degree_buckets() {
+                        self.disable_degree_buckets();
+                        self.enable_degree_buckets();
+                    }
+                    Change::Resize { from: to, to: from }
+                }
+            });
+        }
+        self.recount(&touched);
+        reverted
+    }
+
+    fn journal_mut(&mut self) -> &mut Journal {
+        match self.m_journal.as_mut() {
+            Some(journal) => journal,
+            None => panic!("The journal is not enabled"),
+        }
+    }
+
+    /// Hook called after the capacity changed from `from` to `to`
+    pub(crate) fn journal_resize(&mut self, from: u32, to: u32) {
+        if let Some(journal) = self.m_journal.as_mut() {
+            journal.m_sealed = true;
+            journal.record(Change::Resize { from, to });
+            journal.m_sealed = true;
+        }
+    }
+}
+
+impl<I: VertexId> BitGraph<I> {
+    /// Hook called when a mutating method starts, its changes form a new mutation
+    #[inline]
+    pub(crate) fn journal_begin(&mut self) {
+        if let Some(journal) = self.m_journal.as_mut() {
+            journal.m_sealed = true;
+        }
+    }
+
+    /// Hook called after bit `bit` of the row of `row` was flipped
+    #[inline]
+    pub(crate) fn journal_bit(&mut self, row: u32, bit: u32, added: bool) {
+        if let Some(journal) = self.m_journal.as_mut() {
+            journal.record(Change::Bit { row, bit, added });
+        }
+    }
+
+    /// Hook called before the row of `v` is edited word by word
+    #[inline]
+    pub(crate) fn journal_row(&mut self, v: u32) {
+        if let Some(journal) = self.m_journal.as_mut() {
+            if journal.m_sealed || !journal.m_saved.contains(v) {
+                journal.record(Change::Row {
+                    vertex: v,
+                    row: Arc::clone(&self.m_data[v as usize]),
+                });
+                journal.m_saved.add(v);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::BitGraph;
+
+    #[test]
+    fn undo_and_redo() {
+        let capacity: usize = 6;
+        let mut c = BitGraph::with_journal(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(1, 2);
+        c.remove_edge(3, 4);
+        c.add_edge(2, 3);
+        let path = c.clone();
+        c.contract_edge(1, 2);
+        assert_eq!(c.size(), 2);
+        assert_eq!(c.undo(1), 1);
+        assert_eq!(c, path);
+        assert_eq!(c.undo(1), 1);
+        assert_eq!(c.size(), 2);
+        assert_eq!(c.order(), 3);
+        assert_eq!(c.redo(5), 2);
+        assert_eq!(c.size(), 2);
+        assert!(c.row(1).contains(3));
+        assert_eq!(c.undo(10), 4);
+        assert_eq!(c, BitGraph::with_capacity(capacity as u32));
+        assert_eq!(c.order(), 0);
+        assert_eq!(c.undo(1), 0);
+
+        // a new mutation drops what was undone
+        c.redo(1);
+        c.add_edge(4, 5);
+        assert_eq!(c.redo(1), 0);
+        assert_eq!(c.size(), 2);
+    }
+
+    #[test]
+    fn rollback_to_checkpoint() {
+        let capacity: usize = 40;
+        let mut rng = SplitMix64::new(65);
+        let mut c = generators::gnp(capacity as u32, 0.2, &mut rng);
+        c.enable_journal();
+        c.enable_invariant_cache();
+        c.enable_degree_buckets();
+        let start = c.clone();
+        let triangles = c.triangle_count();
+        let checkpoint = c.checkpoint();
+        let mut snapshots = vec![(c.checkpoint(), c.clone())];
+        for step in 0..300 {
+            let u = rng.below(capacity as u64) as u32;
+            let v = rng.below(capacity as u64) as u32;
+            if u == v {
+                continue;
+            }
+            match step % 12 {
+                0 if c.row(u).contains(v) => c.contract_edge(u, v),
+                1 => c.clear_vertex(u),
+                2 => {
+                    c.eliminate_vertex(u);
+                }
+                3 => c.retain_edges(|a, b| (a + b) % 3 != 0),
+                4 => c.add_edges(&[(u, v), (v, (v + 1) % capacity as u32)]),
+                5 => {
+                    c.neighbors_set_mut(u).add(v);
+                }
+                6 => c.union_with(&start),
+                7 => c.difference_with(&start),
+                8 => c.add_path(&[u, v]),
+                9 if step % 5 == 0 => c.clear(),
+                10 => {
+                    let mut set = hibitset::BitSet::new();
+                    set.add(u);
+                    set.add(v);
+                    set.add((u + 2) % capacity as u32);
+                    c.add_clique(&set);
+                }
+                _ => c.remove_edge(u, v),
+            }
+            snapshots.push((c.checkpoint(), c.clone()));
+        }
+        for (checkpoint, snapshot) in snapshots.into_iter().rev() {
+            c.rollback_to(checkpoint);
+            assert_eq!(c, snapshot);
+            assert_eq!(c.order(), snapshot.order());
+            assert_eq!(c.max_degree(), snapshot.max_degree());
+            assert_eq!(
+                c.max_degree_vertex().map(|v| c.degree(v)),
+                snapshot.max_degree_vertex().map(|v| snapshot.degree(v))
+            );
+        }
+        c.rollback_to(checkpoint);
+        assert_eq!(c, start);
+        assert_eq!(c.triangle_count(), triangles);
+        c.redo(usize::MAX);
+        assert_eq!(c.undo(usize::MAX), c.redo(usize::MAX));
+
+        // growing is a mutation of its own
+        let mut d = BitGraph::with_journal(3);
+        d.add_edge(0, 1);
+        d.grow_to(5);
+        d.add_edge(1, 4);
+        d.grow_to(4);
+        assert_eq!(d.undo(2), 2);
+        assert_eq!(d.capacity(), 3);
+        assert_eq!(d.size(), 1);
+        assert_eq!(d.redo(1), 1);
+        assert_eq!(d.capacity(), 5);
+        assert_eq!(d.size(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn undo_without_journal() {
+        BitGraph::with_capacity(3).undo(1);
+    }
+}
@@ -0,0 +1,204 @@
+//! Arbitrary vertex labels on top of a `BitGraph`
+
+use crate::{BitGraph, Edges, MAX_CAPACITY};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use hibitset::BitIter;
+
+/// A `BitGraph` whose vertices are named by labels of any ordered type. Every new label gets
+/// the next free vertex, and the capacity doubles when it runs out
+#[derive(Debug, Clone)]
+pub struct LabeledBitGraph<T: Ord + Clone> {
+    m_graph: BitGraph,
+    m_ids: BTreeMap<T, u32>,
+    m_labels: Vec<T>,
+}
+
+impl<T: Ord + Clone> LabeledBitGraph<T> {
+    /// Creates a new LabeledBitGraph without vertices
+    pub fn new() -> LabeledBitGraph<T> {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new LabeledBitGraph with room for `capacity` labels before it has to grow
+    pub fn with_capacity(capacity: u32) -> LabeledBitGraph<T> {
+        LabeledBitGraph {
+            m_graph: BitGraph::with_capacity(capacity),
+            m_ids: BTreeMap::new(),
+            m_labels: Vec::new(),
+        }
+    }
+
+    /// Returns the vertex of `label`, which is added if it is new
+    pub fn add_vertex(&mut self, label: &T) -> u32 {
+        if let Some(&id) = self.m_ids.get(label) {
+            return id;
+        }
+        let id = self.m_labels.len() as u32;
+        if id == self.m_graph.capacity() {
+            let grown = (2 * id).clamp(8, MAX_CAPACITY as u32);
+            self.m_graph.grow_to(grown.max(id + 1));
+        }
+        self.m_ids.insert(label.clone(), id);
+        self.m_labels.push(label.clone());
+        id
+    }
+
+    /// Adds a new undirected edge between the vertices of `u` and `v`, new labels are added
+    pub fn add_edge(&mut self, u: &T, v: &T) {
+        let (u, v) = (self.add_vertex(u), self.add_vertex(v));
+        self.m_graph.add_edge(u, v);
+    }
+
+    /// Removes the edge between `u` and `v` if both labels are known and the edge is present
+    pub fn remove_edge(&mut self, u: &T, v: &T) {
+        if let (Some(u), Some(v)) = (self.id(u), self.id(v)) {
+            self.m_graph.remove_edge(u, v);
+        }
+    }
+
+    /// Returns `true` if `u` and `v` are known and adjacent
+    pub fn has_edge(&self, u: &T, v: &T) -> bool {
+        match (self.id(u), self.id(v)) {
+            (Some(u), Some(v)) => self.m_graph.row(u).contains(v),
+            _ => false,
+        }
+    }
+
+    /// Number of neighbors of `label`, 0 if it is unknown
+    pub fn degree(&self, label: &T) -> u32 {
+        self.id(label).map_or(0, |v| self.m_graph.degree(v))
+    }
+
+    /// Returns an iterator over the labels of the neighbors of `label`, which has to be known
+    pub fn neighbors(&self, label: &T) -> LabeledNeighbors<'_, T> {
+        let v = match self.id(label) {
+            Some(v) => v,
+            None => panic!("Unknown label"),
+        };
+        LabeledNeighbors {
+            m_labels: &self.m_labels,
+            m_neighbors: self.m_graph.neighbors(v),
+        }
+    }
+
+    /// Returns an iterator over all edges as pairs of labels, each edge once
+    pub fn edges(&self) -> LabeledEdges<'_, T> {
+        LabeledEdges {
+            m_labels: &self.m_labels,
+            m_edges: self.m_graph.edges(),
+        }
+    }
+
+    /// Vertex of `label`, `None` if it is unknown
+    pub fn id(&self, label: &T) -> Option<u32> {
+        self.m_ids.get(label).copied()
+    }
+
+    /// Label of vertex `v`, `None` if no label was added for it
+    pub fn label(&self, v: u32) -> Option<&T> {
+        self.m_labels.get(v as usize)
+    }
+
+    /// Labels of the vertices in `ids`, to translate results of the algorithms on `graph`.
+    /// Panics on a vertex without label
+    pub fn labels<I: IntoIterator<Item = u32>>(&self, ids: I) -> Vec<&T> {
+        ids.into_iter()
+            .map(|v| &self.m_labels[v as usize])
+            .collect()
+    }
+
+    /// Number of labels
+    pub fn len(&self) -> usize {
+        self.m_labels.len()
+    }
+
+    /// Returns `true` if no labels were added
+    pub fn is_empty(&self) -> bool {
+        self.m_labels.is_empty()
+    }
+
+    /// The underlying graph, whose vertex `v` is named `label(v)`. Its capacity can be larger
+    /// than the number of labels
+    pub fn graph(&self) -> &BitGraph {
+        &self.m_graph
+    }
+}
+
+impl<T: Ord + Clone> Default for LabeledBitGraph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over the labels of the neighbors of a vertex, created by `LabeledBitGraph::neighbors`
+pub struct LabeledNeighbors<'a, T> {
+    m_labels: &'a [T],
+    m_neighbors: BitIter<&'a hibitset::BitSet>,
+}
+
+impl<'a, T> Iterator for LabeledNeighbors<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.m_neighbors.next().map(|w| &self.m_labels[w as usize])
+    }
+}
+
+/// Iterator over the edges as pairs of labels, created by `LabeledBitGraph::edges`
+pub struct LabeledEdges<'a, T> {
+    m_labels: &'a [T],
+    m_edges: Edges<'a>,
+}
+
+impl<'a, T> Iterator for LabeledEdges<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (u, v) = self.m_edges.next()?;
+        Some((&self.m_labels[u as usize], &self.m_labels[v as usize]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LabeledBitGraph;
+
+    #[test]
+    fn labeled_graph() {
+        let mut c: LabeledBitGraph<String> = LabeledBitGraph::new();
+        let names = ["ada", "bob", "cyd", "dot", "eve"];
+        let name = |i: usize| names[i].to_string();
+        for i in 0..4 {
+            c.add_edge(&name(i), &name(i + 1));
+        }
+        c.add_edge(&name(4), &name(0));
+        assert_eq!(c.len(), 5);
+        assert_eq!(c.id(&name(2)), Some(2));
+        assert_eq!(c.label(3), Some(&name(3)));
+        assert!(c.has_edge(&name(0), &name(4)));
+        assert!(!c.has_edge(&name(0), &"zed".to_string()));
+        assert_eq!(c.degree(&name(1)), 2);
+        let mut tmp: Vec<&String> = c.neighbors(&name(0)).collect();
+        tmp.sort();
+        assert_eq!(tmp, vec!["bob", "eve"]);
+
+        c.remove_edge(&name(2), &name(3));
+        let path = c.graph().shortest_path(2, 3).unwrap();
+        assert_eq!(c.labels(path), vec!["cyd", "bob", "ada", "eve", "dot"]);
+        assert_eq!(c.edges().count(), 4);
+        assert!(c.edges().any(|(u, v)| u == "ada" && v == "eve"));
+
+        // growing keeps the numbering
+        let mut d: LabeledBitGraph<u64> = LabeledBitGraph::with_capacity(2);
+        for i in 0..100u64 {
+            d.add_edge(&(i * 1000), &(i * 1000 + 7));
+        }
+        assert_eq!(d.len(), 200);
+        assert!(d.graph().capacity() >= 200);
+        assert_eq!(d.graph().size(), 100);
+        assert_eq!(d.id(&99_007), Some(199));
+        assert_eq!(d.add_vertex(&0), 0);
+        assert!(LabeledBitGraph::<u8>::default().is_empty());
+    }
+}
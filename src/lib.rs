@@ -3,7 +3,7 @@
 // Provides a very fast and space-efficient graph data structure for specific use cases.
 // When to use:
 //  - You know the maximum size your graph can take
-//  - You have at most `mem::size_of::<usize>.pow(4)` vertices
+//  - You have at most `MAX_CAPACITY` vertices, 2^24 on 64-bit targets
 //  - Your graph is undirected and has no values/weights associated with vertices or edges
 //
 // Provided Functionality:
@@ -11,47 +11,193 @@
 //  - Fast DFS Iteration
 //  - Fast edge contractions
 //
+// Features:
+//  - `std`, enabled by default, provides the `io` module, `ConcurrentBitGraph` and the
+//    functions that need floating point logarithms or roots (`generators::gnp`,
+//    `eigenvector_centrality`). Without it the crate is `no_std` and only needs `alloc`.
+//    The bitsets come from `hibitgraph-hibitset`, hibitset 0.6.4 with a `std` feature, which
+//    lives in `hibitset/` and is published alongside
+//  - `ffi` provides the C interface of the `ffi` module, declared in `include/hibitgraph.h`,
+//    and enables `std`
+//
 // Internally the graph stores a vector containing multiple [hibitset::BitSet](https://docs.rs/hibitset/0.6.3/hibitset/struct.BitSet.html)
 
-use bit_set;
-use hibitset;
-use hibitset::{BitIter, BitSetLike, DrainableBitSet};
-use std::mem;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem;
+use hibitset::{BitIter, BitSetAnd, BitSetLike, BitSetNot, DrainableBitSet};
+
+use buckets::DegreeBuckets;
+use invariants::InvariantCache;
+use journal::Journal;
+
+pub mod algorithms;
+mod array;
+mod bipartite;
+mod buckets;
+mod bulk;
+mod centrality;
+mod communities;
+#[cfg(feature = "std")]
+mod concurrent;
+mod cycles;
+mod diff;
+mod digraph;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filtered;
+mod frozen;
+pub mod generators;
+mod graph;
+mod guard;
+mod hybrid;
+mod invariants;
+#[cfg(feature = "std")]
+pub mod io;
+mod isomorphism;
+mod journal;
+mod labeled;
+mod loops;
+mod masked;
+mod matrix;
+mod motifs;
+mod multigraph;
+mod observer;
+mod ordering;
+mod paths;
+mod planarity;
+pub mod random;
+mod setops;
+mod stats;
+mod subgraph;
+pub mod traverse;
+mod trees;
+mod triangle;
+mod truss;
+mod vertex;
+mod view;
+mod weighted;
 
-const MAX_CAPACITY: usize = mem::size_of::<usize>()
-    * mem::size_of::<usize>()
-    * mem::size_of::<usize>()
-    * mem::size_of::<usize>();
+pub use array::{
+    ArrayBitGraph, ArrayBitGraph128, ArrayBitGraph256, ArrayBitGraph64, ArrayDfs, ArrayEdges,
+    ArrayNeighbors,
+};
+pub use bipartite::{BitBipartiteGraph, Side};
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentBitGraph;
+pub use cycles::{SimpleCycles, HELD_KARP_LIMIT};
+pub use diff::GraphDiff;
+pub use digraph::{Arcs, BitDiGraph, DiBfsIterator, DiDfsIterator, UnderlyingGraph};
+pub use filtered::{FilteredEdges, FilteredGraph, FilteredNeighbors};
+pub use frozen::FrozenBitGraph;
+pub use graph::UndirectedGraph;
+pub use guard::{NeighborsGuard, NeighborsMut};
+pub use hybrid::{HybridBitGraph, HybridEdges, HybridNeighbors};
+pub use isomorphism::Automorphisms;
+pub use journal::Checkpoint;
+pub use labeled::{LabeledBitGraph, LabeledEdges, LabeledNeighbors};
+pub use loops::{LoopBitGraph, LoopEdges, LoopNeighbors};
+pub use masked::{SubgraphDfs, SubgraphEdges, SubgraphNeighbors, SubgraphView};
+pub use matrix::{MatrixBitGraph, MatrixEdges};
+pub use motifs::{Motifs4, GRAPHLET_ORBITS};
+pub use multigraph::BitMultiGraph;
+pub use observer::{GraphObserver, ObservedBitGraph};
+pub use stats::GraphStats;
+pub use subgraph::SubgraphMatches;
+pub use triangle::{TriangleBitGraph, TriangleEdges, TriangleNeighbors};
+pub use vertex::{VertexId, VertexIds};
+pub use view::BitGraphView;
+pub use weighted::{WeightedBitGraph, WeightedEdges};
+
+/// Largest capacity of a graph, the number of bits a `hibitset::BitSet` can address: the bits
+/// of a `usize` to the power of its four layers, 16777216 on 64-bit targets. Rows are only
+/// allocated as far as they are used, so in practice memory is the limit
+pub const MAX_CAPACITY: usize = (mem::size_of::<usize>() * 8).pow(4);
 
 /// A `BitGraph` is an undirected graph data structure
-/// Its capacity is limited to `mem::size_of::<usize>.pow(4)`
+/// Its capacity is limited to `MAX_CAPACITY`
+///
+/// Vertices are addressed by `I`, `u16`, `u32` or `usize`, and the degrees are stored in the same
+/// type. The core methods are available for every index type, the rest of the API uses `u32`.
+/// `usize` does not raise the capacity beyond `MAX_CAPACITY`, it only saves conversions
+///
+/// Rows are reference counted and copied on write, so taking a `NeighborsGuard`
+/// is cheap and the row is only copied once the graph modifies it afterwards.
+/// For the same reason `clone` only copies the degrees and one pointer per row,
+/// and each copy writes its own version of a row the first time it modifies it.
+/// A row is only allocated with its first edge and holds words up to its largest
+/// neighbor, so a large capacity with few active vertices costs little memory
 #[derive(Debug, Clone)]
-pub struct BitGraph {
-    m_data: Vec<hibitset::BitSet>,
-    m_degrees: Vec<u32>,
+pub struct BitGraph<I: VertexId = u32> {
+    m_data: Vec<Arc<hibitset::BitSet>>,
+    m_degrees: Vec<I>,
     m_order: u32,
+    m_cache: Option<InvariantCache>,
+    m_buckets: Option<DegreeBuckets>,
+    m_journal: Option<Journal>,
 }
 
-impl BitGraph {
-    /// Creates a new BitGraph preallocated with up to `capacity` vertices
-    /// It is not possible later add vertices >= `capacity`
-    pub fn with_capacity(capacity: u32) -> BitGraph {
-        Self::check_capacity(capacity);
-        BitGraph {
-            m_data: vec![hibitset::BitSet::with_capacity(capacity); capacity as usize],
-            m_degrees: vec![0; capacity as usize],
-            m_order: 0,
+/// Bottom layer words of `row` without trailing zero words
+fn row_words(row: &hibitset::BitSet) -> &[usize] {
+    let words = row.layer0_as_slice();
+    let len = words.iter().rposition(|&w| w != 0).map_or(0, |i| i + 1);
+    &words[..len]
+}
+
+/// Graphs are equal if they have the same capacity and the same edges
+impl<I: VertexId> PartialEq for BitGraph<I> {
+    fn eq(&self, other: &BitGraph<I>) -> bool {
+        self.m_data.len() == other.m_data.len()
+            && self.m_degrees == other.m_degrees
+            && self
+                .m_data
+                .iter()
+                .zip(other.m_data.iter())
+                .all(|(a, b)| row_words(a) == row_words(b))
+    }
+}
+
+impl<I: VertexId> Eq for BitGraph<I> {}
+
+impl<I: VertexId> Hash for BitGraph<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.m_degrees.hash(state);
+        for row in self.m_data.iter() {
+            row_words(row).hash(state);
         }
     }
+}
 
-    #[inline]
-    fn check_capacity(capacity: u32) {
-        if capacity > MAX_CAPACITY as u32 {
+impl<I: VertexId> BitGraph<I> {
+    /// Creates a new graph preallocated with up to `capacity` vertices addressed by `I`, as in
+    /// `BitGraph::<u16>::indexed(1000)`. For the default `u32` this is `with_capacity`
+    pub fn indexed(capacity: I) -> BitGraph<I> {
+        let allowed = MAX_CAPACITY.min(I::LIMIT);
+        if capacity.index() > allowed {
             panic!(
                 "Out of bounds. Given: {}, Allowed: {}",
-                capacity, MAX_CAPACITY
+                capacity.index(),
+                allowed
             )
         }
+        // all rows share one empty set without storage until they are first written, and a
+        // written row only grows as far as its largest neighbor
+        let empty = Arc::new(hibitset::BitSet::new());
+        BitGraph {
+            m_data: vec![empty; capacity.index()],
+            m_degrees: vec![I::default(); capacity.index()],
+            m_order: 0,
+            m_cache: None,
+            m_buckets: None,
+            m_journal: None,
+        }
     }
 
     #[inline]
@@ -72,90 +218,320 @@ impl BitGraph {
         }
     }
 
+    /// Adds a new undirected edge from `u` to `v`
+    /// If the edge already exists, the graph is not updated
+    /// It is not possible to add edges with endpoints >= `capacity`
+    pub fn add_edge(&mut self, u: I, v: I) {
+        let (u, v) = (u.index() as u32, v.index() as u32);
+        self.journal_begin();
+        self.check_bounds(v);
+        self.check_bounds(u);
+        self.check_is_same(u, v);
+        if self.add_endpoint(u, v) {
+            self.add_endpoint(v, u);
+            self.edge_added(u, v);
+        }
+    }
+
+    /// Removes the edge from `u` to `v` after performing boundary checks.
+    /// If the edge is not present the graph is not updated
+    pub fn remove_edge(&mut self, u: I, v: I) {
+        let (u, v) = (u.index() as u32, v.index() as u32);
+        self.journal_begin();
+        self.check_bounds(v);
+        self.check_bounds(u);
+        self.check_is_same(u, v);
+        if self.remove_endpoint(u, v) {
+            self.remove_endpoint(v, u);
+            self.edge_removed(u, v);
+        }
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent
+    pub fn has_edge(&self, u: I, v: I) -> bool {
+        self.check_bounds(u.index() as u32);
+        self.check_bounds(v.index() as u32);
+        self.m_data[u.index()].contains(v.index() as u32)
+    }
+
+    #[inline]
+    fn row(&self, v: u32) -> &hibitset::BitSet {
+        &self.m_data[v as usize]
+    }
+
+    /// Row of `v` for edits word by word, journaled as a whole
+    #[inline]
+    fn row_mut(&mut self, v: u32) -> &mut hibitset::BitSet {
+        self.journal_row(v);
+        Arc::make_mut(&mut self.m_data[v as usize])
+    }
+
+    /// Number of bits the lowest layer of the row of `v` spans
+    #[cfg(any(feature = "std", test))]
+    pub(crate) fn row_span(&self, v: u32) -> u64 {
+        let bits = mem::size_of::<usize>() as u64 * 8;
+        self.m_data[v as usize].layer0_as_slice().len() as u64 * bits
+    }
+
+    /// Row of `v` for single bit edits, which the caller journals
+    #[inline]
+    fn row_bits(&mut self, v: u32) -> &mut hibitset::BitSet {
+        Arc::make_mut(&mut self.m_data[v as usize])
+    }
+
+    fn add_endpoint(&mut self, u: u32, v: u32) -> bool {
+        if !self.row_bits(u).add(v) {
+            self.journal_bit(u, v, true);
+            if self.m_degrees[u as usize] == I::default() {
+                self.m_order += 1;
+            }
+            self.m_degrees[u as usize] += I::ONE;
+            self.degree_changed(u);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_endpoint(&mut self, u: u32, v: u32) -> bool {
+        if self.row_bits(u).remove(v) {
+            self.journal_bit(u, v, false);
+            self.m_degrees[v as usize] -= I::ONE;
+            if self.m_degrees[v as usize] == I::default() {
+                self.m_order -= 1;
+            }
+            self.degree_changed(v);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes all edges incident to `v`
+    pub fn clear_vertex(&mut self, v: I) {
+        let v = v.index() as u32;
+        self.check_bounds(v);
+        self.journal_begin();
+        let neighbors: Vec<u32> = self.row(v).iter().collect();
+        for w in neighbors {
+            self.unlink(v, w);
+        }
+    }
+
+    /// Returns an iterator over the neighborhood of vertex `v`
+    pub fn neighbors(&self, v: I) -> I::Neighbors<'_> {
+        I::neighbors(self.row(v.index() as u32).iter())
+    }
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    pub fn capacity(&self) -> I {
+        I::from_index(self.m_data.len())
+    }
+
+    /// Number of vertices in the graph
+    pub fn order(&self) -> I {
+        I::from_index(self.m_order as usize)
+    }
+
+    /// Number of edges in the graph
+    pub fn size(&self) -> u64 {
+        self.m_degrees
+            .iter()
+            .map(|&d| d.index() as u64)
+            .sum::<u64>()
+            / 2
+    }
+
+    /// Number of neighbors of `v`
+    pub fn degree(&self, v: I) -> I {
+        self.m_degrees[v.index()]
+    }
+
+    /// Estimated number of bytes held by the graph: the rows with their hibitset layers, the
+    /// degree vector and the optional caches. Rows shared between graphs or with a
+    /// `NeighborsGuard` are counted in full, but only once per graph
+    pub fn memory_usage(&self) -> usize {
+        let word = mem::size_of::<usize>();
+        let mut rows: Vec<&Arc<hibitset::BitSet>> = self.m_data.iter().collect();
+        rows.sort_unstable_by_key(|row| Arc::as_ptr(row));
+        rows.dedup_by(|a, b| Arc::ptr_eq(a, b));
+        // every row allocation holds the two reference counts, the set and its layers
+        let row_bytes: usize = rows
+            .iter()
+            .map(|row| {
+                let words = row.layer0_as_slice().len()
+                    + row.layer1_as_slice().len()
+                    + row.layer2_as_slice().len();
+                2 * word + mem::size_of::<hibitset::BitSet>() + words * word
+            })
+            .sum();
+        mem::size_of::<BitGraph<I>>()
+            + self.m_data.capacity() * mem::size_of::<Arc<hibitset::BitSet>>()
+            + self.m_degrees.capacity() * mem::size_of::<I>()
+            + row_bytes
+            + self
+                .m_cache
+                .as_ref()
+                .map_or(0, |_| mem::size_of::<InvariantCache>())
+            + self.m_buckets.as_ref().map_or(0, |b| b.memory_usage())
+            + self.m_journal.as_ref().map_or(0, |j| j.memory_usage())
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u < v`, ordered by `u` and then `v`
+    pub fn edges(&self) -> Edges<'_, I> {
+        Edges {
+            m_graph: self,
+            m_vertex: 0,
+            m_neighbors: None,
+        }
+    }
+}
+
+impl BitGraph {
+    /// Creates a new BitGraph preallocated with up to `capacity` vertices
+    /// It is not possible later add vertices >= `capacity`
+    pub fn with_capacity(capacity: u32) -> BitGraph {
+        Self::indexed(capacity)
+    }
+
+    /// Raises the capacity to `new_capacity`, the new vertices are isolated.
+    /// Does nothing if the capacity is already at least `new_capacity`
+    pub fn grow_to(&mut self, new_capacity: u32) {
+        Self::check_capacity(new_capacity);
+        let n = self.m_data.len();
+        if new_capacity as usize <= n {
+            return;
+        }
+        // existing rows grow on their own once a larger neighbor is added
+        let empty = Arc::new(hibitset::BitSet::new());
+        self.m_data.resize(new_capacity as usize, empty);
+        self.m_degrees.resize(new_capacity as usize, 0);
+        self.journal_resize(n as u32, new_capacity);
+        if self.has_degree_buckets() {
+            self.disable_degree_buckets();
+            self.enable_degree_buckets();
+        }
+    }
+
+    #[inline]
+    fn check_capacity(capacity: u32) {
+        if capacity > MAX_CAPACITY as u32 {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                capacity, MAX_CAPACITY
+            )
+        }
+    }
+
     /// Creates a new BitGraph with `capacity` vertices, with all vertices connected to each other.
     /// It is not possible later add vertices >= `capacity`
     pub fn complete(capacity: u32) -> BitGraph {
         Self::check_capacity(capacity);
-        let mut m_data = vec![hibitset::BitSet::with_capacity(capacity); capacity as usize];
-        for bs in m_data.iter_mut() {
-            for idx in 0..capacity {
-                bs.add(idx);
-            }
-        }
+        let m_data = (0..capacity)
+            .map(|v| {
+                let mut bs = hibitset::BitSet::with_capacity(capacity);
+                for idx in (0..capacity).filter(|&idx| idx != v) {
+                    bs.add(idx);
+                }
+                Arc::new(bs)
+            })
+            .collect();
         BitGraph {
             m_data,
-            m_degrees: vec![capacity; capacity as usize],
-            m_order: capacity,
+            m_degrees: vec![capacity.saturating_sub(1); capacity as usize],
+            m_order: if capacity > 1 { capacity } else { 0 },
+            m_cache: None,
+            m_buckets: None,
+            m_journal: None,
         }
     }
 
-    /// Adds a new undirected edge from `u` to `v`
-    /// If the edge already exists, the graph is not updated
-    /// It is not possible to add edges with endpoints >= `capacity`
-    pub fn add_edge(&mut self, u: u32, v: u32) {
-        self.check_bounds(v);
-        self.check_bounds(u);
-        self.check_is_same(u, v);
-        self.add_endpoint(u, v);
-        self.add_endpoint(v, u);
+    /// Creates a BitGraph directly from its adjacency rows, which must be symmetric
+    /// and free of self-loops
+    pub(crate) fn from_rows(rows: Vec<hibitset::BitSet>) -> BitGraph {
+        Self::check_capacity(rows.len() as u32);
+        let m_degrees: Vec<u32> = rows
+            .iter()
+            .map(|row| {
+                row.layer0_as_slice()
+                    .iter()
+                    .map(|word| word.count_ones())
+                    .sum()
+            })
+            .collect();
+        BitGraph {
+            m_order: m_degrees.iter().filter(|&&d| d > 0).count() as u32,
+            m_degrees,
+            m_data: rows.into_iter().map(Arc::new).collect(),
+            m_cache: None,
+            m_buckets: None,
+            m_journal: None,
+        }
     }
 
     /// Same as `add_edge` except that no boundary checks are performed.
     /// Can corrupt the underlying data
     pub fn add_edge_unchecked(&mut self, u: u32, v: u32) {
+        self.journal_begin();
         self.add_endpoint_unchecked(u, v);
         self.add_endpoint_unchecked(v, u);
-    }
-
-    /// Removes the edge from `u` to `v` after performing boundary checks.
-    /// If the edge is not present the graph is not updated
-    pub fn remove_edge(&mut self, u: u32, v: u32) {
-        self.check_bounds(v);
-        self.check_bounds(u);
-        self.check_is_same(u, v);
-        self.remove_endpoint(u, v);
-        self.remove_endpoint(v, u);
+        self.invalidate_cache();
     }
 
     /// Same as `remove_edge` except that no boundary checks are performed
     /// Can corrupt the underlying data
     pub fn remove_edge_unchecked(&mut self, u: u32, v: u32) {
+        self.journal_begin();
         self.remove_endpoint_unchecked(u, v);
         self.remove_endpoint_unchecked(v, u);
-    }
-
-    fn add_endpoint(&mut self, u: u32, v: u32) {
-        if !self.m_data.get_mut(u as usize).unwrap().add(v) {
-            if self.m_degrees[u as usize] == 0 {
-                self.m_order += 1;
-            }
-            self.m_degrees[u as usize] += 1;
-        }
+        self.invalidate_cache();
     }
 
     fn add_endpoint_unchecked(&mut self, u: u32, v: u32) {
-        self.m_data.get_mut(u as usize).unwrap().add(v);
+        if !self.row_bits(u).add(v) {
+            self.journal_bit(u, v, true);
+        }
         if self.m_degrees[u as usize] == 0 {
             self.m_order += 1;
         }
         self.m_degrees[u as usize] += 1;
-    }
-
-    fn remove_endpoint(&mut self, u: u32, v: u32) {
-        if self.m_data.get_mut(u as usize).unwrap().remove(v) {
-            self.m_degrees[v as usize] -= 1;
-            if self.m_degrees[v as usize] == 0 {
-                self.m_order -= 1;
-            }
-        }
+        self.degree_changed(u);
     }
 
     fn remove_endpoint_unchecked(&mut self, u: u32, v: u32) {
-        self.m_data.get_mut(u as usize).unwrap().remove(v);
+        if self.row_bits(u).remove(v) {
+            self.journal_bit(u, v, false);
+        }
         self.m_degrees[v as usize] -= 1;
         if self.m_degrees[v as usize] == 0 {
             self.m_order -= 1;
         }
+        self.degree_changed(v);
+    }
+
+    /// Removes all edges and keeps the capacity. Rows that are not shared with a clone, a
+    /// `NeighborsGuard` or the journal are cleared in place and keep their allocation
+    pub fn clear(&mut self) {
+        self.journal_begin();
+        let mut empty: Option<Arc<hibitset::BitSet>> = None;
+        for v in 0..self.m_data.len() {
+            if self.m_degrees[v] == 0 {
+                continue;
+            }
+            self.m_degrees[v] = 0;
+            self.journal_row(v as u32);
+            let row = &mut self.m_data[v];
+            match Arc::get_mut(row) {
+                Some(row) => row.clear(),
+                None => *row = Arc::clone(empty.get_or_insert_with(Default::default)),
+            }
+        }
+        self.m_order = 0;
+        self.invalidate_cache();
+        if self.has_degree_buckets() {
+            self.disable_degree_buckets();
+            self.enable_degree_buckets();
+        }
     }
 
     /// Contracts the edge (target, source) by adding all neighbors
@@ -164,8 +540,8 @@ impl BitGraph {
         self.check_bounds(target);
         self.check_bounds(source);
         self.check_is_same(target, source);
-        let t_dat: &hibitset::BitSet = self.m_data.get(target as usize).unwrap();
-        let s_dat: &hibitset::BitSet = self.m_data.get(source as usize).unwrap();
+        let t_dat: &hibitset::BitSet = self.row(target);
+        let s_dat: &hibitset::BitSet = self.row(source);
         if t_dat.contains(source) && s_dat.contains(target) {
             self.contract_edge_unchecked(target, source);
         } else {
@@ -180,129 +556,531 @@ impl BitGraph {
     /// This is very unsafe, and can corrupt the entire graph if the function is called
     /// with invalid arguments
     pub fn contract_edge_unchecked(&mut self, target: u32, source: u32) {
+        self.journal_begin();
         unsafe {
-            let m_data_raw = self.m_data.as_mut_ptr();
-            let source_dat = m_data_raw.add(source as usize);
-            let target_dat = m_data_raw.add(target as usize);
+            let source_dat: *mut hibitset::BitSet = self.row_mut(source);
+            let target_dat: *mut hibitset::BitSet = self.row_bits(target);
             for w in (*source_dat).drain() {
                 if w != target {
                     if !(*target_dat).add(w) {
+                        self.journal_bit(target, w, true);
                         self.m_degrees[target as usize] += 1;
                     }
-                    let w_dat = m_data_raw.add(w as usize);
+                    let w_dat: *mut hibitset::BitSet = self.row_bits(w);
                     (*w_dat).remove(source);
+                    self.journal_bit(w, source, false);
                     if (*w_dat).add(target) {
                         self.m_degrees[w as usize] -= 1;
+                        self.degree_changed(w);
+                    } else {
+                        self.journal_bit(w, target, true);
                     }
                 }
             }
             (*target_dat).remove(source);
+            self.journal_bit(target, source, false);
             self.m_degrees[target as usize] -= 1;
         }
         self.m_degrees[source as usize] = 0;
         self.m_order -= 1;
+        if self.m_degrees[target as usize] == 0 {
+            self.m_order -= 1;
+        }
+        self.degree_changed(source);
+        self.degree_changed(target);
+        self.contracted(target);
     }
 
-    /// Returns an iterator over the neighborhood of vertex `v`
-    pub fn neighbors(&self, v: u32) -> BitIter<&hibitset::BitSet> {
-        self.m_data.get(v as usize).unwrap().iter()
+    /// Returns an iterator over the neighbors of `v` inside `mask`, the AND of its row and the mask
+    pub fn neighbors_in<'a, M: BitSetLike>(
+        &'a self,
+        v: u32,
+        mask: &'a M,
+    ) -> BitIter<BitSetAnd<&'a hibitset::BitSet, &'a M>> {
+        self.check_bounds(v);
+        BitSetAnd(self.row(v), mask).iter()
     }
 
-    /// Number of vertices in the graph
-    pub fn order(&self) -> u32 {
-        self.m_order
+    /// Returns the closed neighborhood of `v`, its neighbors and `v` itself, as a bitset
+    pub fn closed_neighbors(&self, v: u32) -> hibitset::BitSet {
+        self.check_bounds(v);
+        let mut closed = self.row(v).clone();
+        closed.add(v);
+        closed
     }
 
-    /// Number of neighbors of `v`
-    pub fn degree(&self, v: u32) -> u32 {
-        self.m_degrees[v as usize]
+    /// Returns the vertices outside of `set` with a neighbor in `set`, the OR of the rows of the
+    /// members without the set itself
+    pub fn neighborhood_of_set(&self, set: &hibitset::BitSet) -> hibitset::BitSet {
+        let mut union = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        for v in set.iter() {
+            self.check_bounds(v);
+            union |= self.row(v);
+        }
+        BitSetAnd(&union, BitSetNot(set)).iter().collect()
     }
 
-    /// Returns a `DfsIterator` starting at vertex `v`
-    pub fn dfs(&self, v: u32) -> DfsIterator {
-        let mut stack: Vec<u32> = Vec::new();
-        stack.push(v);
-        DfsIterator {
-            m_graph: self,
-            m_visited: bit_set::BitSet::with_capacity(self.m_order as usize),
-            m_stack: stack,
+    /// Returns an iterator over the common neighbors of `u` and `v`, the AND of their rows
+    pub fn common_neighbors(
+        &self,
+        u: u32,
+        v: u32,
+    ) -> BitIter<BitSetAnd<&hibitset::BitSet, &hibitset::BitSet>> {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        BitSetAnd(self.row(u), self.row(v)).iter()
+    }
+
+    /// Number of common neighbors of `u` and `v`, a popcount over the AND of their rows
+    pub fn common_neighbor_count(&self, u: u32, v: u32) -> u32 {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.common_count(u, v)
+    }
+
+    /// Returns the neighborhood of vertex `v` as a bitset, for set algebra with other bitsets
+    pub fn neighbors_set(&self, v: u32) -> &hibitset::BitSet {
+        self.check_bounds(v);
+        self.row(v)
+    }
+
+    /// Returns the neighborhood of vertex `v` as a mutable bitset. The changes are applied to
+    /// the rest of the graph when the returned guard is dropped
+    pub fn neighbors_set_mut(&mut self, v: u32) -> NeighborsMut<'_> {
+        self.check_bounds(v);
+        NeighborsMut::new(self, v)
+    }
+
+    /// Returns a snapshot of the neighborhood of vertex `v`
+    /// The snapshot stays valid and unchanged if the graph is mutated afterwards
+    pub fn neighbors_guard(&self, v: u32) -> NeighborsGuard {
+        self.check_bounds(v);
+        NeighborsGuard::new(Arc::clone(&self.m_data[v as usize]))
+    }
+
+    /// Degrees of all vertices in non-increasing order, isolated vertices included
+    pub fn degree_sequence(&self) -> Vec<u32> {
+        let mut degrees = self.m_degrees.clone();
+        degrees.sort_unstable_by(|a, b| b.cmp(a));
+        degrees
+    }
+
+    /// Number of vertices of every degree, entry `d` counts the vertices of degree `d`.
+    /// The last entry is the one of the maximum degree
+    pub fn degree_histogram(&self) -> Vec<u32> {
+        let mut histogram = vec![0; self.max_degree() as usize + 1];
+        for &d in self.m_degrees.iter() {
+            histogram[d as usize] += 1;
         }
+        histogram
+    }
+
+    /// Smallest degree of a vertex, `0` if the graph has capacity `0`
+    pub fn min_degree(&self) -> u32 {
+        self.m_degrees.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Largest degree of a vertex, `0` if the graph has capacity `0`
+    pub fn max_degree(&self) -> u32 {
+        self.m_degrees.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns a `DfsIterator` starting at vertex `v`
+    pub fn dfs(&self, v: u32) -> DfsIterator<'_> {
+        algorithms::dfs(self, v)
     }
 }
 
-/// Iterator that performs a depths first search on a `BitGraph`
+/// Iterator that performs a depths first search on an `UndirectedGraph`
 /// If the graph is fully-connected, all vertices are explored (spanning tree)
-pub struct DfsIterator<'a> {
-    m_visited: bit_set::BitSet,
+///
+/// Vertices are marked when they are pushed, so each one enters the stack at most once and a
+/// full traversal takes O(n + m) time. The resulting order is a stack-based search order, use
+/// `BitGraph::dfs_events` when a strict depth first preorder is required
+pub struct DfsIterator<'a, G: UndirectedGraph = BitGraph> {
+    m_visited: hibitset::BitSet,
+    m_marked: usize,
     m_stack: Vec<u32>,
-    m_graph: &'a BitGraph,
+    m_graph: &'a G,
+}
+
+impl<'a, G: UndirectedGraph> DfsIterator<'a, G> {
+    pub(crate) fn new(graph: &'a G, v: u32) -> DfsIterator<'a, G> {
+        let mut visited = hibitset::BitSet::with_capacity(graph.capacity());
+        visited.add(v);
+        DfsIterator {
+            m_graph: graph,
+            m_visited: visited,
+            m_marked: 1,
+            m_stack: vec![v],
+        }
+    }
+
+    /// Number of vertices of the graph that have not been reached yet
+    pub fn remaining_unvisited(&self) -> usize {
+        self.m_graph.capacity() as usize - self.m_marked
+    }
 }
 
-impl<'a> Iterator for DfsIterator<'a> {
+impl<'a, G: UndirectedGraph> Iterator for DfsIterator<'a, G> {
     type Item = u32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(v) = self.m_stack.pop() {
-            let mut r = None;
-            if !self.m_visited.contains(v as usize) {
-                self.m_visited.insert(v as usize);
-                r = Some(v);
-            } else {
-                r = None;
-            };
-            self.m_graph.neighbors(v).for_each(|u| {
-                if !self.m_visited.contains(u as usize) {
-                    self.m_stack.push(u);
+        let v = self.m_stack.pop()?;
+        for u in self.m_graph.neighbors(v) {
+            if !self.m_visited.add(u) {
+                self.m_marked += 1;
+                self.m_stack.push(u);
+            }
+        }
+        Some(v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = self.m_stack.len();
+        (pending, Some(pending + self.remaining_unvisited()))
+    }
+}
+
+impl<'a, G: UndirectedGraph> FusedIterator for DfsIterator<'a, G> {}
+
+/// Iterator over the edges of a `BitGraph`, created by `BitGraph::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct Edges<'a, I: VertexId = u32> {
+    m_graph: &'a BitGraph<I>,
+    m_vertex: u32,
+    m_neighbors: Option<BitIter<&'a hibitset::BitSet>>,
+}
+
+impl<'a, I: VertexId> Iterator for Edges<'a, I> {
+    type Item = (I, I);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(neighbors) = self.m_neighbors.as_mut() {
+                let u = self.m_vertex - 1;
+                if let Some(v) = neighbors.find(|&v| v > u) {
+                    return Some((I::from_index(u as usize), I::from_index(v as usize)));
                 }
-            });
-            if r != None {
-                return r;
+                self.m_neighbors = None;
+            }
+            if self.m_vertex as usize >= self.m_graph.m_data.len() {
+                return None;
+            }
+            let u = self.m_vertex;
+            self.m_vertex += 1;
+            if self.m_graph.m_degrees[u as usize] > I::default() {
+                self.m_neighbors = Some(self.m_graph.row(u).iter());
             }
         }
-        None
     }
 }
 
+impl<'a, I: VertexId> FusedIterator for Edges<'a, I> {}
+
 #[cfg(test)]
 mod tests {
-    use super::{BitGraph, DfsIterator};
-    use hibitset::BitSetLike;
+    use super::BitGraph;
 
     #[test]
     fn with_capacity() {
         let capacity: usize = 100;
-        let mut c = BitGraph::with_capacity(capacity as u32);
+        let c = BitGraph::with_capacity(capacity as u32);
 
         assert_eq!(c.m_degrees.len(), capacity);
+        assert_eq!(c.capacity(), capacity as u32);
         assert_eq!(c.order(), 0);
     }
 
     #[test]
     fn complete() {
         let capacity: usize = 100;
-        let mut c = BitGraph::complete(capacity as u32);
+        let c = BitGraph::complete(capacity as u32);
 
         assert_eq!(c.m_degrees.len(), capacity);
         assert_eq!(c.order(), capacity as u32);
-        for i in 0..capacity {
+        for _ in 0..capacity {
             assert_eq!(c.m_degrees.len(), capacity);
         }
     }
 
+    #[test]
+    fn complete_without_loops() {
+        let capacity: usize = 7;
+        let c = BitGraph::complete(capacity as u32);
+        assert_eq!(c.order(), capacity as u32);
+        assert_eq!(c.size(), (capacity * (capacity - 1) / 2) as u64);
+        for v in 0..capacity as u32 {
+            assert_eq!(c.degree(v), capacity as u32 - 1);
+            assert!(!c.neighbors_set(v).contains(v));
+        }
+        let c = BitGraph::complete(1);
+        assert_eq!((c.order(), c.degree(0), c.size()), (0, 0, 0));
+        let c = BitGraph::complete(0);
+        assert_eq!((c.order(), c.size()), (0, 0));
+    }
+
     #[test]
     fn dfs() {
         let capacity: usize = 10;
         let c = BitGraph::complete(capacity as u32);
 
-        let mut bit_set = bit_set::BitSet::with_capacity(capacity);
+        let mut bit_set = hibitset::BitSet::with_capacity(capacity as u32);
         for i in c.dfs(0) {
-            assert_eq!(bit_set.insert(i as usize), true);
+            assert!(!bit_set.add(i));
         }
         for i in 0..capacity {
-            assert_eq!(bit_set.contains(i as usize), true);
+            assert!(bit_set.contains(i as u32));
         }
     }
 
+    #[test]
+    fn dfs_iterator_traits() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 4);
+        let mut dfs = c.dfs(0);
+        assert_eq!(dfs.size_hint(), (1, Some(10)));
+        assert_eq!(dfs.remaining_unvisited(), 9);
+        assert_eq!(dfs.next(), Some(0));
+        assert_eq!(dfs.size_hint(), (2, Some(9)));
+        assert_eq!(dfs.remaining_unvisited(), 7);
+        let mut rest: Vec<u32> = dfs.by_ref().collect();
+        rest.sort_unstable();
+        assert_eq!(rest, vec![1, 2, 3, 4]);
+        assert_eq!(dfs.remaining_unvisited(), 5);
+        assert_eq!(dfs.size_hint(), (0, Some(5)));
+        assert_eq!(dfs.next(), None);
+        assert_eq!(dfs.next(), None);
+
+        let tmp: Vec<u32> = c.dfs(9).collect();
+        assert_eq!(tmp, vec![9]);
+    }
+
+    #[test]
+    fn degree_statistics() {
+        let capacity: usize = 6;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 1..4 {
+            c.add_edge(0, i);
+        }
+        c.add_edge(1, 2);
+        assert_eq!(c.degree_sequence(), vec![3, 2, 2, 1, 0, 0]);
+        assert_eq!(c.degree_histogram(), vec![2, 1, 2, 1]);
+        assert_eq!(c.min_degree(), 0);
+        assert_eq!(c.max_degree(), 3);
+
+        let c = BitGraph::complete(4);
+        assert_eq!(c.degree_histogram(), vec![0, 0, 0, 4]);
+        assert_eq!(c.min_degree(), 3);
+        let c = BitGraph::with_capacity(0);
+        assert_eq!(c.degree_sequence(), vec![]);
+        assert_eq!(c.degree_histogram(), vec![0]);
+        assert_eq!(c.max_degree(), 0);
+    }
+
+    #[test]
+    fn edges() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(3, 1), (0, 9), (1, 0), (5, 4), (9, 3)].iter() {
+            c.add_edge(u, v);
+        }
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (0, 9), (1, 3), (3, 9), (4, 5)]);
+        assert_eq!(BitGraph::complete(capacity as u32).edges().count(), 45);
+        assert_eq!(BitGraph::with_capacity(0).edges().next(), None);
+    }
+
+    #[test]
+    fn memory_usage() {
+        let capacity: usize = 4096;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        let empty = c.memory_usage();
+        // one shared empty row, a vector of pointers and the degrees
+        assert!(empty < capacity * 16 + 4096);
+        c.add_edge(0, 1);
+        let two_rows = c.memory_usage();
+        // both rows hold a single word per layer
+        assert!(two_rows > empty && two_rows < empty + 512);
+        let mut e = c.clone();
+        e.add_edge(0, 4095);
+        assert!(e.memory_usage() > two_rows + capacity / 8);
+        assert!(BitGraph::complete(capacity as u32).memory_usage() > capacity * capacity / 8);
+        // the old row kept alive by the guard is not held by the graph anymore
+        let guard = c.neighbors_guard(0);
+        c.add_edge(0, 2);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        d.add_edge(0, 1);
+        d.add_edge(0, 2);
+        assert_eq!(c.memory_usage(), d.memory_usage());
+        drop(guard);
+    }
+
+    #[test]
+    fn memory_usage_sparse() {
+        let capacity: usize = 100_000;
+        // a star costs one row across the capacity and a word per leaf
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for v in 1..capacity as u32 {
+            c.add_edge(v, 0);
+        }
+        assert!(c.memory_usage() < 256 * capacity);
+        let mut c = BitGraph::with_capacity(10);
+        c.enable_journal();
+        c.grow_to(capacity as u32);
+        c.undo(1);
+        c.redo(1);
+        for v in 1..capacity as u32 {
+            c.add_edge(v, 0);
+        }
+        assert!(c.memory_usage() < 256 * capacity + c.m_journal.as_ref().unwrap().memory_usage());
+    }
+
+    #[test]
+    fn large_capacity() {
+        let capacity: usize = 300_000;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..1000 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 299_999);
+        assert_eq!(c.size(), 1001);
+        assert_eq!(c.degree(0), 2);
+        assert_eq!(c.shortest_path(1, 299_999), Some(vec![1, 0, 299_999]));
+        // pointers and degrees for every vertex, a single full row and short ones for the rest
+        assert!(c.memory_usage() < capacity * 16 + 200_000);
+    }
+
+    #[test]
+    #[ignore = "allocates row pointers and degrees for MAX_CAPACITY vertices"]
+    fn grow_to_max_capacity() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.grow_to(super::MAX_CAPACITY as u32);
+        c.add_edge(super::MAX_CAPACITY as u32 - 1, 2);
+        assert_eq!(c.order(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn capacity_above_limit() {
+        BitGraph::with_capacity(super::MAX_CAPACITY as u32 + 1);
+    }
+
+    #[test]
+    fn grow_to() {
+        let capacity: usize = 3;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.enable_invariant_cache();
+        c.enable_degree_buckets();
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        assert_eq!(c.component_count(), 1);
+        c.grow_to(200);
+        assert_eq!(c.capacity(), 200);
+        assert_eq!(c.degree(199), 0);
+        c.add_edge(1, 199);
+        c.add_edge(150, 120);
+        let tmp: Vec<u32> = c.neighbors(1).collect();
+        assert_eq!(tmp, vec![0, 2, 199]);
+        assert_eq!(c.order(), 6);
+        assert_eq!(c.component_count(), 2);
+        assert_eq!(c.max_degree_vertex(), Some(1));
+        c.grow_to(10);
+        assert_eq!(c.capacity(), 200);
+    }
+
+    #[test]
+    fn clear() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::complete(capacity as u32);
+        c.enable_invariant_cache();
+        c.enable_degree_buckets();
+        assert_eq!(c.triangle_count(), 120);
+        let guard = c.neighbors_guard(3);
+        c.clear_vertex(4);
+        assert_eq!(c.degree(4), 0);
+        assert_eq!(c.degree(3), 8);
+        assert_eq!(c.order(), 9);
+        assert_eq!(c.triangle_count(), 84);
+        assert_eq!(c.min_degree_vertex(), Some(0));
+
+        c.clear();
+        assert_eq!(c, BitGraph::with_capacity(capacity as u32));
+        assert_eq!(c.order(), 0);
+        assert_eq!(c.triangle_count(), 0);
+        assert_eq!(c.max_degree_vertex(), None);
+        assert_eq!(guard.iter().count(), 9);
+        c.add_edge(3, 4);
+        assert_eq!(c.size(), 1);
+        assert_eq!(c.component_count(), 1);
+    }
+
+    #[test]
+    fn common_neighbors() {
+        let capacity: usize = 200;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for &w in [1, 5, 64, 130, 199].iter() {
+            c.add_edge(0, w);
+        }
+        for &w in [5, 63, 130, 199].iter() {
+            c.add_edge(2, w);
+        }
+        let tmp: Vec<u32> = c.common_neighbors(0, 2).collect();
+        assert_eq!(tmp, vec![5, 130, 199]);
+        assert_eq!(c.common_neighbor_count(0, 2), 3);
+        assert_eq!(c.common_neighbor_count(2, 0), 3);
+        assert_eq!(c.common_neighbor_count(0, 5), 0);
+        assert_eq!(c.common_neighbors(3, 4).next(), None);
+    }
+
+    #[test]
+    fn neighborhoods() {
+        use hibitset::BitSetLike;
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..6 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(8, 9);
+        let tmp: Vec<u32> = c.closed_neighbors(3).iter().collect();
+        assert_eq!(tmp, vec![2, 3, 4]);
+        let tmp: Vec<u32> = c.closed_neighbors(7).iter().collect();
+        assert_eq!(tmp, vec![7]);
+
+        let mut set = hibitset::BitSet::new();
+        for &v in [1, 2, 5, 9].iter() {
+            set.add(v);
+        }
+        let tmp: Vec<u32> = c.neighborhood_of_set(&set).iter().collect();
+        assert_eq!(tmp, vec![0, 3, 4, 6, 8]);
+        let mut set = hibitset::BitSet::new();
+        set.add(8);
+        set.add(9);
+        assert!(c.neighborhood_of_set(&set).is_empty());
+        assert!(c.neighborhood_of_set(&hibitset::BitSet::new()).is_empty());
+
+        let mut mask = hibitset::BitSet::new();
+        for &v in [0, 2, 3, 9].iter() {
+            mask.add(v);
+        }
+        let tmp: Vec<u32> = c.neighbors_in(1, &mask).collect();
+        assert_eq!(tmp, vec![0, 2]);
+        assert_eq!(c.neighbors_in(5, &mask).next(), None);
+        let guard = c.neighbors_guard(2);
+        let tmp: Vec<u32> = c.neighbors_in(3, &guard).collect();
+        assert_eq!(tmp, vec![]);
+        let tmp: Vec<u32> = c.neighbors_in(1, &hibitset::BitSetNot(&mask)).collect();
+        assert_eq!(tmp, vec![]);
+        let tmp: Vec<u32> = c.neighbors_in(4, &hibitset::BitSetNot(&mask)).collect();
+        assert_eq!(tmp, vec![5]);
+    }
+
     #[test]
     fn add_edge() {
         let capacity: usize = 10;
@@ -313,8 +1091,8 @@ mod tests {
         assert_eq!(c.m_degrees[u as usize], 1);
         assert_eq!(c.m_degrees[v as usize], 1);
         assert_eq!(c.order(), 2);
-        assert_eq!(c.m_data.get(u as usize).unwrap().contains(v), true);
-        assert_eq!(c.m_data.get(v as usize).unwrap().contains(u), true);
+        assert!(c.m_data.get(u as usize).unwrap().contains(v));
+        assert!(c.m_data.get(v as usize).unwrap().contains(u));
     }
 
     #[test]
@@ -329,8 +1107,8 @@ mod tests {
         assert_eq!(c.m_degrees[u as usize], 0);
         assert_eq!(c.m_degrees[v as usize], 0);
         assert_eq!(c.order(), 0);
-        assert_eq!(c.m_data.get(u as usize).unwrap().contains(v), false);
-        assert_eq!(c.m_data.get(v as usize).unwrap().contains(u), false);
+        assert!(!c.m_data.get(u as usize).unwrap().contains(v));
+        assert!(!c.m_data.get(v as usize).unwrap().contains(u));
     }
 
     #[test]
@@ -351,20 +1129,49 @@ mod tests {
         assert_eq!(c.m_degrees[v as usize], 3);
         assert_eq!(c.order(), 6);
 
-        c.contract_edge(u as u32, v as u32);
+        c.contract_edge(u, v);
 
         assert_eq!(c.m_degrees[v as usize], 0);
         assert_eq!(c.m_degrees[u as usize], 4);
         assert_eq!(c.order(), 5);
 
-        let tmp: Vec<u32> = c.m_data.get(u as usize).unwrap().iter().collect();
-        assert!(tmp.iter().zip([1,2,4,5].iter()).all(|(a,b)| a == b));
-        let tmp: Vec<u32> = c.m_data.get(v as usize).unwrap().iter().collect();
+        let tmp: Vec<u32> = c.neighbors(u).collect();
+        assert!(tmp.iter().zip([1, 2, 4, 5].iter()).all(|(a, b)| a == b));
+        let tmp: Vec<u32> = c.neighbors(v).collect();
         assert_eq!(tmp.len(), 0);
-        for i in [1u32,2,4,5].iter() {
-            let tmp: Vec<u32> = c.m_data.get(*i as usize).unwrap().iter().collect();
+        for i in [1u32, 2, 4, 5].iter() {
+            let tmp: Vec<u32> = c.neighbors(*i).collect();
             assert_eq!(tmp.len(), 1);
-            assert!(tmp.iter().zip([0u32].iter()).all(|(a,b)| a == b));
+            assert!(tmp.iter().zip([0u32].iter()).all(|(a, b)| a == b));
+        }
+    }
+
+    #[test]
+    fn clone_shares_rows() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 1..5 {
+            c.add_edge(0, i);
+            c.add_edge(i, i + 4);
         }
+        let shared = |a: &BitGraph, b: &BitGraph| {
+            a.m_data
+                .iter()
+                .zip(b.m_data.iter())
+                .filter(|(x, y)| alloc::sync::Arc::ptr_eq(x, y))
+                .count()
+        };
+        let mut d = c.clone();
+        assert_eq!(shared(&c, &d), capacity);
+        // contracting (0, 1) writes the rows of 0, 1 and 5
+        d.contract_edge(0, 1);
+        assert_eq!(shared(&c, &d), capacity - 3);
+        assert_eq!(c.degree(1), 2);
+        assert!(c.m_data[5].contains(1));
+        assert!(d.m_data[5].contains(0));
+        let e = d.clone();
+        d.remove_edge(2, 6);
+        assert_eq!(shared(&d, &e), capacity - 2);
+        assert_eq!(e.degree(2), 2);
     }
 }
@@ -0,0 +1,256 @@
+//! Self-loops on top of a `BitGraph`
+
+use crate::{BitGraph, Edges};
+use core::iter::{FusedIterator, Peekable};
+use hibitset::{BitIter, BitSetLike};
+
+/// An undirected graph that allows self-loops. The edges between distinct vertices stay in a
+/// `BitGraph` and the loops are kept in a separate bitset, so the algorithms of `graph` see the
+/// graph without its loops. A loop counts twice towards the degree of its vertex, once for each
+/// of its ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopBitGraph {
+    m_graph: BitGraph,
+    m_loops: hibitset::BitSet,
+}
+
+impl LoopBitGraph {
+    /// Creates a new LoopBitGraph with `capacity` vertices and no edges
+    pub fn with_capacity(capacity: u32) -> LoopBitGraph {
+        LoopBitGraph {
+            m_graph: BitGraph::with_capacity(capacity),
+            m_loops: hibitset::BitSet::with_capacity(capacity),
+        }
+    }
+
+    /// Returns `graph` without loops
+    pub fn from_graph(graph: BitGraph) -> LoopBitGraph {
+        LoopBitGraph {
+            m_loops: hibitset::BitSet::with_capacity(graph.capacity()),
+            m_graph: graph,
+        }
+    }
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    pub fn capacity(&self) -> u32 {
+        self.m_graph.capacity()
+    }
+
+    /// Adds the edge from `u` to `v`, a loop if `u == v`
+    /// If the edge already exists, the graph is not updated
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        if u == v {
+            self.m_graph.check_bounds(v);
+            self.m_loops.add(v);
+        } else {
+            self.m_graph.add_edge(u, v);
+        }
+    }
+
+    /// Removes the edge from `u` to `v`, a loop if `u == v`
+    /// If the edge is not present the graph is not updated
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        if u == v {
+            self.m_graph.check_bounds(v);
+            self.m_loops.remove(v);
+        } else {
+            self.m_graph.remove_edge(u, v);
+        }
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent, or if `v` has a loop when `u == v`
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.m_graph.check_bounds(u);
+        self.m_graph.check_bounds(v);
+        if u == v {
+            self.m_loops.contains(v)
+        } else {
+            self.m_graph.row(u).contains(v)
+        }
+    }
+
+    /// Returns `true` if `v` has a loop
+    pub fn has_loop(&self, v: u32) -> bool {
+        self.has_edge(v, v)
+    }
+
+    /// Returns an iterator over the vertices with a loop
+    pub fn loops(&self) -> BitIter<&hibitset::BitSet> {
+        (&self.m_loops).iter()
+    }
+
+    /// Number of loops
+    pub fn loop_count(&self) -> u32 {
+        self.m_loops
+            .layer0_as_slice()
+            .iter()
+            .map(|word| word.count_ones())
+            .sum()
+    }
+
+    /// Removes all edges incident to `v`, its loop included
+    pub fn clear_vertex(&mut self, v: u32) {
+        self.m_graph.clear_vertex(v);
+        self.m_loops.remove(v);
+    }
+
+    /// Number of vertices with an edge or a loop
+    pub fn order(&self) -> u32 {
+        let isolated = self.loops().filter(|&v| self.m_graph.degree(v) == 0);
+        self.m_graph.order() + isolated.count() as u32
+    }
+
+    /// Number of edges, loops included
+    pub fn size(&self) -> u64 {
+        self.m_graph.size() + self.loop_count() as u64
+    }
+
+    /// Degree of `v`, the number of its neighbors plus 2 if it has a loop
+    pub fn degree(&self, v: u32) -> u32 {
+        self.m_graph.check_bounds(v);
+        self.m_graph.degree(v) + if self.m_loops.contains(v) { 2 } else { 0 }
+    }
+
+    /// Returns an iterator over the neighbors of `v` in increasing order, `v` itself included
+    /// if it has a loop
+    pub fn neighbors(&self, v: u32) -> LoopNeighbors<'_> {
+        self.m_graph.check_bounds(v);
+        LoopNeighbors {
+            m_neighbors: self.m_graph.neighbors(v).peekable(),
+            m_loop: if self.m_loops.contains(v) {
+                Some(v)
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u <= v`, ordered by `u` and then `v`
+    pub fn edges(&self) -> LoopEdges<'_> {
+        LoopEdges {
+            m_edges: self.m_graph.edges().peekable(),
+            m_loops: self.loops().peekable(),
+        }
+    }
+
+    /// Contracts the edge (target, source) like `BitGraph::contract_edge`, except that the
+    /// contracted edge becomes a loop at `target` instead of being dropped. A loop at `source`
+    /// also moves to `target`
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        self.m_graph.contract_edge(target, source);
+        self.m_loops.remove(source);
+        self.m_loops.add(target);
+    }
+
+    /// The graph without its loops
+    pub fn graph(&self) -> &BitGraph {
+        &self.m_graph
+    }
+}
+
+/// Iterator over the neighbors of a vertex and the vertex itself if it has a loop,
+/// created by `LoopBitGraph::neighbors`
+pub struct LoopNeighbors<'a> {
+    m_neighbors: Peekable<BitIter<&'a hibitset::BitSet>>,
+    m_loop: Option<u32>,
+}
+
+impl<'a> Iterator for LoopNeighbors<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(v) = self.m_loop {
+            let before = match self.m_neighbors.peek() {
+                Some(&w) => v < w,
+                None => true,
+            };
+            if before {
+                return self.m_loop.take();
+            }
+        }
+        self.m_neighbors.next()
+    }
+}
+
+impl<'a> FusedIterator for LoopNeighbors<'a> {}
+
+/// Iterator over the edges of a `LoopBitGraph`, created by `LoopBitGraph::edges`.
+/// Every edge is reported once with the smaller vertex first, and a loop at `v` as `(v, v)`
+pub struct LoopEdges<'a> {
+    m_edges: Peekable<Edges<'a>>,
+    m_loops: Peekable<BitIter<&'a hibitset::BitSet>>,
+}
+
+impl<'a> Iterator for LoopEdges<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // a loop (v, v) comes before every edge (v, w) with w > v
+        let take_loop = match (self.m_loops.peek(), self.m_edges.peek()) {
+            (Some(&v), Some(&(u, _))) => v <= u,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if take_loop {
+            self.m_loops.next().map(|v| (v, v))
+        } else {
+            self.m_edges.next()
+        }
+    }
+}
+
+impl<'a> FusedIterator for LoopEdges<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::LoopBitGraph;
+
+    #[test]
+    fn loops() {
+        let capacity: usize = 6;
+        let mut c = LoopBitGraph::with_capacity(capacity as u32);
+        c.add_edge(2, 2);
+        c.add_edge(1, 2);
+        c.add_edge(2, 4);
+        c.add_edge(5, 5);
+        c.add_edge(5, 5);
+        assert!(c.has_loop(2));
+        assert!(c.has_edge(2, 1));
+        assert!(!c.has_edge(1, 1));
+        assert_eq!(c.degree(2), 4);
+        assert_eq!(c.degree(5), 2);
+        assert_eq!(c.size(), 4);
+        assert_eq!(c.order(), 4);
+        assert_eq!(c.graph().size(), 2);
+        let tmp: Vec<u32> = c.neighbors(2).collect();
+        assert_eq!(tmp, vec![1, 2, 4]);
+        let tmp: Vec<u32> = c.neighbors(5).collect();
+        assert_eq!(tmp, vec![5]);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(1, 2), (2, 2), (2, 4), (5, 5)]);
+
+        c.remove_edge(5, 5);
+        assert_eq!(c.degree(5), 0);
+        c.clear_vertex(2);
+        assert_eq!(c.loop_count(), 0);
+        assert_eq!(c.order(), 0);
+    }
+
+    #[test]
+    fn contraction_keeps_loops() {
+        let mut c = LoopBitGraph::from_graph(generators::path(4));
+        c.add_edge(3, 3);
+        c.contract_edge(2, 3);
+        assert!(c.has_loop(2));
+        assert!(!c.has_loop(3));
+        assert_eq!(c.loop_count(), 1);
+        c.contract_edge(1, 2);
+        c.contract_edge(0, 1);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(0, 0)]);
+        // loops are simple, the three contracted edges collapse into one loop
+        assert_eq!(c.degree(0), 2);
+        assert_eq!(c.order(), 1);
+    }
+}
@@ -0,0 +1,319 @@
+//! Induced subgraphs as a vertex mask over a borrowed graph
+
+use crate::{BitGraph, UndirectedGraph};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use hibitset::{BitIter, BitSetAnd, BitSetLike};
+
+/// The subgraph of a borrowed graph induced by a vertex mask, returned by
+/// `BitGraph::subgraph_view`. The neighbors of a vertex are the AND of its row and the mask,
+/// computed when they are asked for, so vertices can be dropped and restored in constant time
+/// without building a new graph. Vertices outside the mask keep their numbers and are isolated
+#[derive(Debug, Clone)]
+pub struct SubgraphView<'a> {
+    m_graph: &'a BitGraph,
+    m_mask: hibitset::BitSet,
+}
+
+/// Number of common bits of `a` and `b`, a popcount over the AND of their bottom layers
+fn and_count(a: &hibitset::BitSet, b: &hibitset::BitSet) -> u32 {
+    a.layer0_as_slice()
+        .iter()
+        .zip(b.layer0_as_slice().iter())
+        .map(|(x, y)| (x & y).count_ones())
+        .sum()
+}
+
+impl<'a> SubgraphView<'a> {
+    /// The graph the view is a subgraph of
+    pub fn graph(&self) -> &'a BitGraph {
+        self.m_graph
+    }
+
+    /// The vertices of the subgraph
+    pub fn mask(&self) -> &hibitset::BitSet {
+        &self.m_mask
+    }
+
+    /// Returns `true` if `v` is a vertex of the subgraph
+    pub fn contains(&self, v: u32) -> bool {
+        self.m_graph.check_bounds(v);
+        self.m_mask.contains(v)
+    }
+
+    /// Adds `v` back to the subgraph, with all its edges to the other vertices of it.
+    /// Returns `true` if `v` was not in the subgraph
+    pub fn add_vertex(&mut self, v: u32) -> bool {
+        self.m_graph.check_bounds(v);
+        !self.m_mask.add(v)
+    }
+
+    /// Drops `v` and its edges from the subgraph. Returns `true` if `v` was in the subgraph
+    pub fn remove_vertex(&mut self, v: u32) -> bool {
+        self.m_graph.check_bounds(v);
+        self.m_mask.remove(v)
+    }
+
+    /// Returns an iterator over the vertices of the subgraph
+    pub fn vertices(&self) -> BitIter<&hibitset::BitSet> {
+        (&self.m_mask).iter()
+    }
+
+    /// Number of vertices of the subgraph, isolated ones included
+    pub fn vertex_count(&self) -> u32 {
+        self.m_mask
+            .layer0_as_slice()
+            .iter()
+            .map(|word| word.count_ones())
+            .sum()
+    }
+
+    /// Number of vertices of the subgraph with at least one neighbor in it
+    pub fn order(&self) -> u32 {
+        self.vertices().filter(|&v| self.degree(v) > 0).count() as u32
+    }
+
+    /// Number of edges of the subgraph
+    pub fn size(&self) -> u64 {
+        self.vertices().map(|v| self.degree(v) as u64).sum::<u64>() / 2
+    }
+
+    /// Number of neighbors of `v` in the subgraph, `0` if `v` is outside of it
+    pub fn degree(&self, v: u32) -> u32 {
+        if !self.contains(v) {
+            return 0;
+        }
+        and_count(self.m_graph.row(v), &self.m_mask)
+    }
+
+    /// Returns `true` if `u` and `v` are both in the subgraph and adjacent
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.contains(u) && self.contains(v) && self.m_graph.row(u).contains(v)
+    }
+
+    /// Returns an iterator over the neighbors of `v` in the subgraph, empty if `v` is outside
+    /// of it
+    pub fn neighbors(&self, v: u32) -> SubgraphNeighbors<'_> {
+        SubgraphNeighbors {
+            m_neighbors: if self.contains(v) {
+                Some(BitSetAnd(self.m_graph.row(v), &self.m_mask).iter())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns an iterator over all edges `(u, v)` of the subgraph with `u < v`, ordered by `u`
+    /// and then `v`
+    pub fn edges(&self) -> SubgraphEdges<'_> {
+        SubgraphEdges {
+            m_view: self,
+            m_vertices: self.vertices(),
+            m_vertex: 0,
+            m_neighbors: None,
+        }
+    }
+
+    /// Returns an iterator over the vertices of the subgraph reachable from `v` in it, in the
+    /// order of `BitGraph::dfs`
+    pub fn dfs(&self, v: u32) -> SubgraphDfs<'_> {
+        let mut visited = hibitset::BitSet::with_capacity(self.m_graph.capacity());
+        let stack = if self.contains(v) {
+            visited.add(v);
+            vec![v]
+        } else {
+            Vec::new()
+        };
+        SubgraphDfs {
+            m_view: self,
+            m_visited: visited,
+            m_stack: stack,
+        }
+    }
+
+    /// Returns the subgraph as a new graph with the same capacity
+    pub fn to_graph(&self) -> BitGraph {
+        BitGraph::from_rows(
+            (0..self.m_graph.capacity())
+                .map(|v| self.neighbors(v).collect())
+                .collect(),
+        )
+    }
+}
+
+impl<'g> UndirectedGraph for SubgraphView<'g> {
+    type Neighbors<'a>
+        = SubgraphNeighbors<'a>
+    where
+        Self: 'a;
+
+    fn capacity(&self) -> u32 {
+        self.m_graph.capacity()
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        SubgraphView::neighbors(self, v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        SubgraphView::degree(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        SubgraphView::has_edge(self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        SubgraphView::size(self)
+    }
+}
+
+impl BitGraph {
+    /// Returns the subgraph induced by the vertices in `mask` as a view
+    pub fn subgraph_view(&self, mask: hibitset::BitSet) -> SubgraphView<'_> {
+        for v in (&mask).iter() {
+            self.check_bounds(v);
+        }
+        SubgraphView {
+            m_graph: self,
+            m_mask: mask,
+        }
+    }
+}
+
+/// Iterator over the neighbors of a vertex in a `SubgraphView`, created by
+/// `SubgraphView::neighbors`
+pub struct SubgraphNeighbors<'a> {
+    m_neighbors: Option<BitIter<BitSetAnd<&'a hibitset::BitSet, &'a hibitset::BitSet>>>,
+}
+
+impl<'a> Iterator for SubgraphNeighbors<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.m_neighbors.as_mut()?.next()
+    }
+}
+
+impl<'a> FusedIterator for SubgraphNeighbors<'a> {}
+
+/// Iterator over the edges of a `SubgraphView`, created by `SubgraphView::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct SubgraphEdges<'a> {
+    m_view: &'a SubgraphView<'a>,
+    m_vertices: BitIter<&'a hibitset::BitSet>,
+    m_vertex: u32,
+    m_neighbors: Option<SubgraphNeighbors<'a>>,
+}
+
+impl<'a> Iterator for SubgraphEdges<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(neighbors) = self.m_neighbors.as_mut() {
+                let u = self.m_vertex;
+                if let Some(v) = neighbors.find(|&v| v > u) {
+                    return Some((u, v));
+                }
+            }
+            self.m_vertex = self.m_vertices.next()?;
+            self.m_neighbors = Some(self.m_view.neighbors(self.m_vertex));
+        }
+    }
+}
+
+impl<'a> FusedIterator for SubgraphEdges<'a> {}
+
+/// Iterator that performs a depth first search inside a `SubgraphView`, created by
+/// `SubgraphView::dfs`
+pub struct SubgraphDfs<'a> {
+    m_view: &'a SubgraphView<'a>,
+    m_visited: hibitset::BitSet,
+    m_stack: Vec<u32>,
+}
+
+impl<'a> Iterator for SubgraphDfs<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_stack.pop()?;
+        for u in self.m_view.neighbors(v) {
+            if !self.m_visited.add(u) {
+                self.m_stack.push(u);
+            }
+        }
+        Some(v)
+    }
+}
+
+impl<'a> FusedIterator for SubgraphDfs<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{algorithms, generators, BitGraph};
+
+    #[test]
+    fn subgraph_view() {
+        let capacity: usize = 6;
+        let c = generators::cycle(capacity as u32);
+        let mask: hibitset::BitSet = [0, 1, 2, 4].iter().copied().collect();
+        let mut view = c.subgraph_view(mask);
+        assert_eq!(view.vertex_count(), 4);
+        assert_eq!(view.order(), 3);
+        assert_eq!(view.size(), 2);
+        assert_eq!(view.degree(1), 2);
+        assert_eq!(view.degree(4), 0);
+        assert_eq!(view.degree(5), 0);
+        assert!(view.has_edge(0, 1) && !view.has_edge(4, 5));
+        let tmp: Vec<u32> = view.neighbors(0).collect();
+        assert_eq!(tmp, vec![1]);
+        assert_eq!(view.neighbors(3).count(), 0);
+        let tmp: Vec<(u32, u32)> = view.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (1, 2)]);
+        let mut tmp: Vec<u32> = view.dfs(2).collect();
+        tmp.sort_unstable();
+        assert_eq!(tmp, vec![0, 1, 2]);
+
+        assert!(view.remove_vertex(1));
+        assert!(!view.remove_vertex(1));
+        assert_eq!(view.size(), 0);
+        assert!(view.add_vertex(5));
+        assert!(view.add_vertex(1));
+        let tmp: Vec<(u32, u32)> = view.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (0, 5), (1, 2), (4, 5)]);
+        assert_eq!(view.dfs(3).count(), 0);
+    }
+
+    #[test]
+    fn matches_induced_subgraph() {
+        let capacity: usize = 40;
+        let mut rng = SplitMix64::new(77);
+        let c = generators::gnp(capacity as u32, 0.15, &mut rng);
+        for _ in 0..10 {
+            let mask: hibitset::BitSet =
+                (0..capacity as u32).filter(|_| rng.below(3) > 0).collect();
+            let view = c.subgraph_view(mask.clone());
+            let mut induced = BitGraph::with_capacity(capacity as u32);
+            for (u, v) in c.edges() {
+                if mask.contains(u) && mask.contains(v) {
+                    induced.add_edge(u, v);
+                }
+            }
+            assert_eq!(view.to_graph(), induced);
+            assert_eq!(view.size(), induced.size());
+            assert_eq!(view.order(), induced.order());
+            let edges: Vec<(u32, u32)> = view.edges().collect();
+            assert_eq!(edges, induced.edges().collect::<Vec<(u32, u32)>>());
+            for v in 0..capacity as u32 {
+                assert_eq!(view.degree(v), induced.degree(v));
+            }
+            assert_eq!(
+                algorithms::connected_components(&view),
+                algorithms::connected_components(&induced)
+            );
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! A graph backend with the whole adjacency matrix in one allocation
+
+use crate::{ArrayNeighbors, BitGraph, UndirectedGraph, MAX_CAPACITY};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// An undirected graph whose adjacency matrix is one contiguous vector of `n * ceil(n / 64)`
+/// words, row after row. Row scans stay within consecutive cache lines, and operations on the
+/// whole graph like `clone`, `complement` or `union_with` are single passes over the words.
+/// Unlike `BitGraph` there is no summary layer, so the iteration over a sparse row visits
+/// every word of it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatrixBitGraph {
+    m_words: Vec<u64>,
+    m_capacity: u32,
+    m_stride: usize,
+}
+
+impl MatrixBitGraph {
+    /// Creates a new MatrixBitGraph with `capacity` vertices and no edges
+    pub fn with_capacity(capacity: u32) -> MatrixBitGraph {
+        if capacity > MAX_CAPACITY as u32 {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                capacity, MAX_CAPACITY
+            )
+        }
+        let stride = (capacity as usize).div_ceil(64);
+        MatrixBitGraph {
+            m_words: vec![0; capacity as usize * stride],
+            m_capacity: capacity,
+            m_stride: stride,
+        }
+    }
+
+    /// Creates a new MatrixBitGraph with `capacity` vertices, all connected to each other
+    pub fn complete(capacity: u32) -> MatrixBitGraph {
+        let mut graph = Self::with_capacity(capacity);
+        graph.complement();
+        graph
+    }
+
+    /// Returns `graph` in a single matrix
+    pub fn from_bit_graph(graph: &BitGraph) -> MatrixBitGraph {
+        let mut matrix = Self::with_capacity(graph.capacity());
+        for (u, v) in graph.edges() {
+            matrix.add_edge(u, v);
+        }
+        matrix
+    }
+
+    /// Returns the graph as a `BitGraph` of the same capacity
+    pub fn to_bit_graph(&self) -> BitGraph {
+        BitGraph::from_rows(
+            (0..self.m_capacity)
+                .map(|v| self.neighbors(v).collect())
+                .collect(),
+        )
+    }
+
+    #[inline]
+    fn check_bounds(&self, idx: u32) {
+        if idx >= self.m_capacity {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                idx, self.m_capacity
+            )
+        }
+    }
+
+    #[inline]
+    fn check_is_same(&self, u: u32, v: u32) {
+        if u == v {
+            panic!("Edge needs two distinct endpoints, given: {} {}", u, v)
+        }
+    }
+
+    #[inline]
+    fn check_same_capacity(&self, other: &MatrixBitGraph) {
+        if self.m_capacity != other.m_capacity {
+            panic!(
+                "Capacities differ. Given: {}, Expected: {}",
+                other.m_capacity, self.m_capacity
+            )
+        }
+    }
+
+    #[inline]
+    fn word(&self, u: u32, v: u32) -> usize {
+        u as usize * self.m_stride + v as usize / 64
+    }
+
+    /// The words of the row of `v`, bit `w % 64` of word `w / 64` is set if `w` is a neighbor
+    pub fn row(&self, v: u32) -> &[u64] {
+        self.check_bounds(v);
+        let start = v as usize * self.m_stride;
+        &self.m_words[start..start + self.m_stride]
+    }
+
+    /// Adds a new undirected edge from `u` to `v`
+    /// If the edge already exists, the graph is not updated
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.check_is_same(u, v);
+        let (uv, vu) = (self.word(u, v), self.word(v, u));
+        self.m_words[uv] |= 1 << (v % 64);
+        self.m_words[vu] |= 1 << (u % 64);
+    }
+
+    /// Removes the edge from `u` to `v` after performing boundary checks.
+    /// If the edge is not present the graph is not updated
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        let (uv, vu) = (self.word(u, v), self.word(v, u));
+        self.m_words[uv] &= !(1 << (v % 64));
+        self.m_words[vu] &= !(1 << (u % 64));
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.m_words[self.word(u, v)] & (1 << (v % 64)) != 0
+    }
+
+    /// Removes all edges and keeps the capacity
+    pub fn clear(&mut self) {
+        self.m_words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Removes all edges incident to `v`
+    pub fn clear_vertex(&mut self, v: u32) {
+        let neighbors: Vec<u32> = self.neighbors(v).collect();
+        for w in neighbors {
+            self.remove_edge(v, w);
+        }
+    }
+
+    /// Contracts the edge (target, source) by adding all neighbors
+    /// of source to `target` and removing `source`
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        self.check_is_same(target, source);
+        if !self.has_edge(target, source) {
+            panic!(
+                "Edge ({}, {}) does not exist. Can't contract!",
+                target, source
+            );
+        }
+        let neighbors: Vec<u32> = self.neighbors(source).collect();
+        for w in neighbors {
+            self.remove_edge(source, w);
+            if w != target {
+                self.add_edge(target, w);
+            }
+        }
+    }
+
+    /// Replaces the edges by the non-edges, one pass over the matrix
+    pub fn complement(&mut self) {
+        let n = self.m_capacity as usize;
+        let tail = match n % 64 {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        };
+        for (v, row) in self.m_words.chunks_mut(self.m_stride.max(1)).enumerate() {
+            for word in row.iter_mut() {
+                *word = !*word;
+            }
+            if let Some(last) = row.last_mut() {
+                *last &= tail;
+            }
+            row[v / 64] &= !(1 << (v % 64));
+        }
+    }
+
+    /// Adds all edges of `other`
+    pub fn union_with(&mut self, other: &MatrixBitGraph) {
+        self.check_same_capacity(other);
+        for (a, b) in self.m_words.iter_mut().zip(other.m_words.iter()) {
+            *a |= b;
+        }
+    }
+
+    /// Removes all edges that are not in `other`
+    pub fn intersect_with(&mut self, other: &MatrixBitGraph) {
+        self.check_same_capacity(other);
+        for (a, b) in self.m_words.iter_mut().zip(other.m_words.iter()) {
+            *a &= b;
+        }
+    }
+
+    /// Removes all edges that are in `other`
+    pub fn difference_with(&mut self, other: &MatrixBitGraph) {
+        self.check_same_capacity(other);
+        for (a, b) in self.m_words.iter_mut().zip(other.m_words.iter()) {
+            *a &= !b;
+        }
+    }
+
+    /// Returns an iterator over the neighborhood of vertex `v`
+    pub fn neighbors(&self, v: u32) -> ArrayNeighbors<'_> {
+        ArrayNeighbors::new(self.row(v))
+    }
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    pub fn capacity(&self) -> u32 {
+        self.m_capacity
+    }
+
+    /// Number of neighbors of `v`, a popcount over its row
+    pub fn degree(&self, v: u32) -> u32 {
+        self.row(v).iter().map(|word| word.count_ones()).sum()
+    }
+
+    /// Number of vertices in the graph
+    pub fn order(&self) -> u32 {
+        self.m_words
+            .chunks(self.m_stride.max(1))
+            .filter(|row| row.iter().any(|&word| word != 0))
+            .count() as u32
+    }
+
+    /// Number of edges in the graph
+    pub fn size(&self) -> u64 {
+        self.m_words
+            .iter()
+            .map(|word| word.count_ones() as u64)
+            .sum::<u64>()
+            / 2
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u < v`, ordered by `u` and then `v`
+    pub fn edges(&self) -> MatrixEdges<'_> {
+        MatrixEdges {
+            m_graph: self,
+            m_vertex: 0,
+            m_neighbors: None,
+        }
+    }
+
+    /// Estimated number of bytes held by the graph
+    pub fn memory_usage(&self) -> usize {
+        core::mem::size_of::<MatrixBitGraph>()
+            + self.m_words.capacity() * core::mem::size_of::<u64>()
+    }
+}
+
+impl UndirectedGraph for MatrixBitGraph {
+    type Neighbors<'a> = ArrayNeighbors<'a>;
+
+    fn capacity(&self) -> u32 {
+        self.m_capacity
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        MatrixBitGraph::neighbors(self, v)
+    }
+
+    fn degree(&self, v: u32) -> u32 {
+        MatrixBitGraph::degree(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        MatrixBitGraph::has_edge(self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        MatrixBitGraph::size(self)
+    }
+}
+
+/// Iterator over the edges of a `MatrixBitGraph`, created by `MatrixBitGraph::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct MatrixEdges<'a> {
+    m_graph: &'a MatrixBitGraph,
+    m_vertex: u32,
+    m_neighbors: Option<ArrayNeighbors<'a>>,
+}
+
+impl<'a> Iterator for MatrixEdges<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(neighbors) = self.m_neighbors.as_mut() {
+                let u = self.m_vertex - 1;
+                if let Some(v) = neighbors.find(|&v| v > u) {
+                    return Some((u, v));
+                }
+                self.m_neighbors = None;
+            }
+            if self.m_vertex >= self.m_graph.m_capacity {
+                return None;
+            }
+            self.m_neighbors = Some(self.m_graph.neighbors(self.m_vertex));
+            self.m_vertex += 1;
+        }
+    }
+}
+
+impl<'a> FusedIterator for MatrixEdges<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{algorithms, generators, BitGraph, MatrixBitGraph};
+
+    #[test]
+    fn matrix_graph() {
+        let capacity: usize = 70;
+        let mut c = MatrixBitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 69);
+        c.add_edge(3, 64);
+        c.add_edge(3, 4);
+        c.add_edge(4, 3);
+        assert_eq!(c.size(), 3);
+        assert_eq!(c.order(), 5);
+        assert_eq!(c.degree(3), 2);
+        let tmp: Vec<u32> = c.neighbors(3).collect();
+        assert_eq!(tmp, vec![4, 64]);
+        assert_eq!(c.row(64), &[1 << 3, 0][..]);
+        c.contract_edge(3, 64);
+        assert_eq!(c.degree(64), 0);
+        assert!(!c.has_edge(3, 64));
+        c.remove_edge(0, 69);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(3, 4)]);
+
+        c.complement();
+        assert_eq!(c.size(), (70 * 69 / 2 - 1) as u64);
+        assert!(!c.has_edge(4, 3) && c.has_edge(69, 0));
+        assert_eq!(
+            MatrixBitGraph::complete(70).to_bit_graph(),
+            BitGraph::complete(70)
+        );
+        assert_eq!(MatrixBitGraph::complete(0).size(), 0);
+        c.clear();
+        assert_eq!(c.order(), 0);
+    }
+
+    #[test]
+    fn matches_bit_graph() {
+        let capacity: usize = 90;
+        let mut rng = SplitMix64::new(80);
+        let a = generators::gnp(capacity as u32, 0.1, &mut rng);
+        let b = generators::gnp(capacity as u32, 0.1, &mut rng);
+        let mut c = MatrixBitGraph::from_bit_graph(&a);
+        let d = MatrixBitGraph::from_bit_graph(&b);
+        assert_eq!(c.to_bit_graph(), a);
+        assert!(c.edges().eq(a.edges()));
+        assert_eq!(
+            algorithms::connected_components(&c),
+            algorithms::connected_components(&a)
+        );
+        let mut e = c.clone();
+        e.union_with(&d);
+        assert_eq!(e.to_bit_graph(), a.union(&b));
+        e = c.clone();
+        e.intersect_with(&d);
+        assert_eq!(e.to_bit_graph(), a.intersection(&b));
+        c.difference_with(&d);
+        assert_eq!(c.to_bit_graph(), a.difference(&b));
+        for _ in 0..10 {
+            let u = rng.below(capacity as u64) as u32;
+            let mut f = a.clone();
+            let mut g = MatrixBitGraph::from_bit_graph(&a);
+            if let Some(w) = f.neighbors(u).next() {
+                f.contract_edge(u, w);
+                g.contract_edge(u, w);
+            }
+            f.clear_vertex(5);
+            g.clear_vertex(5);
+            assert_eq!(g.to_bit_graph(), f);
+        }
+    }
+}
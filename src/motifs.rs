@@ -0,0 +1,280 @@
+//! Counting small induced subgraphs
+
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::{BitSetAnd, BitSetLike};
+
+/// Number of induced copies of every connected graph on 4 vertices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Motifs4 {
+    /// Paths on 4 vertices
+    pub paths: u64,
+    /// Stars with 3 leaves
+    pub stars: u64,
+    /// Cycles on 4 vertices
+    pub cycles: u64,
+    /// Triangles with one pendant edge
+    pub paws: u64,
+    /// Cliques on 4 vertices without one edge
+    pub diamonds: u64,
+    /// Cliques on 4 vertices
+    pub cliques: u64,
+}
+
+impl BitGraph {
+    /// Counts the induced connected subgraphs on 4 vertices. Counts the (not necessarily
+    /// induced) copies of each shape from degrees and common neighbor counts, which are popcounts
+    /// of row intersections, and then subtracts the copies contained in denser shapes
+    pub fn count_motifs4(&self) -> Motifs4 {
+        let n = self.m_data.len() as u32;
+        let choose2 = |x: u64| x * x.saturating_sub(1) / 2;
+        let choose3 = |x: u64| x * x.saturating_sub(1) * x.saturating_sub(2) / 6;
+        let mut cliques = 0;
+        let mut diamonds = 0;
+        let mut paws = 0;
+        let mut stars = 0;
+        let mut paths = 0;
+        let mut triangles = 0;
+        let mut triangles_at = vec![0u64; n as usize];
+        for u in 0..n {
+            let du = self.degree(u) as u64;
+            stars += choose3(du);
+            for v in self.neighbors(u).filter(|&v| u < v) {
+                let common = BitSetAnd(self.row(u), self.row(v));
+                let t = self.common_count(u, v) as u64;
+                diamonds += choose2(t);
+                paths += (du - 1) * (self.degree(v) as u64 - 1);
+                for w in (&common).iter().filter(|&w| v < w) {
+                    triangles += 1;
+                    triangles_at[u as usize] += 1;
+                    triangles_at[v as usize] += 1;
+                    triangles_at[w as usize] += 1;
+                    cliques += BitSetAnd(&common, self.row(w))
+                        .iter()
+                        .filter(|&x| w < x)
+                        .count() as u64;
+                }
+            }
+        }
+        paths -= 3 * triangles;
+        for v in 0..n {
+            paws += triangles_at[v as usize] * (self.degree(v) as u64).saturating_sub(2);
+        }
+        // every 4-cycle is seen from both pairs of opposite vertices
+        let mut cycles = 0;
+        for u in 0..n {
+            for w in u + 1..n {
+                cycles += choose2(self.common_count(u, w) as u64);
+            }
+        }
+        cycles /= 2;
+
+        let diamonds = diamonds - 6 * cliques;
+        let cycles = cycles - diamonds - 3 * cliques;
+        let paws = paws - 4 * diamonds - 12 * cliques;
+        Motifs4 {
+            paths: paths - 2 * paws - 4 * cycles - 6 * diamonds - 12 * cliques,
+            stars: stars - paws - 2 * diamonds - 4 * cliques,
+            cycles,
+            paws,
+            diamonds,
+            cliques,
+        }
+    }
+}
+
+/// Number of automorphism orbits of the graphlets on 2 to 4 vertices
+pub const GRAPHLET_ORBITS: usize = 15;
+
+impl BitGraph {
+    /// Graphlet degree vector of every vertex: entry `i` counts the induced connected subgraphs
+    /// on up to 4 vertices in which the vertex sits in orbit `i`, numbered as by Pržulj.
+    /// Orbits 0 to 3 follow from degrees and triangles, the 4-vertex subgraphs are enumerated
+    /// once each with the ESU algorithm
+    pub fn graphlet_degree_vectors(&self) -> Vec<[u64; GRAPHLET_ORBITS]> {
+        let n = self.m_data.len() as u32;
+        let mut gdv = vec![[0u64; GRAPHLET_ORBITS]; n as usize];
+        let mut triangles_at = vec![0u64; n as usize];
+        for u in 0..n {
+            for v in self.neighbors(u).filter(|&v| u < v) {
+                for w in BitSetAnd(self.row(u), self.row(v))
+                    .iter()
+                    .filter(|&w| v < w)
+                {
+                    triangles_at[u as usize] += 1;
+                    triangles_at[v as usize] += 1;
+                    triangles_at[w as usize] += 1;
+                }
+            }
+        }
+        for v in 0..n {
+            let d = self.degree(v) as u64;
+            let t = triangles_at[v as usize];
+            let ends: u64 = self.neighbors(v).map(|w| self.degree(w) as u64 - 1).sum();
+            let orbits = &mut gdv[v as usize];
+            orbits[0] = d;
+            orbits[1] = ends - 2 * t;
+            orbits[2] = d * d.saturating_sub(1) / 2 - t;
+            orbits[3] = t;
+        }
+        for v in 0..n {
+            let extension: Vec<u32> = self.neighbors(v).filter(|&u| u > v).collect();
+            self.extend_subgraph(&mut vec![v], extension, v, &mut gdv);
+        }
+        gdv
+    }
+
+    /// One step of ESU, `root` is the smallest vertex of every subgraph grown from `set`
+    fn extend_subgraph(
+        &self,
+        set: &mut Vec<u32>,
+        mut extension: Vec<u32>,
+        root: u32,
+        gdv: &mut [[u64; GRAPHLET_ORBITS]],
+    ) {
+        if set.len() == 4 {
+            self.count_orbits4(set, gdv);
+            return;
+        }
+        while let Some(w) = extension.pop() {
+            let mut next = extension.clone();
+            // exclusive neighbors of `w`: outside of the set and not adjacent to it
+            for u in self.neighbors(w).filter(|&u| u > root) {
+                if !set.contains(&u)
+                    && !next.contains(&u)
+                    && set.iter().all(|&x| !self.row(x).contains(u))
+                {
+                    next.push(u);
+                }
+            }
+            set.push(w);
+            self.extend_subgraph(set, next, root, gdv);
+            set.pop();
+        }
+    }
+
+    fn count_orbits4(&self, set: &[u32], gdv: &mut [[u64; GRAPHLET_ORBITS]]) {
+        let mut degrees = [0usize; 4];
+        for (i, &v) in set.iter().enumerate() {
+            degrees[i] = set.iter().filter(|&&w| self.row(v).contains(w)).count();
+        }
+        let edges = degrees.iter().sum::<usize>() / 2;
+        let max = *degrees.iter().max().unwrap();
+        for (i, &v) in set.iter().enumerate() {
+            let orbit = match (edges, max, degrees[i]) {
+                (3, 2, 1) => 4,
+                (3, 2, _) => 5,
+                (3, 3, 1) => 6,
+                (3, 3, _) => 7,
+                (4, 2, _) => 8,
+                (4, 3, 1) => 9,
+                (4, 3, 2) => 10,
+                (4, 3, _) => 11,
+                (5, _, 2) => 12,
+                (5, _, _) => 13,
+                _ => 14,
+            };
+            gdv[v as usize][orbit] += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Motifs4, GRAPHLET_ORBITS};
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    /// Classifies every 4-subset by its sorted degree sequence, within the subset
+    fn brute_force(c: &BitGraph) -> Motifs4 {
+        let n = c.m_degrees.len() as u32;
+        let mut m = Motifs4::default();
+        for a in 0..n {
+            for b in a + 1..n {
+                for x in b + 1..n {
+                    for d in x + 1..n {
+                        let set = [a, b, x, d];
+                        let mut degrees: Vec<usize> = set
+                            .iter()
+                            .map(|&v| {
+                                set.iter()
+                                    .filter(|&&w| c.neighbors(v).any(|y| y == w))
+                                    .count()
+                            })
+                            .collect();
+                        degrees.sort_unstable();
+                        match degrees.as_slice() {
+                            [1, 1, 2, 2] => m.paths += 1,
+                            [1, 1, 1, 3] => m.stars += 1,
+                            [2, 2, 2, 2] => m.cycles += 1,
+                            [1, 2, 2, 3] => m.paws += 1,
+                            [2, 2, 3, 3] => m.diamonds += 1,
+                            [3, 3, 3, 3] => m.cliques += 1,
+                            // disconnected subsets have a vertex of degree 0 or are [1, 1, 1, 1]
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        m
+    }
+
+    #[test]
+    fn count_motifs4() {
+        let m = BitGraph::complete(5).count_motifs4();
+        assert_eq!(
+            m,
+            Motifs4 {
+                cliques: 5,
+                ..Motifs4::default()
+            }
+        );
+        assert_eq!(generators::cycle(4).count_motifs4().cycles, 1);
+        assert_eq!(generators::star(6).count_motifs4().stars, 10);
+        let mut rng = SplitMix64::new(12);
+        for &p in [0.2, 0.4, 0.7].iter() {
+            for _ in 0..5 {
+                let c = generators::gnp(12, p, &mut rng);
+                assert_eq!(c.count_motifs4(), brute_force(&c));
+            }
+        }
+    }
+
+    #[test]
+    fn graphlet_degree_vectors() {
+        let gdv = generators::star(4).graphlet_degree_vectors();
+        let mut leaf = [0; GRAPHLET_ORBITS];
+        leaf[0] = 1;
+        leaf[1] = 2;
+        leaf[6] = 1;
+        assert_eq!(gdv[1], leaf);
+        let mut center = [0; GRAPHLET_ORBITS];
+        center[0] = 3;
+        center[2] = 3;
+        center[7] = 1;
+        assert_eq!(gdv[0], center);
+        let gdv = BitGraph::complete(4).graphlet_degree_vectors();
+        assert!(gdv.iter().all(|o| o[0] == 3 && o[3] == 3 && o[14] == 1));
+
+        let mut rng = SplitMix64::new(13);
+        for &p in [0.2, 0.5].iter() {
+            let c = generators::gnp(14, p, &mut rng);
+            let gdv = c.graphlet_degree_vectors();
+            let m = brute_force(&c);
+            let total = |i: usize| gdv.iter().map(|o| o[i]).sum::<u64>();
+            assert_eq!(total(3), 3 * c.triangle_count());
+            assert_eq!((total(4), total(5)), (2 * m.paths, 2 * m.paths));
+            assert_eq!((total(6), total(7)), (3 * m.stars, m.stars));
+            assert_eq!(total(8), 4 * m.cycles);
+            assert_eq!(
+                (total(9), total(10), total(11)),
+                (m.paws, 2 * m.paws, m.paws)
+            );
+            assert_eq!((total(12), total(13)), (2 * m.diamonds, 2 * m.diamonds));
+            assert_eq!(total(14), 4 * m.cliques);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Parallel edges on top of a `BitGraph`
+
+use crate::random::RandomSource;
+use crate::BitGraph;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::BitIter;
+
+/// An undirected multigraph without self-loops. The rows of a `BitGraph` tell which vertices
+/// are adjacent, and a map counts the edges of every pair with more than one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMultiGraph {
+    m_graph: BitGraph,
+    m_extra: BTreeMap<(u32, u32), u32>,
+    m_degrees: Vec<u64>,
+    m_size: u64,
+}
+
+/// Map key of the pair `(u, v)`
+#[inline]
+fn key(u: u32, v: u32) -> (u32, u32) {
+    (u.min(v), u.max(v))
+}
+
+impl BitMultiGraph {
+    /// Creates a new BitMultiGraph with `capacity` vertices and no edges
+    pub fn with_capacity(capacity: u32) -> BitMultiGraph {
+        BitMultiGraph {
+            m_graph: BitGraph::with_capacity(capacity),
+            m_extra: BTreeMap::new(),
+            m_degrees: vec![0; capacity as usize],
+            m_size: 0,
+        }
+    }
+
+    /// Returns `graph` with every edge once
+    pub fn from_graph(graph: BitGraph) -> BitMultiGraph {
+        BitMultiGraph {
+            m_degrees: (0..graph.capacity())
+                .map(|v| graph.degree(v) as u64)
+                .collect(),
+            m_size: graph.size(),
+            m_graph: graph,
+            m_extra: BTreeMap::new(),
+        }
+    }
+
+    /// Number of vertices the graph can hold
+    pub fn capacity(&self) -> u32 {
+        self.m_graph.capacity()
+    }
+
+    /// Adds `count` parallel edges between `u` and `v`
+    pub fn add_edges(&mut self, u: u32, v: u32, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let before = self.multiplicity(u, v);
+        self.set_multiplicity(u, v, before + count);
+    }
+
+    /// Adds one more edge between `u` and `v`
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        self.add_edges(u, v, 1);
+    }
+
+    /// Removes one of the edges between `u` and `v`, if there is one
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        let before = self.multiplicity(u, v);
+        if before > 0 {
+            self.set_multiplicity(u, v, before - 1);
+        }
+    }
+
+    /// Removes all edges between `u` and `v` and returns how many there were
+    pub fn remove_all_edges(&mut self, u: u32, v: u32) -> u32 {
+        let before = self.multiplicity(u, v);
+        self.set_multiplicity(u, v, 0);
+        before
+    }
+
+    /// Number of edges between `u` and `v`
+    pub fn multiplicity(&self, u: u32, v: u32) -> u32 {
+        self.m_graph.check_bounds(u);
+        self.m_graph.check_bounds(v);
+        if !self.m_graph.row(u).contains(v) {
+            0
+        } else {
+            self.m_extra.get(&key(u, v)).copied().unwrap_or(1)
+        }
+    }
+
+    /// Number of edges incident to `v`, counted with multiplicity
+    pub fn degree(&self, v: u32) -> u64 {
+        self.m_graph.check_bounds(v);
+        self.m_degrees[v as usize]
+    }
+
+    /// Number of edges, counted with multiplicity
+    pub fn size(&self) -> u64 {
+        self.m_size
+    }
+
+    /// Returns an iterator over the distinct neighbors of `v`
+    pub fn neighbors(&self, v: u32) -> BitIter<&hibitset::BitSet> {
+        self.m_graph.neighbors(v)
+    }
+
+    /// The simple graph with an edge for every adjacent pair
+    pub fn graph(&self) -> &BitGraph {
+        &self.m_graph
+    }
+
+    /// Contracts all edges between `target` and `source` like `BitGraph::contract_edge`. The
+    /// edges of `source` move to `target`, so a neighbor of both ends up with the sum of both
+    /// multiplicities. The contracted edges would become loops and are dropped
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        let joined = self.multiplicity(target, source);
+        let moved: Vec<(u32, u32)> = self
+            .m_graph
+            .neighbors(source)
+            .filter(|&w| w != target)
+            .map(|w| (w, self.multiplicity(source, w)))
+            .collect();
+        let merged: Vec<u32> = moved
+            .iter()
+            .map(|&(w, count)| count + self.multiplicity(target, w))
+            .collect();
+        self.m_graph.contract_edge(target, source);
+        for (&(w, _), &count) in moved.iter().zip(merged.iter()) {
+            self.m_extra.remove(&key(source, w));
+            if count > 1 {
+                self.m_extra.insert(key(target, w), count);
+            } else {
+                self.m_extra.remove(&key(target, w));
+            }
+        }
+        self.m_extra.remove(&key(target, source));
+        let degree = core::mem::replace(&mut self.m_degrees[source as usize], 0);
+        // both degrees counted the contracted edges
+        self.m_degrees[target as usize] =
+            self.m_degrees[target as usize] + degree - 2 * joined as u64;
+        self.m_size -= joined as u64;
+    }
+
+    /// Returns the size of the smallest cut found by `trials` runs of Karger's algorithm, which
+    /// contracts edges picked with probability proportional to their multiplicity until two
+    /// vertices are left. A single run finds the minimum cut with probability at least
+    /// `2 / (n * (n - 1))`. Isolated vertices are ignored, a disconnected graph has a cut of 0
+    pub fn karger_min_cut<R: RandomSource>(&self, trials: u32, rng: &mut R) -> u64 {
+        let mut best = u64::MAX;
+        for _ in 0..trials {
+            let mut c = self.clone();
+            // contracted vertices can become isolated, so count the merged vertices left
+            let mut left = c.m_graph.order();
+            while left > 2 && c.m_size > 0 {
+                let mut pick = rng.below(c.m_size);
+                let (u, v) = c
+                    .m_graph
+                    .edges()
+                    .find(|&(u, v)| {
+                        let count = c.multiplicity(u, v) as u64;
+                        if pick < count {
+                            return true;
+                        }
+                        pick -= count;
+                        false
+                    })
+                    .unwrap();
+                c.contract_edge(u, v);
+                left -= 1;
+            }
+            let cut = if left > 2 { 0 } else { c.m_size };
+            best = best.min(cut);
+        }
+        best
+    }
+
+    fn set_multiplicity(&mut self, u: u32, v: u32, count: u32) {
+        let before = self.multiplicity(u, v);
+        match count {
+            0 => {
+                self.m_graph.remove_edge(u, v);
+                self.m_extra.remove(&key(u, v));
+            }
+            1 => {
+                self.m_graph.add_edge(u, v);
+                self.m_extra.remove(&key(u, v));
+            }
+            _ => {
+                self.m_graph.add_edge(u, v);
+                self.m_extra.insert(key(u, v), count);
+            }
+        }
+        let delta = count as i64 - before as i64;
+        for &x in [u, v].iter() {
+            self.m_degrees[x as usize] = (self.m_degrees[x as usize] as i64 + delta) as u64;
+        }
+        self.m_size = (self.m_size as i64 + delta) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::{BitGraph, BitMultiGraph};
+
+    #[test]
+    fn multiplicities() {
+        let capacity: usize = 5;
+        let mut c = BitMultiGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 0);
+        c.add_edges(1, 2, 3);
+        c.add_edge(0, 2);
+        c.add_edge(2, 3);
+        assert_eq!(c.multiplicity(0, 1), 2);
+        assert_eq!(c.multiplicity(2, 1), 3);
+        assert_eq!(c.size(), 7);
+        assert_eq!(c.degree(1), 5);
+        assert_eq!(c.graph().size(), 4);
+        c.remove_edge(1, 2);
+        assert_eq!(c.multiplicity(1, 2), 2);
+        assert_eq!(c.remove_all_edges(3, 2), 1);
+        assert_eq!(c.degree(3), 0);
+        assert_eq!(c.graph().order(), 3);
+
+        // 1 moves into 0, its two edges to 2 join the one of 0
+        c.contract_edge(0, 1);
+        assert_eq!(c.multiplicity(0, 2), 3);
+        assert_eq!(c.multiplicity(0, 1), 0);
+        assert_eq!(c.size(), 3);
+        assert_eq!((c.degree(0), c.degree(1), c.degree(2)), (3, 0, 3));
+
+        let mut d = BitMultiGraph::with_capacity(capacity as u32);
+        d.add_edges(0, 2, 3);
+        assert_eq!(c, d);
+        assert_eq!(
+            BitMultiGraph::from_graph(BitGraph::complete(4)).degree(2),
+            3
+        );
+    }
+
+    #[test]
+    fn karger_min_cut() {
+        // two 5-cliques joined by two edges
+        let mut c = BitMultiGraph::with_capacity(10);
+        for u in 0..5 {
+            for v in u + 1..5 {
+                c.add_edges(u, v, 2);
+                c.add_edges(u + 5, v + 5, 2);
+            }
+        }
+        c.add_edge(0, 5);
+        c.add_edge(3, 8);
+        let mut rng = SplitMix64::new(73);
+        assert_eq!(c.karger_min_cut(60, &mut rng), 2);
+        let cycle = BitMultiGraph::from_graph(generators::cycle(8));
+        assert_eq!(cycle.karger_min_cut(30, &mut rng), 2);
+        let mut two = BitMultiGraph::with_capacity(5);
+        two.add_edges(0, 1, 4);
+        two.add_edge(2, 3);
+        assert_eq!(two.karger_min_cut(5, &mut rng), 0);
+        two.remove_all_edges(2, 3);
+        assert_eq!(two.karger_min_cut(5, &mut rng), 4);
+    }
+}
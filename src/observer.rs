@@ -0,0 +1,182 @@
+//! Callbacks on mutations, to keep structures outside of the graph in sync
+
+use crate::BitGraph;
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+/// Receives the changes made through an `ObservedBitGraph`. Every method is called after the
+/// change with the updated graph and does nothing by default
+pub trait GraphObserver {
+    /// The new edge `(u, v)` was added
+    fn edge_added(&mut self, _graph: &BitGraph, _u: u32, _v: u32) {}
+
+    /// The edge `(u, v)` was removed
+    fn edge_removed(&mut self, _graph: &BitGraph, _u: u32, _v: u32) {}
+
+    /// The edge `(target, source)` was contracted into `target`
+    fn contracted(&mut self, _graph: &BitGraph, _target: u32, _source: u32) {}
+}
+
+/// A graph whose mutations are reported to an observer, returned by `BitGraph::observe`.
+/// Queries go to the graph through `Deref`
+#[derive(Debug)]
+pub struct ObservedBitGraph<'a, O: GraphObserver> {
+    m_graph: &'a mut BitGraph,
+    m_observer: &'a mut O,
+}
+
+impl<'a, O: GraphObserver> ObservedBitGraph<'a, O> {
+    /// Same as `BitGraph::add_edge`, reports the edge if it is new
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        let before = self.m_graph.size();
+        self.m_graph.add_edge(u, v);
+        if self.m_graph.size() > before {
+            self.m_observer.edge_added(self.m_graph, u, v);
+        }
+    }
+
+    /// Same as `BitGraph::remove_edge`, reports the edge if it was present
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        let before = self.m_graph.size();
+        self.m_graph.remove_edge(u, v);
+        if self.m_graph.size() < before {
+            self.m_observer.edge_removed(self.m_graph, u, v);
+        }
+    }
+
+    /// Same as `BitGraph::contract_edge`
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        self.m_graph.contract_edge(target, source);
+        self.m_observer.contracted(self.m_graph, target, source);
+    }
+
+    /// Same as `BitGraph::add_edges`, reports every new edge once
+    pub fn add_edges(&mut self, edges: &[(u32, u32)]) {
+        let mut fresh: Vec<(u32, u32)> = edges
+            .iter()
+            .filter(|&&(u, v)| u < self.m_graph.capacity() && !self.m_graph.row(u).contains(v))
+            .map(|&(u, v)| (u.min(v), u.max(v)))
+            .collect();
+        self.m_graph.add_edges(edges);
+        fresh.sort_unstable();
+        fresh.dedup();
+        for (u, v) in fresh {
+            self.m_observer.edge_added(self.m_graph, u, v);
+        }
+    }
+
+    /// Same as `BitGraph::clear_vertex`, reports every removed edge
+    pub fn clear_vertex(&mut self, v: u32) {
+        let neighbors: Vec<u32> = self.m_graph.neighbors(v).collect();
+        for w in neighbors {
+            self.remove_edge(v, w);
+        }
+    }
+
+    /// The observer, to read the state it keeps
+    pub fn observer(&self) -> &O {
+        self.m_observer
+    }
+}
+
+impl<'a, O: GraphObserver> Deref for ObservedBitGraph<'a, O> {
+    type Target = BitGraph;
+
+    fn deref(&self) -> &BitGraph {
+        self.m_graph
+    }
+}
+
+impl BitGraph {
+    /// Returns a handle that mutates the graph and reports every change to `observer`.
+    /// Changes made to the graph directly are not reported
+    pub fn observe<'a, O: GraphObserver>(
+        &'a mut self,
+        observer: &'a mut O,
+    ) -> ObservedBitGraph<'a, O> {
+        ObservedBitGraph {
+            m_graph: self,
+            m_observer: observer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::{BitGraph, GraphObserver};
+
+    /// Union-find over the vertices, which only ever merges, and the number of edges
+    struct Components {
+        m_parent: Vec<u32>,
+        m_size: u64,
+    }
+
+    impl Components {
+        fn find(&mut self, v: u32) -> u32 {
+            let mut v = v;
+            while self.m_parent[v as usize] != v {
+                let grandparent = self.m_parent[self.m_parent[v as usize] as usize];
+                self.m_parent[v as usize] = grandparent;
+                v = grandparent;
+            }
+            v
+        }
+
+        fn union(&mut self, u: u32, v: u32) {
+            let (a, b) = (self.find(u), self.find(v));
+            self.m_parent[a.max(b) as usize] = a.min(b);
+        }
+    }
+
+    impl GraphObserver for Components {
+        fn edge_added(&mut self, graph: &BitGraph, u: u32, v: u32) {
+            assert!(graph.row(u).contains(v));
+            self.m_size += 1;
+            self.union(u, v);
+        }
+
+        fn edge_removed(&mut self, graph: &BitGraph, u: u32, v: u32) {
+            assert!(!graph.row(u).contains(v));
+            self.m_size -= 1;
+        }
+
+        fn contracted(&mut self, graph: &BitGraph, _target: u32, source: u32) {
+            assert_eq!(graph.degree(source), 0);
+            self.m_size = graph.size();
+        }
+    }
+
+    #[test]
+    fn observer() {
+        let capacity: usize = 50;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        let mut components = Components {
+            m_parent: (0..capacity as u32).collect(),
+            m_size: 0,
+        };
+        {
+            let mut observed = c.observe(&mut components);
+            let mut rng = SplitMix64::new(69);
+            for _ in 0..40 {
+                let u = rng.below(capacity as u64) as u32;
+                let v = rng.below(capacity as u64) as u32;
+                if u != v {
+                    observed.add_edge(u, v);
+                    observed.add_edge(v, u);
+                }
+            }
+            observed.add_edges(&[(0, 1), (1, 0), (2, 3), (0, 1)]);
+            let w = observed.neighbors(0).next().unwrap();
+            observed.contract_edge(0, w);
+            observed.remove_edge(1, 2);
+            observed.clear_vertex(3);
+            assert_eq!(observed.observer().m_size, observed.size());
+        }
+        assert_eq!(components.m_size, c.size());
+        // the union-find never splits, so both ends of every edge are in one of its sets
+        for (u, v) in c.edges() {
+            assert_eq!(components.find(u), components.find(v));
+        }
+    }
+}
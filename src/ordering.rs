@@ -0,0 +1,311 @@
+//! Vertex orderings
+
+use crate::BitGraph;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+impl BitGraph {
+    /// Returns all vertices in Cuthill–McKee order, which keeps adjacent vertices close and so
+    /// reduces the bandwidth of the adjacency matrix. Every component is searched breadth first
+    /// from one of its vertices of smallest degree, and the unvisited neighbors of a vertex are
+    /// queued by increasing degree
+    pub fn cuthill_mckee(&self) -> Vec<u32> {
+        let n = self.m_data.len() as u32;
+        let mut order: Vec<u32> = Vec::with_capacity(n as usize);
+        let mut visited = hibitset::BitSet::with_capacity(n);
+        let mut queue = VecDeque::new();
+        let mut component: Vec<u32> = Vec::new();
+        for s in 0..n {
+            if visited.contains(s) {
+                continue;
+            }
+            // collects the component first to start at a vertex of smallest degree in it
+            component.clear();
+            component.push(s);
+            visited.add(s);
+            let mut i = 0;
+            while i < component.len() {
+                for w in self.neighbors(component[i]) {
+                    if !visited.add(w) {
+                        component.push(w);
+                    }
+                }
+                i += 1;
+            }
+            for &v in component.iter() {
+                visited.remove(v);
+            }
+            let start = *component
+                .iter()
+                .min_by_key(|&&v| (self.degree(v), v))
+                .unwrap();
+            visited.add(start);
+            queue.push_back(start);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let mut next: Vec<u32> = self.neighbors(v).filter(|&w| !visited.add(w)).collect();
+                next.sort_by_key(|&w| (self.degree(w), w));
+                queue.extend(next);
+            }
+        }
+        order
+    }
+
+    /// Returns all vertices in reverse Cuthill–McKee order, which usually produces less fill-in
+    /// than `cuthill_mckee` during a Cholesky factorization
+    pub fn reverse_cuthill_mckee(&self) -> Vec<u32> {
+        let mut order = self.cuthill_mckee();
+        order.reverse();
+        order
+    }
+
+    /// Largest distance in `order` between the two ends of an edge, `order` lists every vertex
+    /// once. This is the bandwidth of the adjacency matrix with rows and columns in that order
+    pub fn bandwidth(&self, order: &[u32]) -> u32 {
+        let position = self.positions(order);
+        self.edges()
+            .map(|(u, v)| position[u as usize].abs_diff(position[v as usize]))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Sum over all vertices of the distance in `order` to their earliest neighbor before them,
+    /// `order` lists every vertex once. This is the size of the envelope of the lower triangle
+    /// of the adjacency matrix with rows and columns in that order
+    pub fn profile(&self, order: &[u32]) -> u64 {
+        let position = self.positions(order);
+        order
+            .iter()
+            .map(|&v| {
+                let i = position[v as usize];
+                let first = self
+                    .neighbors(v)
+                    .map(|w| position[w as usize])
+                    .min()
+                    .unwrap_or(i);
+                i.saturating_sub(first) as u64
+            })
+            .sum()
+    }
+
+    /// Returns a copy of the graph in which vertex `v` is renamed to `perm[v]`, `perm` has to be
+    /// a permutation of all vertices
+    pub fn relabel(&self, perm: &[u32]) -> BitGraph {
+        self.positions(perm);
+        let n = self.m_data.len() as u32;
+        let mut rows: Vec<hibitset::BitSet> = (0..n).map(|_| hibitset::BitSet::new()).collect();
+        for v in 0..n {
+            let row = &mut rows[perm[v as usize] as usize];
+            for w in self.neighbors(v) {
+                row.add(perm[w as usize]);
+            }
+        }
+        BitGraph::from_rows(rows)
+    }
+
+    /// Renames every vertex `v` to `perm[v]` like `relabel`, but in place. Cached invariants
+    /// are kept, as they do not depend on the vertex names
+    pub fn relabel_in_place(&mut self, perm: &[u32]) {
+        let relabeled = self.relabel(perm);
+        self.journal_begin();
+        for v in 0..self.m_data.len() as u32 {
+            self.journal_row(v);
+        }
+        self.m_data = relabeled.m_data;
+        self.m_degrees = relabeled.m_degrees;
+        if self.has_degree_buckets() {
+            self.disable_degree_buckets();
+            self.enable_degree_buckets();
+        }
+    }
+
+    /// Returns a copy without the isolated vertices, in which the remaining vertices are numbered
+    /// densely in their old order, and the new number of every old vertex. Isolated vertices
+    /// map to `u32::MAX`. The copy has a capacity of `order()`
+    pub fn compact(&self) -> (BitGraph, Vec<u32>) {
+        let mut mapping = vec![u32::MAX; self.m_data.len()];
+        let mut next = 0;
+        for v in (0..self.m_data.len()).filter(|&v| self.m_degrees[v] > 0) {
+            mapping[v] = next;
+            next += 1;
+        }
+        let mut rows: Vec<hibitset::BitSet> = (0..next).map(|_| hibitset::BitSet::new()).collect();
+        for (v, &new) in mapping
+            .iter()
+            .enumerate()
+            .filter(|(_, &new)| new != u32::MAX)
+        {
+            for w in self.neighbors(v as u32) {
+                rows[new as usize].add(mapping[w as usize]);
+            }
+        }
+        (BitGraph::from_rows(rows), mapping)
+    }
+
+    /// Position of every vertex in `order`, which has to list every vertex once
+    fn positions(&self, order: &[u32]) -> Vec<u32> {
+        let n = self.m_data.len();
+        if order.len() != n {
+            panic!("Order needs {} vertices, given: {}", n, order.len())
+        }
+        let mut position = vec![u32::MAX; n];
+        for (i, &v) in order.iter().enumerate() {
+            self.check_bounds(v);
+            if position[v as usize] != u32::MAX {
+                panic!("Vertex {} appears twice in the order", v)
+            }
+            position[v as usize] = i as u32;
+        }
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::BitGraph;
+
+    #[test]
+    fn cuthill_mckee() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(0, 5), (5, 2), (2, 7), (7, 4), (8, 1)].iter() {
+            c.add_edge(u, v);
+        }
+        assert_eq!(c.cuthill_mckee(), vec![0, 5, 2, 7, 4, 1, 8, 3, 6, 9]);
+        assert_eq!(
+            c.reverse_cuthill_mckee(),
+            vec![9, 6, 3, 8, 1, 4, 7, 2, 5, 0]
+        );
+
+        // a star starts at a leaf, the center comes right after it
+        assert_eq!(generators::star(5).cuthill_mckee(), vec![1, 0, 2, 3, 4]);
+
+        // a shuffled grid gets back to a bandwidth close to its width
+        let grid = generators::grid(6, 20, false);
+        let mut rng = SplitMix64::new(12);
+        let mut perm: Vec<u32> = (0..120).collect();
+        for i in (1..perm.len()).rev() {
+            perm.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        let mut shuffled = BitGraph::with_capacity(120);
+        for (u, v) in grid.edges() {
+            shuffled.add_edge(perm[u as usize], perm[v as usize]);
+        }
+        let identity: Vec<u32> = (0..120).collect();
+        assert!(shuffled.bandwidth(&identity) > 50);
+        let order = shuffled.cuthill_mckee();
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, identity);
+        assert!(shuffled.bandwidth(&order) <= 8);
+        assert_eq!(
+            shuffled.bandwidth(&shuffled.reverse_cuthill_mckee()),
+            shuffled.bandwidth(&order)
+        );
+    }
+
+    #[test]
+    fn bandwidth_and_profile() {
+        let capacity: usize = 5;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(0, 1), (1, 2), (2, 3), (0, 3)].iter() {
+            c.add_edge(u, v);
+        }
+        let identity: Vec<u32> = (0..5).collect();
+        assert_eq!(c.bandwidth(&identity), 3);
+        assert_eq!(c.profile(&identity), 1 + 1 + 3);
+        let order = [0, 1, 3, 2, 4];
+        assert_eq!(c.bandwidth(&order), 2);
+        assert_eq!(c.profile(&order), 1 + 2 + 2);
+        assert_eq!(c.bandwidth(&c.cuthill_mckee()), 2);
+        assert_eq!(
+            generators::path(6).profile(&generators::path(6).cuthill_mckee()),
+            5
+        );
+        assert_eq!(BitGraph::with_capacity(3).bandwidth(&[2, 0, 1]), 0);
+        assert_eq!(BitGraph::complete(4).profile(&[3, 1, 0, 2]), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bandwidth_repeated_vertex() {
+        generators::path(3).bandwidth(&[0, 1, 1]);
+    }
+
+    #[test]
+    fn relabel() {
+        let capacity: usize = 6;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(0, 1), (1, 2), (2, 0), (2, 3)].iter() {
+            c.add_edge(u, v);
+        }
+        let perm = [5, 3, 0, 1, 2, 4];
+        let d = c.relabel(&perm);
+        let tmp: Vec<(u32, u32)> = d.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (0, 3), (0, 5), (3, 5)]);
+        assert_eq!(d.degree(0), 3);
+        assert_eq!(d.order(), 4);
+
+        c.enable_invariant_cache();
+        c.enable_degree_buckets();
+        assert_eq!(c.triangle_count(), 1);
+        c.relabel_in_place(&perm);
+        assert_eq!(c, d);
+        assert_eq!(c.triangle_count(), 1);
+        assert_eq!(c.max_degree_vertex(), Some(0));
+        c.add_edge(4, 5);
+        assert_eq!(c.degree(5), 3);
+
+        // rows of the copy only span up to their largest neighbor
+        let capacity: usize = 100_000;
+        let c = generators::star(capacity as u32);
+        let perm: Vec<u32> = (0..capacity as u32)
+            .map(|v| if v == 0 { 0 } else { capacity as u32 - v })
+            .collect();
+        let d = c.relabel(&perm);
+        assert_eq!(d.degree(0), capacity as u32 - 1);
+        assert!(d.memory_usage() < 256 * capacity);
+    }
+
+    #[test]
+    #[should_panic]
+    fn relabel_not_a_permutation() {
+        generators::path(3).relabel(&[0, 2, 0]);
+    }
+
+    #[test]
+    fn compact() {
+        let capacity: usize = 100;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for &(u, v) in [(90, 10), (10, 40), (40, 90), (40, 70)].iter() {
+            c.add_edge(u, v);
+        }
+        let (d, mapping) = c.compact();
+        assert_eq!(d.m_data.len(), 4);
+        assert_eq!(d.order(), 4);
+        assert_eq!(
+            (mapping[10], mapping[40], mapping[70], mapping[90]),
+            (0, 1, 2, 3)
+        );
+        assert_eq!(mapping.iter().filter(|&&x| x == u32::MAX).count(), 96);
+        let tmp: Vec<(u32, u32)> = d.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (0, 3), (1, 2), (1, 3)]);
+
+        let (d, mapping) = BitGraph::with_capacity(5).compact();
+        assert_eq!(d.m_data.len(), 0);
+        assert_eq!(mapping, vec![u32::MAX; 5]);
+
+        // rows of the copy only span up to their largest neighbor
+        let capacity: usize = 200_000;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for v in (2..capacity as u32).step_by(2) {
+            c.add_edge(0, v);
+        }
+        let (d, _) = c.compact();
+        assert_eq!(d.m_data.len(), capacity / 2);
+        assert!(d.memory_usage() < 128 * capacity);
+    }
+}
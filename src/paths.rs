@@ -0,0 +1,238 @@
+//! Path finding between vertices
+
+use crate::algorithms::{self, unwind};
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+
+const NONE: u32 = u32::MAX;
+
+impl BitGraph {
+    /// Returns some path from `u` to `v` found by a depth first search, `None` if `v` is not
+    /// reachable from `u`. The path is not necessarily a shortest one
+    pub fn find_path(&self, u: u32, v: u32) -> Option<Vec<u32>> {
+        algorithms::find_path(self, u, v)
+    }
+
+    /// Returns a shortest path from `u` to `v` found by a breadth first search,
+    /// `None` if `v` is not reachable from `u`
+    pub fn shortest_path(&self, u: u32, v: u32) -> Option<Vec<u32>> {
+        algorithms::shortest_path(self, u, v)
+    }
+
+    /// Returns the vertex closest to `v` for which `predicate` holds, `v` itself included.
+    /// The breadth first search stops as soon as a match is found
+    pub fn search<P: FnMut(u32) -> bool>(&self, v: u32, predicate: P) -> Option<u32> {
+        self.search_path(v, predicate)
+            .map(|path| *path.last().unwrap())
+    }
+
+    /// Like `search`, but returns a shortest path from `v` to the found vertex
+    pub fn search_path<P: FnMut(u32) -> bool>(&self, v: u32, predicate: P) -> Option<Vec<u32>> {
+        algorithms::search_path(self, v, predicate)
+    }
+
+    /// Returns `true` if `v` is reachable from `u`, stopping the search as soon as it is found
+    pub fn contains_on_path(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.dfs(u).any(|x| x == v)
+    }
+
+    /// Returns a shortest path from `u` to `v`, `None` if `v` is not reachable from `u`.
+    /// Grows frontiers from both endpoints, always expanding the smaller one by a full level,
+    /// and stops as soon as they meet. On high-diameter graphs this visits far fewer
+    /// vertices than `shortest_path`
+    pub fn shortest_path_bidirectional(&self, u: u32, v: u32) -> Option<Vec<u32>> {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if u == v {
+            return Some(vec![u]);
+        }
+        let n = self.m_data.len();
+        let mut sides = [BfsSide::new(n, u), BfsSide::new(n, v)];
+        loop {
+            if sides[0].frontier.is_empty() || sides[1].frontier.is_empty() {
+                return None;
+            }
+            let s = if sides[0].frontier.len() <= sides[1].frontier.len() {
+                0
+            } else {
+                1
+            };
+            sides[s].expand(self);
+            let (this, other) = (&sides[s], &sides[1 - s]);
+            let meet = this
+                .frontier
+                .iter()
+                .copied()
+                .filter(|&m| other.parent[m as usize] != NONE)
+                .min_by_key(|&m| this.dist[m as usize] + other.dist[m as usize]);
+            if let Some(m) = meet {
+                let mut path = unwind(&sides[0].parent, m);
+                let mut back = unwind(&sides[1].parent, m);
+                back.pop();
+                path.extend(back.into_iter().rev());
+                return Some(path);
+            }
+        }
+    }
+}
+
+/// One half of a bidirectional BFS, the vertices with a parent are the visited ones
+struct BfsSide {
+    frontier: Vec<u32>,
+    next: Vec<u32>,
+    parent: Vec<u32>,
+    dist: Vec<u32>,
+}
+
+impl BfsSide {
+    fn new(n: usize, root: u32) -> BfsSide {
+        let mut parent = vec![NONE; n];
+        parent[root as usize] = root;
+        let mut dist = vec![NONE; n];
+        dist[root as usize] = 0;
+        BfsSide {
+            frontier: vec![root],
+            next: Vec::new(),
+            parent,
+            dist,
+        }
+    }
+
+    /// Replaces the frontier by all unvisited neighbors of it, reusing the buffer of the
+    /// previous level for the next one
+    fn expand(&mut self, graph: &BitGraph) {
+        self.next.clear();
+        for &x in self.frontier.iter() {
+            let d = self.dist[x as usize] + 1;
+            for w in graph.neighbors(x) {
+                if self.parent[w as usize] == NONE {
+                    self.parent[w as usize] = x;
+                    self.dist[w as usize] = d;
+                    self.next.push(w);
+                }
+            }
+        }
+        mem::swap(&mut self.frontier, &mut self.next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{generators, BitGraph};
+    use std::time::Instant;
+
+    fn is_path(c: &BitGraph, path: &[u32]) -> bool {
+        path.windows(2)
+            .all(|pair| c.neighbors(pair[0]).any(|w| w == pair[1]))
+    }
+
+    #[test]
+    fn find_path() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.find_path(0, 1), None);
+        assert_eq!(c.find_path(2, 2), Some(vec![2]));
+        for i in 0..5 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 5);
+        c.add_edge(7, 8);
+        let path = c.find_path(1, 4).unwrap();
+        assert_eq!((path[0], *path.last().unwrap()), (1, 4));
+        assert!(is_path(&c, &path));
+        assert_eq!(c.find_path(1, 8), None);
+    }
+
+    #[test]
+    fn shortest_path() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.shortest_path(0, 1), None);
+        for i in 0..5 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 5);
+        c.add_edge(7, 8);
+        assert_eq!(c.shortest_path(1, 4), Some(vec![1, 0, 5, 4]));
+        assert_eq!(c.shortest_path(1, 3), Some(vec![1, 2, 3]));
+        assert_eq!(c.shortest_path(8, 7), Some(vec![8, 7]));
+        assert_eq!(c.shortest_path(1, 8), None);
+    }
+
+    #[test]
+    fn shortest_path_bidirectional() {
+        let capacity: usize = 100;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.shortest_path_bidirectional(0, 1), None);
+        assert_eq!(c.shortest_path_bidirectional(3, 3), Some(vec![3]));
+        for i in 0..60 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 30);
+        c.add_edge(70, 71);
+        for &(u, v) in [(0, 60), (5, 40), (60, 2), (29, 31), (10, 10)].iter() {
+            let path = c.shortest_path_bidirectional(u, v).unwrap();
+            assert_eq!(path.len(), c.shortest_path(u, v).unwrap().len());
+            assert_eq!((path[0], *path.last().unwrap()), (u, v));
+            assert!(is_path(&c, &path));
+        }
+        assert_eq!(c.shortest_path_bidirectional(0, 71), None);
+    }
+
+    #[test]
+    fn shortest_path_bidirectional_long_path() {
+        let capacity: usize = 20000;
+        let c = generators::path(capacity as u32);
+        let last = capacity as u32 - 1;
+        // best of a few runs, every level only touches its own frontier
+        let time = |f: &dyn Fn() -> Option<Vec<u32>>| {
+            (0..5)
+                .map(|_| {
+                    let start = Instant::now();
+                    assert_eq!(f().unwrap().len(), capacity);
+                    start.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+        let one_sided = time(&|| c.shortest_path(0, last));
+        let bidirectional = time(&|| c.shortest_path_bidirectional(0, last));
+        assert!(
+            bidirectional < 3 * one_sided,
+            "{:?} {:?}",
+            bidirectional,
+            one_sided
+        );
+    }
+
+    #[test]
+    fn search() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..5 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 5);
+        c.add_edge(7, 8);
+        assert_eq!(c.search(1, |x| x >= 4), Some(5));
+        assert_eq!(c.search_path(1, |x| x >= 4), Some(vec![1, 0, 5]));
+        assert_eq!(c.search(2, |x| x % 2 == 0), Some(2));
+        assert_eq!(c.search(1, |x| x > 6), None);
+        let mut visited = 0;
+        assert_eq!(
+            c.search(7, |x| {
+                visited += 1;
+                x == 8
+            }),
+            Some(8)
+        );
+        assert_eq!(visited, 2);
+        assert!(c.contains_on_path(2, 4));
+        assert!(c.contains_on_path(9, 9));
+        assert!(!c.contains_on_path(2, 8));
+    }
+}
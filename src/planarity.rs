@@ -0,0 +1,458 @@
+//! Planarity testing based on the left-right criterion of de Fraysseix and Rosenstiehl,
+//! following the formulation of Brandes ("The Left-Right Planarity Test").
+//! Both depth first searches are iterative so deep DFS trees can't overflow the stack.
+
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Interval {
+    low: Option<usize>,
+    high: Option<usize>,
+}
+
+impl Interval {
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ConflictPair {
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    fn swap(&mut self) {
+        core::mem::swap(&mut self.left, &mut self.right);
+    }
+}
+
+struct LrState {
+    adj: Vec<Vec<u32>>,
+    height: Vec<Option<u32>>,
+    parent: Vec<Option<u32>>,
+    parent_edge: Vec<Option<usize>>,
+    roots: Vec<u32>,
+    // oriented edges
+    source: Vec<u32>,
+    target: Vec<u32>,
+    out: Vec<Vec<usize>>,
+    lowpt: Vec<u32>,
+    lowpt2: Vec<u32>,
+    nesting_depth: Vec<u32>,
+    // testing phase
+    reference: Vec<Option<usize>>,
+    lowpt_edge: Vec<usize>,
+    stack_bottom: Vec<usize>,
+    stack: Vec<ConflictPair>,
+}
+
+impl LrState {
+    fn new(graph: &BitGraph) -> LrState {
+        let n = graph.m_data.len();
+        LrState {
+            adj: (0..n as u32)
+                .map(|v| graph.neighbors(v).filter(|&w| w != v).collect())
+                .collect(),
+            height: vec![None; n],
+            parent: vec![None; n],
+            parent_edge: vec![None; n],
+            roots: Vec::new(),
+            source: Vec::new(),
+            target: Vec::new(),
+            out: vec![Vec::new(); n],
+            lowpt: Vec::new(),
+            lowpt2: Vec::new(),
+            nesting_depth: Vec::new(),
+            reference: Vec::new(),
+            lowpt_edge: Vec::new(),
+            stack_bottom: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    fn conflicting(&self, interval: &Interval, edge: usize) -> bool {
+        match interval.high {
+            Some(high) => self.lowpt[high] > self.lowpt[edge],
+            None => false,
+        }
+    }
+
+    fn lowest(&self, pair: &ConflictPair) -> u32 {
+        if pair.left.is_empty() {
+            return self.lowpt[pair.right.low.unwrap()];
+        }
+        if pair.right.is_empty() {
+            return self.lowpt[pair.left.low.unwrap()];
+        }
+        self.lowpt[pair.left.low.unwrap()].min(self.lowpt[pair.right.low.unwrap()])
+    }
+
+    /// Orients all edges along a DFS and computes lowpoints and nesting depths
+    fn orient(&mut self) {
+        for root in 0..self.adj.len() {
+            if self.height[root].is_some() || self.adj[root].is_empty() {
+                continue;
+            }
+            self.height[root] = Some(0);
+            self.roots.push(root as u32);
+            let mut frames: Vec<(u32, usize)> = vec![(root as u32, 0)];
+            while let Some(&(v, i)) = frames.last() {
+                if i == self.adj[v as usize].len() {
+                    frames.pop();
+                    if let Some(e) = self.parent_edge[v as usize] {
+                        self.finish_edge(e);
+                    }
+                    continue;
+                }
+                frames.last_mut().unwrap().1 += 1;
+                let w = self.adj[v as usize][i];
+                let h_v = self.height[v as usize].unwrap();
+                let tree_edge = match self.height[w as usize] {
+                    None => true,
+                    Some(h_w) if h_w < h_v && self.parent[v as usize] != Some(w) => false,
+                    _ => continue,
+                };
+                let vw = self.target.len();
+                self.source.push(v);
+                self.target.push(w);
+                self.out[v as usize].push(vw);
+                self.lowpt.push(h_v);
+                self.lowpt2.push(h_v);
+                self.nesting_depth.push(0);
+                if tree_edge {
+                    self.parent[w as usize] = Some(v);
+                    self.parent_edge[w as usize] = Some(vw);
+                    self.height[w as usize] = Some(h_v + 1);
+                    frames.push((w, 0));
+                } else {
+                    self.lowpt[vw] = self.height[w as usize].unwrap();
+                    self.finish_edge(vw);
+                }
+            }
+        }
+    }
+
+    /// Computes the nesting depth of `vw` and propagates its lowpoints to the parent edge of `v`
+    fn finish_edge(&mut self, vw: usize) {
+        let v = self.source[vw];
+        let h_v = self.height[v as usize].unwrap();
+        self.nesting_depth[vw] = 2 * self.lowpt[vw];
+        if self.lowpt2[vw] < h_v {
+            self.nesting_depth[vw] += 1;
+        }
+        if let Some(e) = self.parent_edge[v as usize] {
+            if self.lowpt[vw] < self.lowpt[e] {
+                self.lowpt2[e] = self.lowpt[e].min(self.lowpt2[vw]);
+                self.lowpt[e] = self.lowpt[vw];
+            } else if self.lowpt[vw] > self.lowpt[e] {
+                self.lowpt2[e] = self.lowpt2[e].min(self.lowpt[vw]);
+            } else {
+                self.lowpt2[e] = self.lowpt2[e].min(self.lowpt2[vw]);
+            }
+        }
+    }
+
+    fn test(&mut self) -> bool {
+        let m = self.target.len();
+        self.reference = vec![None; m];
+        self.lowpt_edge = vec![0; m];
+        self.stack_bottom = vec![0; m];
+        for v in 0..self.out.len() {
+            let mut edges = core::mem::take(&mut self.out[v]);
+            edges.sort_by_key(|&e| self.nesting_depth[e]);
+            self.out[v] = edges;
+        }
+        for r in 0..self.roots.len() {
+            if !self.test_from(self.roots[r]) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn test_from(&mut self, root: u32) -> bool {
+        let mut frames: Vec<(u32, usize)> = vec![(root, 0)];
+        let mut returned = false;
+        while let Some(&(v, i)) = frames.last() {
+            if returned {
+                returned = false;
+                if !self.integrate(v, i - 1) {
+                    return false;
+                }
+                continue;
+            }
+            if i == self.out[v as usize].len() {
+                frames.pop();
+                if let Some(e) = self.parent_edge[v as usize] {
+                    self.remove_back_edges(e);
+                    returned = true;
+                }
+                continue;
+            }
+            frames.last_mut().unwrap().1 += 1;
+            let ei = self.out[v as usize][i];
+            let w = self.target[ei];
+            self.stack_bottom[ei] = self.stack.len();
+            if self.parent_edge[w as usize] == Some(ei) {
+                frames.push((w, 0));
+            } else {
+                self.lowpt_edge[ei] = ei;
+                self.stack.push(ConflictPair {
+                    left: Interval::default(),
+                    right: Interval {
+                        low: Some(ei),
+                        high: Some(ei),
+                    },
+                });
+                if !self.integrate(v, i) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Integrates the return edges of the `i`-th outgoing edge of `v`
+    fn integrate(&mut self, v: u32, i: usize) -> bool {
+        let ei = self.out[v as usize][i];
+        if self.lowpt[ei] < self.height[v as usize].unwrap() {
+            let e = self.parent_edge[v as usize].unwrap();
+            if i == 0 {
+                self.lowpt_edge[e] = self.lowpt_edge[ei];
+            } else if !self.add_constraints(ei, e) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn add_constraints(&mut self, ei: usize, e: usize) -> bool {
+        let mut p = ConflictPair::default();
+        // merge return edges of ei into p.right
+        loop {
+            let mut q = self.stack.pop().unwrap();
+            if !q.left.is_empty() {
+                q.swap();
+            }
+            if !q.left.is_empty() {
+                return false;
+            }
+            if self.lowpt[q.right.low.unwrap()] > self.lowpt[e] {
+                if p.right.is_empty() {
+                    p.right = q.right;
+                } else {
+                    self.reference[p.right.low.unwrap()] = q.right.high;
+                }
+                p.right.low = q.right.low;
+            } else {
+                self.reference[q.right.low.unwrap()] = Some(self.lowpt_edge[e]);
+            }
+            if self.stack.len() == self.stack_bottom[ei] {
+                break;
+            }
+        }
+        // merge conflicting return edges of the previous siblings into p.left
+        while let Some(top) = self.stack.last() {
+            if !self.conflicting(&top.left, ei) && !self.conflicting(&top.right, ei) {
+                break;
+            }
+            let mut q = self.stack.pop().unwrap();
+            if self.conflicting(&q.right, ei) {
+                q.swap();
+            }
+            if self.conflicting(&q.right, ei) {
+                return false;
+            }
+            if let Some(low) = p.right.low {
+                self.reference[low] = q.right.high;
+            }
+            if q.right.low.is_some() {
+                p.right.low = q.right.low;
+            }
+            if p.left.is_empty() {
+                p.left = q.left;
+            } else {
+                self.reference[p.left.low.unwrap()] = q.left.high;
+            }
+            p.left.low = q.left.low;
+        }
+        if !(p.left.is_empty() && p.right.is_empty()) {
+            self.stack.push(p);
+        }
+        true
+    }
+
+    fn remove_back_edges(&mut self, e: usize) {
+        let u = self.source[e];
+        let h_u = self.height[u as usize].unwrap();
+        while let Some(top) = self.stack.last() {
+            if self.lowest(top) != h_u {
+                break;
+            }
+            self.stack.pop();
+        }
+        if let Some(mut p) = self.stack.pop() {
+            // trim left interval
+            while let Some(high) = p.left.high {
+                if self.target[high] != u {
+                    break;
+                }
+                p.left.high = self.reference[high];
+            }
+            if p.left.high.is_none() {
+                if let Some(low) = p.left.low {
+                    self.reference[low] = p.right.low;
+                    p.left.low = None;
+                }
+            }
+            // trim right interval
+            while let Some(high) = p.right.high {
+                if self.target[high] != u {
+                    break;
+                }
+                p.right.high = self.reference[high];
+            }
+            if p.right.high.is_none() {
+                if let Some(low) = p.right.low {
+                    self.reference[low] = p.left.low;
+                    p.right.low = None;
+                }
+            }
+            self.stack.push(p);
+        }
+        // the side of e is the side of a highest return edge
+        if self.lowpt[e] < h_u {
+            if let Some(top) = self.stack.last() {
+                let hl = top.left.high;
+                let hr = top.right.high;
+                self.reference[e] = match (hl, hr) {
+                    (Some(l), Some(r)) if self.lowpt[l] > self.lowpt[r] => hl,
+                    (Some(_), None) => hl,
+                    _ => hr,
+                };
+            }
+        }
+    }
+}
+
+impl BitGraph {
+    /// Returns `true` if the graph can be drawn in the plane without crossing edges
+    pub fn is_planar(&self) -> bool {
+        let n = self.order() as u64;
+        let m = self.size();
+        if n > 2 && m > 3 * n - 6 {
+            return false;
+        }
+        let mut state = LrState::new(self);
+        state.orient();
+        state.test()
+    }
+
+    /// Returns a subgraph that is a subdivision of K5 or K3,3 if the graph is not planar,
+    /// otherwise `None`.
+    /// Edges are removed greedily as long as the remainder stays non-planar,
+    /// so this performs one planarity test per edge
+    pub fn kuratowski_subgraph(&self) -> Option<BitGraph> {
+        if self.is_planar() {
+            return None;
+        }
+        let mut subgraph = self.clone();
+        for u in 0..self.m_data.len() as u32 {
+            for v in self.neighbors(u).filter(|&v| v > u) {
+                subgraph.remove_edge(u, v);
+                if subgraph.is_planar() {
+                    subgraph.add_edge(u, v);
+                }
+            }
+        }
+        Some(subgraph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BitGraph;
+
+    fn k33() -> BitGraph {
+        let mut c = BitGraph::with_capacity(6);
+        for u in 0..3 {
+            for v in 3..6 {
+                c.add_edge(u, v);
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn is_planar() {
+        assert!(BitGraph::with_capacity(10).is_planar());
+        assert!(BitGraph::complete(4).is_planar());
+        assert!(!BitGraph::complete(5).is_planar());
+        assert!(!k33().is_planar());
+
+        // K5 minus a single edge is planar
+        let mut c = BitGraph::with_capacity(5);
+        for u in 0..5 {
+            for v in (u + 1)..5 {
+                if (u, v) != (0, 1) {
+                    c.add_edge(u, v);
+                }
+            }
+        }
+        assert!(c.is_planar());
+
+        // grid graph
+        let mut c = BitGraph::with_capacity(25);
+        for i in 0..5 {
+            for j in 0..5 {
+                if j < 4 {
+                    c.add_edge(i * 5 + j, i * 5 + j + 1);
+                }
+                if i < 4 {
+                    c.add_edge(i * 5 + j, (i + 1) * 5 + j);
+                }
+            }
+        }
+        assert!(c.is_planar());
+
+        // petersen graph, not planar but sparse enough to pass the edge count bound
+        let mut c = BitGraph::with_capacity(10);
+        for i in 0..5 {
+            c.add_edge(i, (i + 1) % 5);
+            c.add_edge(i, i + 5);
+            c.add_edge(i + 5, (i + 2) % 5 + 5);
+        }
+        assert!(!c.is_planar());
+    }
+
+    #[test]
+    fn kuratowski_subgraph() {
+        assert!(BitGraph::complete(4).kuratowski_subgraph().is_none());
+
+        // K3,3 with a pendant path and a subdivided edge
+        let mut c = BitGraph::with_capacity(9);
+        for u in 0..3 {
+            for v in 3..6 {
+                if (u, v) != (0, 3) {
+                    c.add_edge(u, v);
+                }
+            }
+        }
+        c.add_edge(0, 6);
+        c.add_edge(6, 3);
+        c.add_edge(1, 7);
+        c.add_edge(7, 8);
+        let k = c.kuratowski_subgraph().unwrap();
+        assert!(!k.is_planar());
+        assert_eq!(k.degree(7), 0);
+        assert_eq!(k.degree(8), 0);
+        assert_eq!(k.degree(6), 2);
+        for v in 0..6 {
+            assert_eq!(k.degree(v), 3);
+        }
+    }
+}
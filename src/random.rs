@@ -0,0 +1,83 @@
+//! Minimal random number interface used by the randomized algorithms and generators
+//!
+//! Any `FnMut() -> u64` is a `RandomSource`, so generators from other crates can be
+//! plugged in with a closure, e.g. `&mut || rng.next_u64()`.
+
+/// Source of uniformly distributed random bits
+pub trait RandomSource {
+    /// Returns the next 64 random bits
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a uniformly distributed number in `0..n`, `n` must be positive
+    fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Empty range");
+        // rejection sampling on the largest multiple of n
+        let zone = u64::MAX - (u64::MAX - n + 1) % n;
+        loop {
+            let r = self.next_u64();
+            if r <= zone {
+                return r % n;
+            }
+        }
+    }
+
+    /// Returns a uniformly distributed number in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+impl<F: FnMut() -> u64> RandomSource for F {
+    fn next_u64(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Small and fast seedable generator (SplitMix64), not suitable for cryptography
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    m_state: u64,
+}
+
+impl SplitMix64 {
+    /// Creates a new generator from `seed`
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { m_state: seed }
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.m_state = self.m_state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.m_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RandomSource, SplitMix64};
+
+    #[test]
+    fn split_mix() {
+        let mut rng = SplitMix64::new(42);
+        let mut counts = [0u32; 6];
+        for _ in 0..6000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 850 && c < 1150));
+        for _ in 0..100 {
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+
+        let mut x = 0u64;
+        let mut closure = || {
+            x += 1;
+            x
+        };
+        assert_eq!(closure.below(10), 1);
+    }
+}
@@ -0,0 +1,258 @@
+//! Set operations between the edge sets of graphs of the same capacity, row by row
+
+use crate::BitGraph;
+use hibitset::BitSetNot;
+
+impl BitGraph {
+    /// Adds all edges of `other`
+    pub fn union_with(&mut self, other: &BitGraph) {
+        self.check_same_capacity(other);
+        self.journal_begin();
+        let mut touched = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        for v in 0..self.m_data.len() as u32 {
+            if other.m_degrees[v as usize] == 0 {
+                continue;
+            }
+            *self.row_mut(v) |= other.row(v);
+            touched.add(v);
+        }
+        self.recount(&touched);
+    }
+
+    /// Removes all edges that are not in `other`
+    pub fn intersect_with(&mut self, other: &BitGraph) {
+        self.check_same_capacity(other);
+        self.journal_begin();
+        let mut touched = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        for v in 0..self.m_data.len() as u32 {
+            if self.m_degrees[v as usize] == 0 {
+                continue;
+            }
+            *self.row_mut(v) &= other.row(v);
+            touched.add(v);
+        }
+        self.recount(&touched);
+    }
+
+    /// Removes all edges that are in `other`
+    pub fn difference_with(&mut self, other: &BitGraph) {
+        self.check_same_capacity(other);
+        self.journal_begin();
+        let mut touched = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        for v in 0..self.m_data.len() as u32 {
+            if self.m_degrees[v as usize] == 0 || other.m_degrees[v as usize] == 0 {
+                continue;
+            }
+            *self.row_mut(v) &= &BitSetNot(other.row(v));
+            touched.add(v);
+        }
+        self.recount(&touched);
+    }
+
+    /// Adds the edges of `other` that are not in `self` and removes the ones that are
+    pub fn symmetric_difference_with(&mut self, other: &BitGraph) {
+        self.check_same_capacity(other);
+        self.journal_begin();
+        let mut touched = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        for v in 0..self.m_data.len() as u32 {
+            if other.m_degrees[v as usize] == 0 {
+                continue;
+            }
+            *self.row_mut(v) ^= other.row(v);
+            touched.add(v);
+        }
+        self.recount(&touched);
+    }
+
+    /// Returns a graph with the edges of both graphs
+    pub fn union(&self, other: &BitGraph) -> BitGraph {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    /// Returns a graph with the edges that are in both graphs
+    pub fn intersection(&self, other: &BitGraph) -> BitGraph {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    /// Returns a graph with the edges of `self` that are not in `other`
+    pub fn difference(&self, other: &BitGraph) -> BitGraph {
+        let mut result = self.clone();
+        result.difference_with(other);
+        result
+    }
+
+    /// Returns a graph with the edges that are in exactly one of the graphs
+    pub fn symmetric_difference(&self, other: &BitGraph) -> BitGraph {
+        let mut result = self.clone();
+        result.symmetric_difference_with(other);
+        result
+    }
+
+    /// Number of edges that are in exactly one of the graphs, which is the number of edge
+    /// insertions and deletions that turn one into the other
+    pub fn edit_distance_edges(&self, other: &BitGraph) -> u64 {
+        self.check_same_capacity(other);
+        let twice: u64 = self
+            .m_data
+            .iter()
+            .zip(other.m_data.iter())
+            .map(|(a, b)| {
+                let (a, b) = (a.layer0_as_slice(), b.layer0_as_slice());
+                (0..a.len().max(b.len()))
+                    .map(|i| {
+                        let x = a.get(i).copied().unwrap_or(0) ^ b.get(i).copied().unwrap_or(0);
+                        x.count_ones() as u64
+                    })
+                    .sum::<u64>()
+            })
+            .sum();
+        twice / 2
+    }
+
+    /// Returns `true` if every edge of `self` is an edge of `other`
+    pub fn is_subgraph_of(&self, other: &BitGraph) -> bool {
+        self.check_same_capacity(other);
+        (0..self.m_data.len()).all(|v| {
+            if self.m_degrees[v] > other.m_degrees[v] {
+                return false;
+            }
+            let (a, b) = (
+                self.m_data[v].layer0_as_slice(),
+                other.m_data[v].layer0_as_slice(),
+            );
+            a.iter()
+                .enumerate()
+                .all(|(i, &x)| x & !b.get(i).copied().unwrap_or(0) == 0)
+        })
+    }
+
+    /// Returns `true` if every edge of `other` is an edge of `self`
+    pub fn is_supergraph_of(&self, other: &BitGraph) -> bool {
+        other.is_subgraph_of(self)
+    }
+
+    #[inline]
+    pub(crate) fn check_same_capacity(&self, other: &BitGraph) {
+        if self.m_data.len() != other.m_data.len() {
+            panic!(
+                "Capacities differ. Given: {}, Expected: {}",
+                other.m_data.len(),
+                self.m_data.len()
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    fn from_edges(capacity: u32, edges: &[(u32, u32)]) -> BitGraph {
+        let mut c = BitGraph::with_capacity(capacity);
+        c.add_edges(edges);
+        c
+    }
+
+    #[test]
+    fn union_intersection_difference() {
+        let capacity: usize = 6;
+        let c = from_edges(capacity as u32, &[(0, 1), (1, 2), (2, 3)]);
+        let d = from_edges(capacity as u32, &[(1, 2), (3, 4), (4, 5)]);
+        let tmp: Vec<(u32, u32)> = c.union(&d).edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+        let tmp: Vec<(u32, u32)> = c.intersection(&d).edges().collect();
+        assert_eq!(tmp, vec![(1, 2)]);
+        assert_eq!(c.intersection(&d).order(), 2);
+        let tmp: Vec<(u32, u32)> = c.difference(&d).edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (2, 3)]);
+
+        let mut rng = SplitMix64::new(61);
+        let a = generators::gnp(50, 0.3, &mut rng);
+        let b = generators::gnp(50, 0.3, &mut rng);
+        let mut e = a.clone();
+        e.enable_invariant_cache();
+        e.enable_degree_buckets();
+        assert!(e.triangle_count() > 0);
+        e.intersect_with(&b);
+        let both: Vec<(u32, u32)> = a.edges().filter(|&(u, v)| b.row(u).contains(v)).collect();
+        assert_eq!(e, from_edges(50, &both));
+        assert_eq!(e.triangle_count(), from_edges(50, &both).triangle_count());
+        e.union_with(&a);
+        assert_eq!(e, a);
+        e.difference_with(&a);
+        assert_eq!(e, BitGraph::with_capacity(50));
+        assert_eq!(e.order(), 0);
+        assert_eq!(e.max_degree_vertex(), None);
+        assert_eq!(a.difference(&b).union(&a.intersection(&b)), a);
+    }
+
+    #[test]
+    fn symmetric_difference() {
+        let capacity: usize = 6;
+        let c = from_edges(capacity as u32, &[(0, 1), (1, 2), (2, 3)]);
+        let d = from_edges(capacity as u32, &[(1, 2), (3, 4), (4, 5)]);
+        let e = c.symmetric_difference(&d);
+        let tmp: Vec<(u32, u32)> = e.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (2, 3), (3, 4), (4, 5)]);
+        assert_eq!(e.degree(1), 1);
+        assert_eq!(c.edit_distance_edges(&d), 4);
+        assert_eq!(c.edit_distance_edges(&c), 0);
+
+        let mut rng = SplitMix64::new(62);
+        let a = generators::gnp(70, 0.2, &mut rng);
+        let b = generators::gnp(70, 0.2, &mut rng);
+        let x = a.symmetric_difference(&b);
+        assert_eq!(x, a.difference(&b).union(&b.difference(&a)));
+        assert_eq!(a.edit_distance_edges(&b), x.size());
+        assert_eq!(b.edit_distance_edges(&a), x.size());
+        assert_eq!(x.symmetric_difference(&b), a);
+        let empty = BitGraph::with_capacity(70);
+        assert_eq!(a.edit_distance_edges(&empty), a.size());
+        let mut y = a.clone();
+        y.symmetric_difference_with(&a);
+        assert_eq!(y, empty);
+        assert_eq!(y.order(), 0);
+    }
+
+    #[test]
+    fn is_subgraph_of() {
+        let capacity: usize = 6;
+        let c = from_edges(capacity as u32, &[(0, 1), (1, 2), (2, 3)]);
+        let d = from_edges(capacity as u32, &[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert!(c.is_subgraph_of(&d));
+        assert!(d.is_supergraph_of(&c));
+        assert!(!d.is_subgraph_of(&c));
+        assert!(c.is_subgraph_of(&c));
+        assert!(BitGraph::with_capacity(6).is_subgraph_of(&c));
+        // same degrees, different edges
+        let e = from_edges(capacity as u32, &[(0, 2), (1, 2), (1, 3)]);
+        assert!(!e.is_subgraph_of(&d));
+
+        // a far edge grows the rows of the supergraph only
+        let mut f = BitGraph::with_capacity(300);
+        f.add_edge(0, 1);
+        let mut g = f.clone();
+        g.add_edge(0, 299);
+        assert!(f.is_subgraph_of(&g));
+        assert!(!g.is_subgraph_of(&f));
+
+        let mut rng = SplitMix64::new(63);
+        let a = generators::gnp(60, 0.3, &mut rng);
+        let b = generators::gnp(60, 0.3, &mut rng);
+        assert!(a.intersection(&b).is_subgraph_of(&a));
+        assert!(a.union(&b).is_supergraph_of(&b));
+        assert!(!a.is_subgraph_of(&b));
+    }
+
+    #[test]
+    #[should_panic]
+    fn union_capacity_mismatch() {
+        BitGraph::with_capacity(3).union_with(&BitGraph::with_capacity(4));
+    }
+}
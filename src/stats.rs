@@ -0,0 +1,213 @@
+//! Summary statistics
+
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::BitSetLike;
+
+/// Summary of the basic statistics of a graph, returned by `BitGraph::stats`.
+/// Density and degrees are taken over all vertices, isolated ones included
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GraphStats {
+    /// Number of non-isolated vertices, see `BitGraph::order`
+    pub order: u32,
+    /// Number of edges
+    pub size: u64,
+    /// Fraction of vertex pairs that are adjacent
+    pub density: f64,
+    /// Smallest degree
+    pub min_degree: u32,
+    /// Average degree
+    pub avg_degree: f64,
+    /// Largest degree
+    pub max_degree: u32,
+    /// Number of connected components, see `BitGraph::component_count`
+    pub components: u32,
+    /// Lower and upper bound on the largest distance between two connected vertices,
+    /// only computed by `BitGraph::stats_with_diameter`
+    pub diameter_bounds: Option<(u32, u32)>,
+}
+
+impl BitGraph {
+    /// Returns the basic statistics of the graph, without diameter bounds
+    pub fn stats(&self) -> GraphStats {
+        let n = self.m_data.len() as f64;
+        let size = self.size();
+        GraphStats {
+            order: self.order(),
+            size,
+            density: if n < 2.0 {
+                0.0
+            } else {
+                2.0 * size as f64 / (n * (n - 1.0))
+            },
+            min_degree: self.min_degree(),
+            avg_degree: if n == 0.0 { 0.0 } else { 2.0 * size as f64 / n },
+            max_degree: self.max_degree(),
+            components: self.component_count(),
+            diameter_bounds: None,
+        }
+    }
+
+    /// Returns the basic statistics of the graph together with diameter bounds. Per component,
+    /// a search from the vertex `u` found last by a search from any vertex gives a lower bound
+    /// `l`, twice the eccentricity of the middle vertex of a longest path found from `u` an
+    /// upper one. The lower bound is exact on trees
+    pub fn stats_with_diameter(&self) -> GraphStats {
+        let mut stats = self.stats();
+        let n = self.m_data.len() as u32;
+        let mut visited = hibitset::BitSet::with_capacity(n);
+        let (mut lower, mut upper) = (0, 0);
+        for v in 0..n {
+            if self.degree(v) == 0 || visited.contains(v) {
+                continue;
+            }
+            let from_v = self.distances(v);
+            for w in (0..n).filter(|&w| from_v[w as usize] != u32::MAX) {
+                visited.add(w);
+            }
+            let u = farthest(&from_v);
+            let from_u = self.distances(u);
+            let w = farthest(&from_u);
+            let length = from_u[w as usize];
+            let from_w = self.distances(w);
+            let middle = (0..n)
+                .find(|&m| {
+                    from_u[m as usize] == length / 2 && from_w[m as usize] == length - length / 2
+                })
+                .unwrap();
+            let from_middle = self.distances(middle);
+            let radius = from_middle[farthest(&from_middle) as usize];
+            lower = lower.max(length);
+            upper = upper.max(2 * radius);
+        }
+        stats.diameter_bounds = Some((lower, upper));
+        stats
+    }
+
+    /// Pearson correlation of the degrees at the two ends of an edge, taken over both orientations
+    /// of every edge. `None` if the graph has no edges or all edges join vertices of one degree
+    pub fn degree_assortativity(&self) -> Option<f64> {
+        let (mut m, mut product, mut sum, mut squares) = (0.0, 0.0, 0.0, 0.0);
+        for (u, v) in self.edges() {
+            let (j, k) = (self.degree(u) as f64, self.degree(v) as f64);
+            m += 1.0;
+            product += j * k;
+            sum += (j + k) / 2.0;
+            squares += (j * j + k * k) / 2.0;
+        }
+        let mean = sum / m;
+        let variance = squares / m - mean * mean;
+        if m == 0.0 || variance <= 1e-12 * squares / m {
+            return None;
+        }
+        Some((product / m - mean * mean) / variance)
+    }
+
+    /// Distance from `v` to every vertex, `u32::MAX` for unreachable ones
+    fn distances(&self, v: u32) -> Vec<u32> {
+        let mut distances = vec![u32::MAX; self.m_data.len()];
+        for (depth, layer) in self.bfs_layers(v).enumerate() {
+            for w in (&layer).iter() {
+                distances[w as usize] = depth as u32;
+            }
+        }
+        distances
+    }
+}
+
+/// A vertex of largest finite distance
+fn farthest(distances: &[u32]) -> u32 {
+    (0..distances.len())
+        .filter(|&w| distances[w] != u32::MAX)
+        .max_by_key(|&w| distances[w])
+        .unwrap() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    #[test]
+    fn stats() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..4 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(6, 7);
+        let stats = c.stats();
+        assert_eq!(stats.order, 7);
+        assert_eq!(stats.size, 5);
+        assert!((stats.density - 10.0 / 90.0).abs() < 1e-12);
+        assert_eq!(stats.min_degree, 0);
+        assert!((stats.avg_degree - 1.0).abs() < 1e-12);
+        assert_eq!(stats.max_degree, 2);
+        assert_eq!(stats.components, 2);
+        assert_eq!(stats.diameter_bounds, None);
+        assert_eq!(c.stats_with_diameter().diameter_bounds, Some((4, 4)));
+        let mut rng = SplitMix64::new(3);
+        for c in [
+            generators::random_tree(40, &mut rng),
+            generators::gnp(40, 0.06, &mut rng),
+            generators::watts_strogatz(40, 4, 0.1, &mut rng),
+        ]
+        .iter()
+        {
+            let diameter = (0..40)
+                .flat_map(|v| c.distances(v))
+                .filter(|&d| d != u32::MAX)
+                .max()
+                .unwrap();
+            let (lower, upper) = c.stats_with_diameter().diameter_bounds.unwrap();
+            assert!(lower <= diameter && diameter <= upper);
+            assert!(upper <= 2 * lower);
+            if c.is_tree() {
+                assert_eq!(lower, diameter);
+            }
+        }
+
+        let stats = BitGraph::complete(5).stats_with_diameter();
+        assert!((stats.density - 1.0).abs() < 1e-12);
+        assert_eq!(stats.diameter_bounds, Some((1, 2)));
+        let (lower, upper) = generators::cycle(9)
+            .stats_with_diameter()
+            .diameter_bounds
+            .unwrap();
+        assert!(lower <= 4 && 4 <= upper);
+        let (lower, upper) = generators::named::petersen()
+            .stats_with_diameter()
+            .diameter_bounds
+            .unwrap();
+        assert!(lower <= 2 && 2 <= upper);
+
+        let stats = BitGraph::with_capacity(0).stats_with_diameter();
+        assert_eq!(BitGraph::with_capacity(0).degree_assortativity(), None);
+        assert_eq!(stats.density, 0.0);
+        assert_eq!(stats.avg_degree, 0.0);
+        assert_eq!(stats.diameter_bounds, Some((0, 0)));
+    }
+
+    #[test]
+    fn degree_assortativity() {
+        // a star is perfectly disassortative, two stars joined at their centers are not quite
+        assert!((generators::star(6).degree_assortativity().unwrap() + 1.0).abs() < 1e-12);
+        let mut c = BitGraph::with_capacity(8);
+        for i in 1..4 {
+            c.add_edge(0, i);
+            c.add_edge(4, 4 + i);
+        }
+        c.add_edge(0, 4);
+        let r = c.degree_assortativity().unwrap();
+        assert!((r + 0.75).abs() < 1e-12);
+        assert_eq!(generators::cycle(7).degree_assortativity(), None);
+        assert_eq!(BitGraph::complete(5).degree_assortativity(), None);
+
+        // two paths on 3 vertices are stars as well
+        let mut two_components = generators::path(6);
+        two_components.remove_edge(2, 3);
+        assert!((two_components.degree_assortativity().unwrap() + 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,264 @@
+//! Subgraph isomorphism
+
+use crate::BitGraph;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::{BitSetAnd, BitSetLike};
+
+impl BitGraph {
+    /// Returns a lazy iterator over the embeddings of `pattern` into the graph. An embedding maps
+    /// pattern vertex `p` onto the distinct vertex `map[p]`, such that every pattern edge becomes
+    /// an edge. If `induced` is set, pattern non-edges have to become non-edges as well.
+    /// Every vertex of `pattern` takes part, including isolated ones
+    pub fn find_subgraph<'a>(
+        &'a self,
+        pattern: &'a BitGraph,
+        induced: bool,
+    ) -> SubgraphMatches<'a> {
+        let k = pattern.m_data.len();
+        let domains = self.pattern_domains(pattern);
+        // pattern vertices with many already placed neighbors first, so candidates stay few
+        let mut order: Vec<u32> = Vec::with_capacity(k);
+        let mut placed = vec![false; k];
+        let mut links = vec![0u32; k];
+        for _ in 0..k {
+            let p = (0..k)
+                .filter(|&p| !placed[p])
+                .max_by_key(|&p| (links[p], pattern.m_degrees[p], core::cmp::Reverse(p)))
+                .unwrap() as u32;
+            placed[p as usize] = true;
+            order.push(p);
+            for q in pattern.neighbors(p) {
+                links[q as usize] += 1;
+            }
+        }
+        let feasible =
+            k <= self.m_data.len() && domains.iter().all(|d| (&d).iter().next().is_some());
+        SubgraphMatches {
+            m_graph: self,
+            m_pattern: pattern,
+            m_induced: induced,
+            m_domains: domains,
+            m_order: order,
+            m_map: vec![u32::MAX; k],
+            m_used: hibitset::BitSet::with_capacity(self.m_data.len() as u32),
+            m_stack: Vec::new(),
+            m_done: !feasible,
+        }
+    }
+
+    /// Host vertices every pattern vertex can be mapped onto. Starts from the vertices of large
+    /// enough degree and keeps those with a candidate for every pattern neighbor among their own
+    /// neighbors
+    fn pattern_domains(&self, pattern: &BitGraph) -> Vec<hibitset::BitSet> {
+        let n = self.m_data.len() as u32;
+        let mut domains: Vec<hibitset::BitSet> = pattern
+            .m_degrees
+            .iter()
+            .map(|&d| {
+                let mut domain = hibitset::BitSet::with_capacity(n);
+                for v in (0..n).filter(|&v| self.m_degrees[v as usize] >= d) {
+                    domain.add(v);
+                }
+                domain
+            })
+            .collect();
+        for p in 0..pattern.m_data.len() as u32 {
+            let removed: Vec<u32> = (&domains[p as usize])
+                .iter()
+                .filter(|&v| {
+                    pattern.neighbors(p).any(|q| {
+                        BitSetAnd(self.row(v), &domains[q as usize])
+                            .iter()
+                            .next()
+                            .is_none()
+                    })
+                })
+                .collect();
+            for v in removed {
+                domains[p as usize].remove(v);
+            }
+        }
+        domains
+    }
+}
+
+/// Iterator over the embeddings of a pattern graph, created by `BitGraph::find_subgraph`.
+/// Yields `map` with pattern vertex `p` placed on `map[p]`
+///
+/// Pattern vertices are placed by backtracking in a fixed order. The candidates of a vertex are
+/// its domain intersected with the row of the image of one placed pattern neighbor, the other
+/// placed vertices are checked against the row of the candidate
+pub struct SubgraphMatches<'a> {
+    m_graph: &'a BitGraph,
+    m_pattern: &'a BitGraph,
+    m_induced: bool,
+    m_domains: Vec<hibitset::BitSet>,
+    m_order: Vec<u32>,
+    m_map: Vec<u32>,
+    m_used: hibitset::BitSet,
+    m_stack: Vec<alloc::vec::IntoIter<u32>>,
+    m_done: bool,
+}
+
+impl<'a> SubgraphMatches<'a> {
+    fn candidates(&self, depth: usize) -> Vec<u32> {
+        let p = self.m_order[depth];
+        let domain = &self.m_domains[p as usize];
+        let anchor = self
+            .m_pattern
+            .neighbors(p)
+            .find(|&q| self.m_map[q as usize] != u32::MAX);
+        let candidates: Vec<u32> = match anchor {
+            Some(q) => BitSetAnd(domain, self.m_graph.row(self.m_map[q as usize]))
+                .iter()
+                .collect(),
+            None => domain.iter().collect(),
+        };
+        let pattern_row = self.m_pattern.row(p);
+        candidates
+            .into_iter()
+            .filter(|&c| !self.m_used.contains(c))
+            .filter(|&c| {
+                let row = self.m_graph.row(c);
+                self.m_order[..depth].iter().all(|&q| {
+                    let adjacent = row.contains(self.m_map[q as usize]);
+                    if pattern_row.contains(q) {
+                        adjacent
+                    } else {
+                        !self.m_induced || !adjacent
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn unplace(&mut self, depth: usize) {
+        let p = self.m_order[depth] as usize;
+        if self.m_map[p] != u32::MAX {
+            self.m_used.remove(self.m_map[p]);
+            self.m_map[p] = u32::MAX;
+        }
+    }
+}
+
+impl<'a> Iterator for SubgraphMatches<'a> {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.m_done {
+            return None;
+        }
+        if self.m_order.is_empty() {
+            self.m_done = true;
+            return Some(Vec::new());
+        }
+        if self.m_stack.is_empty() {
+            let first = self.candidates(0).into_iter();
+            self.m_stack.push(first);
+        }
+        loop {
+            let depth = self.m_stack.len() - 1;
+            self.unplace(depth);
+            let c = match self.m_stack[depth].next() {
+                Some(c) => c,
+                None => {
+                    self.m_stack.pop();
+                    if self.m_stack.is_empty() {
+                        self.m_done = true;
+                        return None;
+                    }
+                    continue;
+                }
+            };
+            self.m_map[self.m_order[depth] as usize] = c;
+            self.m_used.add(c);
+            if depth + 1 == self.m_order.len() {
+                return Some(self.m_map.clone());
+            }
+            let next = self.candidates(depth + 1).into_iter();
+            self.m_stack.push(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    fn check_embedding(c: &BitGraph, pattern: &BitGraph, map: &[u32], induced: bool) {
+        let k = pattern.m_data.len() as u32;
+        for p in 0..k {
+            for q in (0..k).filter(|&q| q != p) {
+                assert_ne!(map[p as usize], map[q as usize]);
+                let edge = c.row(map[p as usize]).contains(map[q as usize]);
+                if pattern.row(p).contains(q) {
+                    assert!(edge);
+                } else if induced {
+                    assert!(!edge);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn find_subgraph() {
+        let triangle = BitGraph::complete(3);
+        let path3 = generators::path(3);
+        let k4 = BitGraph::complete(4);
+        assert_eq!(k4.find_subgraph(&triangle, false).count(), 24);
+        assert_eq!(k4.find_subgraph(&path3, false).count(), 24);
+        assert_eq!(k4.find_subgraph(&path3, true).count(), 0);
+        assert_eq!(generators::cycle(5).find_subgraph(&path3, true).count(), 10);
+        assert_eq!(
+            generators::cycle(5).find_subgraph(&triangle, false).count(),
+            0
+        );
+        assert_eq!(triangle.find_subgraph(&k4, false).count(), 0);
+        assert_eq!(
+            k4.find_subgraph(&BitGraph::with_capacity(0), true).count(),
+            1
+        );
+
+        // two isolated vertices have to stay apart in induced mode
+        let capacity: usize = 4;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(2, 3);
+        let pair = BitGraph::with_capacity(2);
+        assert_eq!(c.find_subgraph(&pair, false).count(), 12);
+        assert_eq!(c.find_subgraph(&pair, true).count(), 8);
+
+        // induced embeddings are copies times automorphisms of the shape
+        let mut rng = SplitMix64::new(31);
+        let c = generators::gnp(25, 0.3, &mut rng);
+        let motifs = c.count_motifs4();
+        let shapes = [
+            (generators::path(4), motifs.paths * 2),
+            (generators::star(4), motifs.stars * 6),
+            (generators::cycle(4), motifs.cycles * 8),
+            (BitGraph::complete(4), motifs.cliques * 24),
+        ];
+        for (pattern, expected) in shapes.iter() {
+            let mut count = 0;
+            for map in c.find_subgraph(pattern, true) {
+                check_embedding(&c, pattern, &map, true);
+                count += 1;
+            }
+            assert_eq!(count, *expected);
+        }
+        // a 4-cycle lies in every diamond once and in every clique on 4 vertices three times
+        let pattern = generators::cycle(4);
+        let mut count = 0;
+        for map in c.find_subgraph(&pattern, false) {
+            check_embedding(&c, &pattern, &map, false);
+            count += 1;
+        }
+        assert_eq!(
+            count,
+            (motifs.cycles + motifs.diamonds + 3 * motifs.cliques) * 8
+        );
+    }
+}
@@ -0,0 +1,802 @@
+//! Graph traversals beyond the plain `DfsIterator`
+
+use crate::{algorithms, BitGraph, UndirectedGraph};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::{BitSetAnd, BitSetLike, BitSetNot};
+
+impl BitGraph {
+    /// Returns a `DfsEvents` iterator starting at vertex `v`
+    pub fn dfs_events(&self, v: u32) -> DfsEvents<'_> {
+        algorithms::dfs_events(self, v)
+    }
+
+    /// Returns a `DfsPostorder` iterator starting at vertex `v`
+    pub fn dfs_postorder(&self, v: u32) -> DfsPostorder<'_> {
+        algorithms::dfs_postorder(self, v)
+    }
+
+    /// Returns a `DfsBounded` iterator starting at vertex `v` that does not descend deeper
+    /// than `max_depth` edges
+    pub fn dfs_bounded(&self, v: u32, max_depth: u32) -> DfsBounded<'_> {
+        algorithms::dfs_bounded(self, v, max_depth)
+    }
+
+    /// Returns a shortest path from `v` to `target` found by iterative deepening depth first
+    /// search, `None` if `target` is not reachable from `v`.
+    /// Uses memory proportional to the path length on top of the per-vertex depth table
+    pub fn iddfs(&self, v: u32, target: u32) -> Option<Vec<u32>> {
+        algorithms::iddfs(self, v, target)
+    }
+
+    /// Returns a `DfsEdges` iterator starting at vertex `v`
+    pub fn dfs_edges(&self, v: u32) -> DfsEdges<'_> {
+        algorithms::dfs_edges(self, v)
+    }
+
+    /// Performs a depth first search from `v` and reports its progress to `visitor`.
+    /// Every edge of the explored component is reported exactly once, either as a tree edge
+    /// or as a non-tree edge from a vertex to one of its ancestors
+    pub fn depth_first_visit<V: Visitor>(&self, v: u32, visitor: &mut V) {
+        algorithms::depth_first_visit(self, v, visitor)
+    }
+
+    /// Like `dfs`, but keeps its state in `scratch` instead of allocating it
+    pub fn dfs_with<'a>(&'a self, scratch: &'a mut TraversalScratch, v: u32) -> DfsWith<'a> {
+        algorithms::dfs_with(self, scratch, v)
+    }
+
+    /// Returns a `BfsWith` iterator starting at vertex `v` that keeps its state in `scratch`
+    pub fn bfs_with<'a>(&'a self, scratch: &'a mut TraversalScratch, v: u32) -> BfsWith<'a> {
+        algorithms::bfs_with(self, scratch, v)
+    }
+
+    /// Returns a `BfsLayers` iterator starting at vertex `v`
+    pub fn bfs_layers(&self, v: u32) -> BfsLayers<'_> {
+        self.check_bounds(v);
+        let mut visited = hibitset::BitSet::with_capacity(self.m_data.len() as u32);
+        visited.add(v);
+        let mut frontier = hibitset::BitSet::new();
+        frontier.add(v);
+        BfsLayers {
+            m_graph: self,
+            m_visited: visited,
+            m_frontier: Some(frontier),
+        }
+    }
+
+    /// Returns a `MultiBfs` iterator that starts a breadth first search from all vertices
+    /// in `sources` at once and yields every reached vertex with its distance to the closest source
+    pub fn bfs_multi(&self, sources: &hibitset::BitSet) -> MultiBfs<'_> {
+        algorithms::bfs_multi(self, sources)
+    }
+}
+
+/// Callbacks invoked by `BitGraph::depth_first_visit`, all of them default to doing nothing
+pub trait Visitor {
+    /// `v` is visited for the first time
+    fn discover(&mut self, _v: u32) {}
+
+    /// The search descends from `u` to its new child `v`, reported before `v` is discovered
+    fn tree_edge(&mut self, _u: u32, _v: u32) {}
+
+    /// `u` is adjacent to its ancestor `v` through an edge that is not in the DFS tree
+    fn non_tree_edge(&mut self, _u: u32, _v: u32) {}
+
+    /// All neighbors of `v` have been explored
+    fn finish(&mut self, _v: u32) {}
+}
+
+/// Reusable state for `BitGraph::dfs_with` and `BitGraph::bfs_with`
+///
+/// The buffers keep their allocation between traversals and only the vertices marked by the
+/// previous traversal are cleared, so a reset costs nothing for small traversals on a large graph
+#[derive(Debug, Clone, Default)]
+pub struct TraversalScratch {
+    m_visited: hibitset::BitSet,
+    m_touched: Vec<u32>,
+    m_stack: Vec<u32>,
+    m_queue: VecDeque<u32>,
+}
+
+impl TraversalScratch {
+    /// Creates an empty workspace, it grows to the size of the graphs it is used with
+    pub fn new() -> TraversalScratch {
+        TraversalScratch::default()
+    }
+
+    /// Creates a workspace for graphs with up to `capacity` vertices
+    pub fn with_capacity(capacity: u32) -> TraversalScratch {
+        TraversalScratch {
+            m_visited: hibitset::BitSet::with_capacity(capacity),
+            m_touched: Vec::with_capacity(capacity as usize),
+            m_stack: Vec::new(),
+            m_queue: VecDeque::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        for v in self.m_touched.drain(..) {
+            self.m_visited.remove(v);
+        }
+        self.m_stack.clear();
+        self.m_queue.clear();
+    }
+
+    /// Marks `v` as visited, returns `false` if it already was
+    fn mark(&mut self, v: u32) -> bool {
+        let new = !self.m_visited.add(v);
+        if new {
+            self.m_touched.push(v);
+        }
+        new
+    }
+}
+
+/// Depth first search iterator returned by `BitGraph::dfs_with`
+pub struct DfsWith<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_scratch: &'a mut TraversalScratch,
+}
+
+impl<'a, G: UndirectedGraph> DfsWith<'a, G> {
+    pub(crate) fn new(graph: &'a G, scratch: &'a mut TraversalScratch, v: u32) -> DfsWith<'a, G> {
+        scratch.reset();
+        scratch.mark(v);
+        scratch.m_stack.push(v);
+        DfsWith {
+            m_graph: graph,
+            m_scratch: scratch,
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for DfsWith<'a, G> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_scratch.m_stack.pop()?;
+        for w in self.m_graph.neighbors(v) {
+            if self.m_scratch.mark(w) {
+                self.m_scratch.m_stack.push(w);
+            }
+        }
+        Some(v)
+    }
+}
+
+/// Breadth first search iterator returned by `BitGraph::bfs_with`
+pub struct BfsWith<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_scratch: &'a mut TraversalScratch,
+}
+
+impl<'a, G: UndirectedGraph> BfsWith<'a, G> {
+    pub(crate) fn new(graph: &'a G, scratch: &'a mut TraversalScratch, v: u32) -> BfsWith<'a, G> {
+        scratch.reset();
+        scratch.mark(v);
+        scratch.m_queue.push_back(v);
+        BfsWith {
+            m_graph: graph,
+            m_scratch: scratch,
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for BfsWith<'a, G> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_scratch.m_queue.pop_front()?;
+        for w in self.m_graph.neighbors(v) {
+            if self.m_scratch.mark(w) {
+                self.m_scratch.m_queue.push_back(w);
+            }
+        }
+        Some(v)
+    }
+}
+
+/// Event emitted by `DfsEvents`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfsEvent {
+    /// `v` is visited for the first time, its `parent` is `None` for the start vertex
+    Discover {
+        v: u32,
+        parent: Option<u32>,
+        depth: u32,
+    },
+    /// All neighbors of `v` have been explored
+    Finish { v: u32 },
+}
+
+/// Iterator that performs a depth first search on an `UndirectedGraph` and yields
+/// structured `DfsEvent`s instead of bare vertices
+pub struct DfsEvents<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_visited: hibitset::BitSet,
+    m_stack: Vec<(u32, G::Neighbors<'a>)>,
+    m_start: Option<u32>,
+}
+
+impl<'a, G: UndirectedGraph> DfsEvents<'a, G> {
+    pub(crate) fn new(graph: &'a G, v: u32) -> DfsEvents<'a, G> {
+        DfsEvents {
+            m_graph: graph,
+            m_visited: hibitset::BitSet::with_capacity(graph.capacity()),
+            m_stack: Vec::new(),
+            m_start: Some(v),
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for DfsEvents<'a, G> {
+    type Item = DfsEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(v) = self.m_start.take() {
+            self.m_visited.add(v);
+            self.m_stack.push((v, self.m_graph.neighbors(v)));
+            return Some(DfsEvent::Discover {
+                v,
+                parent: None,
+                depth: 0,
+            });
+        }
+        let depth = self.m_stack.len() as u32;
+        let (v, neighbors) = self.m_stack.last_mut()?;
+        let v = *v;
+        for w in neighbors {
+            if !self.m_visited.add(w) {
+                self.m_stack.push((w, self.m_graph.neighbors(w)));
+                return Some(DfsEvent::Discover {
+                    v: w,
+                    parent: Some(v),
+                    depth,
+                });
+            }
+        }
+        self.m_stack.pop();
+        Some(DfsEvent::Finish { v })
+    }
+}
+
+/// Iterator that yields the vertices reachable from the start vertex in the order in which
+/// a depth first search finishes them, i.e. every vertex after all of its DFS descendants
+pub struct DfsPostorder<'a, G: UndirectedGraph = BitGraph> {
+    m_events: DfsEvents<'a, G>,
+}
+
+impl<'a, G: UndirectedGraph> DfsPostorder<'a, G> {
+    pub(crate) fn new(graph: &'a G, v: u32) -> DfsPostorder<'a, G> {
+        DfsPostorder {
+            m_events: DfsEvents::new(graph, v),
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for DfsPostorder<'a, G> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for event in &mut self.m_events {
+            if let DfsEvent::Finish { v } = event {
+                return Some(v);
+            }
+        }
+        None
+    }
+}
+
+/// Iterator that performs a depth first search limited to `max_depth` edges from the start
+/// vertex and yields every vertex within that distance exactly once.
+/// A vertex that is reached again on a shorter path is explored again, so no vertex within
+/// the bound is missed because the search first entered it from too deep
+pub struct DfsBounded<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_depth: Vec<u32>,
+    m_seen: hibitset::BitSet,
+    m_stack: Vec<(u32, G::Neighbors<'a>)>,
+    m_start: Option<u32>,
+    m_max_depth: u32,
+}
+
+impl<'a, G: UndirectedGraph> DfsBounded<'a, G> {
+    pub(crate) fn new(graph: &'a G, v: u32, max_depth: u32) -> DfsBounded<'a, G> {
+        let n = graph.capacity() as usize;
+        DfsBounded {
+            m_graph: graph,
+            m_depth: vec![u32::MAX; n],
+            m_seen: hibitset::BitSet::with_capacity(n as u32),
+            m_stack: Vec::new(),
+            m_start: Some(v),
+            m_max_depth: max_depth,
+        }
+    }
+
+    /// Returns the path to `target` if the search reaches it, otherwise `Err` telling
+    /// whether some vertex was cut off at the depth bound
+    pub(crate) fn path_to(mut self, target: u32) -> Result<Vec<u32>, bool> {
+        let mut cut_off = false;
+        while let Some(w) = self.advance() {
+            if w == target {
+                let mut path: Vec<u32> = self.m_stack.iter().map(|f| f.0).collect();
+                if path.last() != Some(&target) {
+                    path.push(target);
+                }
+                return Ok(path);
+            }
+            cut_off |= self.m_depth[w as usize] == self.m_max_depth;
+        }
+        Err(cut_off)
+    }
+
+    /// Returns the next vertex whose depth improved, rediscoveries included
+    fn advance(&mut self) -> Option<u32> {
+        if let Some(v) = self.m_start.take() {
+            self.m_depth[v as usize] = 0;
+            if self.m_max_depth > 0 {
+                self.m_stack.push((v, self.m_graph.neighbors(v)));
+            }
+            return Some(v);
+        }
+        loop {
+            let depth = self.m_stack.len() as u32;
+            let (_, neighbors) = self.m_stack.last_mut()?;
+            let mut next = None;
+            for w in neighbors {
+                if self.m_depth[w as usize] > depth {
+                    self.m_depth[w as usize] = depth;
+                    next = Some(w);
+                    break;
+                }
+            }
+            match next {
+                Some(w) => {
+                    if depth < self.m_max_depth {
+                        self.m_stack.push((w, self.m_graph.neighbors(w)));
+                    }
+                    return Some(w);
+                }
+                None => {
+                    self.m_stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for DfsBounded<'a, G> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(v) = self.advance() {
+            if !self.m_seen.add(v) {
+                return Some(v);
+            }
+        }
+        None
+    }
+}
+
+/// Label of an edge traversed by `DfsEdges`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// The edge discovered a new vertex and is part of the DFS tree
+    Tree,
+    /// The edge leads from a vertex to one of its ancestors, other than its parent
+    Back,
+}
+
+/// Iterator that performs a depth first search on an `UndirectedGraph` and yields every edge of the
+/// explored component exactly once as `(from, to, kind)`.
+/// Tree edges point away from the start vertex, back edges point from descendant to ancestor
+pub struct DfsEdges<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_depth: Vec<u32>,
+    m_stack: Vec<(u32, G::Neighbors<'a>)>,
+}
+
+impl<'a, G: UndirectedGraph> DfsEdges<'a, G> {
+    pub(crate) fn new(graph: &'a G, v: u32) -> DfsEdges<'a, G> {
+        let mut depth = vec![u32::MAX; graph.capacity() as usize];
+        depth[v as usize] = 0;
+        DfsEdges {
+            m_graph: graph,
+            m_depth: depth,
+            m_stack: vec![(v, graph.neighbors(v))],
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for DfsEdges<'a, G> {
+    type Item = (u32, u32, EdgeKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let len = self.m_stack.len();
+            let parent = if len > 1 {
+                Some(self.m_stack[len - 2].0)
+            } else {
+                None
+            };
+            let (v, neighbors) = self.m_stack.last_mut()?;
+            let v = *v;
+            let depth = self.m_depth[v as usize];
+            for w in neighbors {
+                let d = self.m_depth[w as usize];
+                if d == u32::MAX {
+                    self.m_depth[w as usize] = depth + 1;
+                    self.m_stack.push((w, self.m_graph.neighbors(w)));
+                    return Some((v, w, EdgeKind::Tree));
+                }
+                if d < depth && parent != Some(w) {
+                    return Some((v, w, EdgeKind::Back));
+                }
+            }
+            self.m_stack.pop();
+        }
+    }
+}
+
+/// Iterator that performs a breadth first search and yields whole distance layers as bitsets,
+/// the `i`-th item holds all vertices at distance `i` from the start vertex
+pub struct BfsLayers<'a> {
+    m_graph: &'a BitGraph,
+    m_visited: hibitset::BitSet,
+    m_frontier: Option<hibitset::BitSet>,
+}
+
+impl<'a> Iterator for BfsLayers<'a> {
+    type Item = hibitset::BitSet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frontier = self.m_frontier.take()?;
+        // a layer only grows as far as its largest vertex, so a long search does not pay the
+        // whole capacity for every layer
+        let mut next = hibitset::BitSet::new();
+        for x in (&frontier).iter() {
+            next |= &BitSetAnd(self.m_graph.row(x), BitSetNot(&self.m_visited));
+        }
+        // `|=` may leave summary bits of empty words set, so look at the members instead
+        if (&next).iter().next().is_some() {
+            self.m_visited |= &next;
+            self.m_frontier = Some(next);
+        }
+        Some(frontier)
+    }
+}
+
+/// Iterator that performs a breadth first search from a set of sources on an `UndirectedGraph`,
+/// yielding `(vertex, distance)` pairs in order of non-decreasing distance
+pub struct MultiBfs<'a, G: UndirectedGraph = BitGraph> {
+    m_graph: &'a G,
+    m_visited: hibitset::BitSet,
+    m_queue: VecDeque<(u32, u32)>,
+}
+
+impl<'a, G: UndirectedGraph> MultiBfs<'a, G> {
+    pub(crate) fn new(graph: &'a G, sources: &hibitset::BitSet) -> MultiBfs<'a, G> {
+        let mut visited = hibitset::BitSet::with_capacity(graph.capacity());
+        let mut queue = VecDeque::new();
+        for s in sources.iter() {
+            visited.add(s);
+            queue.push_back((s, 0));
+        }
+        MultiBfs {
+            m_graph: graph,
+            m_visited: visited,
+            m_queue: queue,
+        }
+    }
+}
+
+impl<'a, G: UndirectedGraph> Iterator for MultiBfs<'a, G> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (v, d) = self.m_queue.pop_front()?;
+        for w in self.m_graph.neighbors(v) {
+            if !self.m_visited.add(w) {
+                self.m_queue.push_back((w, d + 1));
+            }
+        }
+        Some((v, d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DfsEvent, EdgeKind, TraversalScratch, Visitor};
+    use crate::{generators, BitGraph};
+    use hibitset::BitSetLike;
+
+    #[test]
+    fn bfs_multi() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..6 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(8, 9);
+        let mut sources = hibitset::BitSet::new();
+        sources.add(0);
+        sources.add(5);
+        let mut reached: Vec<(u32, u32)> = c.bfs_multi(&sources).collect();
+        reached.sort();
+        assert_eq!(
+            reached,
+            vec![(0, 0), (1, 1), (2, 2), (3, 2), (4, 1), (5, 0), (6, 1)]
+        );
+        let distances: Vec<u32> = c.bfs_multi(&sources).map(|(_, d)| d).collect();
+        assert!(distances.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(c.bfs_multi(&hibitset::BitSet::new()).count(), 0);
+    }
+
+    #[test]
+    fn dfs_events() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(0, 3);
+        c.add_edge(2, 0);
+        c.add_edge(8, 9);
+        let events: Vec<DfsEvent> = c.dfs_events(0).collect();
+        assert_eq!(
+            events,
+            vec![
+                DfsEvent::Discover {
+                    v: 0,
+                    parent: None,
+                    depth: 0
+                },
+                DfsEvent::Discover {
+                    v: 1,
+                    parent: Some(0),
+                    depth: 1
+                },
+                DfsEvent::Discover {
+                    v: 2,
+                    parent: Some(1),
+                    depth: 2
+                },
+                DfsEvent::Finish { v: 2 },
+                DfsEvent::Finish { v: 1 },
+                DfsEvent::Discover {
+                    v: 3,
+                    parent: Some(0),
+                    depth: 1
+                },
+                DfsEvent::Finish { v: 3 },
+                DfsEvent::Finish { v: 0 },
+            ]
+        );
+        assert_eq!(c.dfs_events(5).count(), 2);
+    }
+
+    #[test]
+    fn dfs_edges() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(2, 0);
+        c.add_edge(2, 3);
+        c.add_edge(3, 1);
+        c.add_edge(0, 4);
+        c.add_edge(8, 9);
+        let edges: Vec<(u32, u32, EdgeKind)> = c.dfs_edges(0).collect();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 1, EdgeKind::Tree),
+                (1, 2, EdgeKind::Tree),
+                (2, 0, EdgeKind::Back),
+                (2, 3, EdgeKind::Tree),
+                (3, 1, EdgeKind::Back),
+                (0, 4, EdgeKind::Tree),
+            ]
+        );
+        let complete = BitGraph::complete(6);
+        let edges: Vec<(u32, u32, EdgeKind)> = complete.dfs_edges(3).collect();
+        assert_eq!(edges.len(), 15);
+        assert_eq!(edges.iter().filter(|e| e.2 == EdgeKind::Tree).count(), 5);
+        assert_eq!(c.dfs_edges(5).count(), 0);
+    }
+
+    #[test]
+    fn dfs_postorder() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(1, 3);
+        c.add_edge(0, 4);
+        c.add_edge(4, 2);
+        c.add_edge(8, 9);
+        let order: Vec<u32> = c.dfs_postorder(0).collect();
+        assert_eq!(order, vec![4, 2, 3, 1, 0]);
+        let order: Vec<u32> = c.dfs_postorder(9).collect();
+        assert_eq!(order, vec![8, 9]);
+        let order: Vec<u32> = c.dfs_postorder(5).collect();
+        assert_eq!(order, vec![5]);
+    }
+
+    #[test]
+    fn dfs_bounded() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..6 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 6);
+        let mut reached: Vec<u32> = c.dfs_bounded(0, 2).collect();
+        reached.sort_unstable();
+        assert_eq!(reached, vec![0, 1, 2, 5, 6]);
+        let reached: Vec<u32> = c.dfs_bounded(0, 0).collect();
+        assert_eq!(reached, vec![0]);
+        assert_eq!(c.dfs_bounded(3, 10).count(), 7);
+    }
+
+    #[test]
+    fn iddfs() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..6 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 6);
+        c.add_edge(8, 9);
+        assert_eq!(c.iddfs(0, 0), Some(vec![0]));
+        assert_eq!(c.iddfs(0, 5), Some(vec![0, 6, 5]));
+        assert_eq!(c.iddfs(1, 4), Some(vec![1, 2, 3, 4]));
+        assert_eq!(c.iddfs(0, 8), None);
+        assert_eq!(c.iddfs(7, 8), None);
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        log: Vec<String>,
+    }
+
+    impl Visitor for Recorder {
+        fn discover(&mut self, v: u32) {
+            self.log.push(format!("d{}", v));
+        }
+
+        fn tree_edge(&mut self, u: u32, v: u32) {
+            self.log.push(format!("t{}{}", u, v));
+        }
+
+        fn non_tree_edge(&mut self, u: u32, v: u32) {
+            self.log.push(format!("n{}{}", u, v));
+        }
+
+        fn finish(&mut self, v: u32) {
+            self.log.push(format!("f{}", v));
+        }
+    }
+
+    /// Finds bridges with low-link values, the classic use of edge callbacks
+    struct Bridges {
+        time: u32,
+        disc: Vec<u32>,
+        low: Vec<u32>,
+        parent: Vec<u32>,
+        bridges: Vec<(u32, u32)>,
+    }
+
+    impl Visitor for Bridges {
+        fn discover(&mut self, v: u32) {
+            self.disc[v as usize] = self.time;
+            self.low[v as usize] = self.time;
+            self.time += 1;
+        }
+
+        fn tree_edge(&mut self, u: u32, v: u32) {
+            self.parent[v as usize] = u;
+        }
+
+        fn non_tree_edge(&mut self, u: u32, v: u32) {
+            self.low[u as usize] = self.low[u as usize].min(self.disc[v as usize]);
+        }
+
+        fn finish(&mut self, v: u32) {
+            let p = self.parent[v as usize];
+            if p != u32::MAX {
+                self.low[p as usize] = self.low[p as usize].min(self.low[v as usize]);
+                if self.low[v as usize] > self.disc[p as usize] {
+                    self.bridges.push((p, v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn depth_first_visit() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(2, 0);
+        c.add_edge(2, 3);
+        let mut recorder = Recorder::default();
+        c.depth_first_visit(0, &mut recorder);
+        assert_eq!(
+            recorder.log,
+            vec!["d0", "t01", "d1", "t12", "d2", "n20", "t23", "d3", "f3", "f2", "f1", "f0"]
+        );
+
+        c.add_edge(3, 4);
+        c.add_edge(4, 5);
+        c.add_edge(5, 3);
+        c.add_edge(5, 6);
+        let mut bridges = Bridges {
+            time: 0,
+            disc: vec![0; capacity],
+            low: vec![0; capacity],
+            parent: vec![u32::MAX; capacity],
+            bridges: Vec::new(),
+        };
+        c.depth_first_visit(0, &mut bridges);
+        bridges.bridges.sort_unstable();
+        assert_eq!(bridges.bridges, vec![(2, 3), (5, 6)]);
+    }
+
+    #[test]
+    fn traversal_scratch() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..5 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 3);
+        c.add_edge(7, 8);
+        let mut scratch = TraversalScratch::new();
+        for v in 0..capacity as u32 {
+            let expected: Vec<u32> = c.dfs(v).collect();
+            let tmp: Vec<u32> = c.dfs_with(&mut scratch, v).collect();
+            assert_eq!(tmp, expected);
+        }
+        let tmp: Vec<u32> = c.bfs_with(&mut scratch, 0).collect();
+        assert_eq!(tmp, vec![0, 1, 3, 2, 4, 5]);
+        assert_eq!(c.dfs_with(&mut scratch, 0).take(2).count(), 2);
+        let tmp: Vec<u32> = c.bfs_with(&mut scratch, 8).collect();
+        assert_eq!(tmp, vec![8, 7]);
+
+        let mut scratch = TraversalScratch::with_capacity(capacity as u32);
+        assert_eq!(c.bfs_with(&mut scratch, 5).count(), 6);
+    }
+
+    #[test]
+    fn bfs_layers() {
+        let capacity: usize = 10;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for i in 0..6 {
+            c.add_edge(i, i + 1);
+        }
+        c.add_edge(0, 3);
+        c.add_edge(8, 9);
+        let layers: Vec<Vec<u32>> = c
+            .bfs_layers(0)
+            .map(|layer| layer.iter().collect())
+            .collect();
+        assert_eq!(
+            layers,
+            vec![vec![0], vec![1, 3], vec![2, 4], vec![5], vec![6]]
+        );
+        let layers: Vec<Vec<u32>> = c
+            .bfs_layers(7)
+            .map(|layer| layer.iter().collect())
+            .collect();
+        assert_eq!(layers, vec![vec![7]]);
+        assert_eq!(c.bfs_layers(9).count(), 2);
+
+        let c = generators::path(4096);
+        for (i, layer) in c.bfs_layers(0).enumerate() {
+            assert_eq!(layer.layer0_as_slice().len(), i / 64 + 1);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Spanning trees and other tree related algorithms
+
+use crate::random::RandomSource;
+use crate::{algorithms, BitGraph};
+use alloc::vec::Vec;
+
+impl BitGraph {
+    /// Returns a BFS spanning tree of the component containing `root`.
+    /// The tree has the same capacity as the graph, vertices outside the component are isolated
+    pub fn spanning_tree(&self, root: u32) -> BitGraph {
+        algorithms::spanning_tree(self, root)
+    }
+
+    /// Returns a spanning forest consisting of one BFS spanning tree per component
+    pub fn spanning_forest(&self) -> BitGraph {
+        algorithms::spanning_forest(self)
+    }
+
+    /// Returns the Prüfer sequence of the graph if it is a tree spanning all `capacity`
+    /// vertices, `None` otherwise. Repeatedly removes the smallest leaf and records its neighbor
+    pub fn to_prufer(&self) -> Option<Vec<u32>> {
+        algorithms::to_prufer(self)
+    }
+
+    /// Returns a uniformly random spanning tree of each component using Wilson's
+    /// loop-erased random walk algorithm. For connected graphs this is a uniform spanning tree
+    pub fn random_spanning_tree<R: RandomSource>(&self, rng: &mut R) -> BitGraph {
+        algorithms::random_spanning_tree(self, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SplitMix64;
+    use crate::BitGraph;
+
+    #[test]
+    fn spanning_tree() {
+        let capacity: usize = 10;
+        let c = BitGraph::complete(capacity as u32);
+        let t = c.spanning_tree(3);
+        assert!(t.is_tree());
+        assert_eq!(t.order(), capacity as u32);
+        assert_eq!(t.degree(3), capacity as u32 - 1);
+
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.spanning_tree(0).size(), 0);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(2, 0);
+        c.add_edge(5, 6);
+        c.add_edge(6, 7);
+        c.add_edge(7, 8);
+        c.add_edge(8, 5);
+        let t = c.spanning_tree(0);
+        assert!(t.is_tree());
+        assert_eq!(t.order(), 3);
+
+        let f = c.spanning_forest();
+        assert!(f.is_forest());
+        assert_eq!(f.size(), 5);
+        assert_eq!(f.component_count(), 2);
+        for u in 0..capacity as u32 {
+            for v in f.neighbors(u) {
+                assert!(c.neighbors(u).any(|w| w == v));
+            }
+        }
+    }
+
+    #[test]
+    fn random_spanning_tree() {
+        let mut rng = SplitMix64::new(7);
+        let c = BitGraph::complete(4);
+        // K4 has 16 spanning trees, each should show up
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..500 {
+            let t = c.random_spanning_tree(&mut rng);
+            assert!(t.is_tree());
+            assert_eq!(t.order(), 4);
+            let edges: Vec<(u32, u32)> = (0..4u32)
+                .flat_map(|u| t.neighbors(u).filter(move |&v| v > u).map(move |v| (u, v)))
+                .collect();
+            seen.insert(edges);
+        }
+        assert_eq!(seen.len(), 16);
+
+        let mut c = BitGraph::with_capacity(10);
+        c.add_edge(0, 1);
+        c.add_edge(1, 2);
+        c.add_edge(2, 0);
+        c.add_edge(5, 6);
+        let f = c.random_spanning_tree(&mut rng);
+        assert!(f.is_forest());
+        assert_eq!(f.size(), 3);
+        assert_eq!(f.degree(5), 1);
+    }
+}
@@ -0,0 +1,337 @@
+//! A graph backend that stores every edge bit once
+
+use crate::{BitGraph, UndirectedGraph, MAX_CAPACITY};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// An undirected graph that keeps only the lower triangle of its adjacency matrix, one bit for
+/// every pair `u > v` at position `u * (u - 1) / 2 + v`. It needs half the memory of a full
+/// matrix. The neighbors of `v` below it are consecutive bits, the ones above it are one bit
+/// in each later row, so iterating a neighborhood is slower than in `BitGraph` or
+/// `MatrixBitGraph`, and degrees are counted instead of stored
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TriangleBitGraph {
+    m_words: Vec<u64>,
+    m_capacity: u32,
+}
+
+/// Position of the pair `(u, v)` in the lower triangle, `u` and `v` must differ
+#[inline]
+fn position(u: u32, v: u32) -> usize {
+    let (hi, lo) = if u > v { (u, v) } else { (v, u) };
+    hi as usize * (hi as usize - 1) / 2 + lo as usize
+}
+
+impl TriangleBitGraph {
+    /// Creates a new TriangleBitGraph with `capacity` vertices and no edges
+    pub fn with_capacity(capacity: u32) -> TriangleBitGraph {
+        if capacity > MAX_CAPACITY as u32 {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                capacity, MAX_CAPACITY
+            )
+        }
+        let n = capacity as usize;
+        TriangleBitGraph {
+            m_words: vec![0; (n * n.saturating_sub(1) / 2).div_ceil(64)],
+            m_capacity: capacity,
+        }
+    }
+
+    /// Returns `graph` with each edge stored once
+    pub fn from_bit_graph(graph: &BitGraph) -> TriangleBitGraph {
+        let mut triangle = Self::with_capacity(graph.capacity());
+        for (u, v) in graph.edges() {
+            triangle.add_edge(u, v);
+        }
+        triangle
+    }
+
+    /// Returns the graph as a `BitGraph` of the same capacity
+    pub fn to_bit_graph(&self) -> BitGraph {
+        BitGraph::from_rows(
+            (0..self.m_capacity)
+                .map(|v| self.neighbors(v).collect())
+                .collect(),
+        )
+    }
+
+    #[inline]
+    fn check_bounds(&self, idx: u32) {
+        if idx >= self.m_capacity {
+            panic!(
+                "Out of bounds. Given: {}, Allowed: {}",
+                idx, self.m_capacity
+            )
+        }
+    }
+
+    #[inline]
+    fn check_is_same(&self, u: u32, v: u32) {
+        if u == v {
+            panic!("Edge needs two distinct endpoints, given: {} {}", u, v)
+        }
+    }
+
+    #[inline]
+    fn bit(&self, position: usize) -> bool {
+        self.m_words[position / 64] & (1 << (position % 64)) != 0
+    }
+
+    /// Adds a new undirected edge from `u` to `v`
+    /// If the edge already exists, the graph is not updated
+    pub fn add_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        self.check_is_same(u, v);
+        let p = position(u, v);
+        self.m_words[p / 64] |= 1 << (p % 64);
+    }
+
+    /// Removes the edge from `u` to `v` after performing boundary checks.
+    /// If the edge is not present the graph is not updated
+    pub fn remove_edge(&mut self, u: u32, v: u32) {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        if u != v {
+            let p = position(u, v);
+            self.m_words[p / 64] &= !(1 << (p % 64));
+        }
+    }
+
+    /// Returns `true` if `u` and `v` are adjacent
+    pub fn has_edge(&self, u: u32, v: u32) -> bool {
+        self.check_bounds(u);
+        self.check_bounds(v);
+        u != v && self.bit(position(u, v))
+    }
+
+    /// Removes all edges and keeps the capacity
+    pub fn clear(&mut self) {
+        self.m_words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Removes all edges incident to `v`
+    pub fn clear_vertex(&mut self, v: u32) {
+        let neighbors: Vec<u32> = self.neighbors(v).collect();
+        for w in neighbors {
+            self.remove_edge(v, w);
+        }
+    }
+
+    /// Contracts the edge (target, source) by adding all neighbors
+    /// of source to `target` and removing `source`
+    pub fn contract_edge(&mut self, target: u32, source: u32) {
+        if !self.has_edge(target, source) {
+            panic!(
+                "Edge ({}, {}) does not exist. Can't contract!",
+                target, source
+            );
+        }
+        let neighbors: Vec<u32> = self.neighbors(source).collect();
+        for w in neighbors {
+            self.remove_edge(source, w);
+            if w != target {
+                self.add_edge(target, w);
+            }
+        }
+    }
+
+    /// Returns an iterator over the neighborhood of vertex `v` in increasing order
+    pub fn neighbors(&self, v: u32) -> TriangleNeighbors<'_> {
+        self.check_bounds(v);
+        TriangleNeighbors {
+            m_graph: self,
+            m_vertex: v,
+            m_next: 0,
+        }
+    }
+
+    /// Number of vertex slots, valid vertices are `0..capacity()`
+    pub fn capacity(&self) -> u32 {
+        self.m_capacity
+    }
+
+    /// Number of neighbors of `v`, counted by iterating them
+    pub fn degree(&self, v: u32) -> u32 {
+        self.neighbors(v).count() as u32
+    }
+
+    /// Number of vertices in the graph
+    pub fn order(&self) -> u32 {
+        let mut seen = hibitset::BitSet::with_capacity(self.m_capacity);
+        for (u, v) in self.edges() {
+            seen.add(u);
+            seen.add(v);
+        }
+        seen.layer0_as_slice()
+            .iter()
+            .map(|word| word.count_ones())
+            .sum()
+    }
+
+    /// Number of edges in the graph, a popcount over the triangle
+    pub fn size(&self) -> u64 {
+        self.m_words
+            .iter()
+            .map(|word| word.count_ones() as u64)
+            .sum()
+    }
+
+    /// Returns an iterator over all edges `(u, v)` with `u < v`, in storage order: ordered by
+    /// `v` and then `u`
+    pub fn edges(&self) -> TriangleEdges<'_> {
+        TriangleEdges {
+            m_words: &self.m_words,
+            m_index: 0,
+            m_current: self.m_words.first().copied().unwrap_or(0),
+            m_row: 1,
+            m_row_start: 0,
+        }
+    }
+
+    /// Estimated number of bytes held by the graph
+    pub fn memory_usage(&self) -> usize {
+        core::mem::size_of::<TriangleBitGraph>()
+            + self.m_words.capacity() * core::mem::size_of::<u64>()
+    }
+}
+
+impl UndirectedGraph for TriangleBitGraph {
+    type Neighbors<'a> = TriangleNeighbors<'a>;
+
+    fn capacity(&self) -> u32 {
+        self.m_capacity
+    }
+
+    fn neighbors(&self, v: u32) -> Self::Neighbors<'_> {
+        TriangleBitGraph::neighbors(self, v)
+    }
+
+    fn has_edge(&self, u: u32, v: u32) -> bool {
+        TriangleBitGraph::has_edge(self, u, v)
+    }
+
+    fn size(&self) -> u64 {
+        TriangleBitGraph::size(self)
+    }
+}
+
+/// Iterator over the neighbors of a vertex of a `TriangleBitGraph`, created by
+/// `TriangleBitGraph::neighbors`
+pub struct TriangleNeighbors<'a> {
+    m_graph: &'a TriangleBitGraph,
+    m_vertex: u32,
+    m_next: u32,
+}
+
+impl<'a> Iterator for TriangleNeighbors<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.m_vertex;
+        while self.m_next < self.m_graph.m_capacity {
+            let w = self.m_next;
+            self.m_next += 1;
+            if w != v && self.m_graph.bit(position(v, w)) {
+                return Some(w);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> FusedIterator for TriangleNeighbors<'a> {}
+
+/// Iterator over the edges of a `TriangleBitGraph`, created by `TriangleBitGraph::edges`.
+/// Every edge is reported once, with the smaller vertex first
+pub struct TriangleEdges<'a> {
+    m_words: &'a [u64],
+    m_index: usize,
+    m_current: u64,
+    m_row: u32,
+    m_row_start: usize,
+}
+
+impl<'a> Iterator for TriangleEdges<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.m_current == 0 {
+            self.m_index += 1;
+            self.m_current = *self.m_words.get(self.m_index)?;
+        }
+        let p = self.m_index * 64 + self.m_current.trailing_zeros() as usize;
+        self.m_current &= self.m_current - 1;
+        // row `v` holds the positions `v * (v - 1) / 2..v * (v + 1) / 2`
+        while p >= self.m_row_start + self.m_row as usize {
+            self.m_row_start += self.m_row as usize;
+            self.m_row += 1;
+        }
+        Some(((p - self.m_row_start) as u32, self.m_row))
+    }
+}
+
+impl<'a> FusedIterator for TriangleEdges<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SplitMix64;
+    use crate::{algorithms, generators, BitGraph, MatrixBitGraph, TriangleBitGraph};
+
+    #[test]
+    fn triangle_graph() {
+        let capacity: usize = 70;
+        let mut c = TriangleBitGraph::with_capacity(capacity as u32);
+        c.add_edge(0, 69);
+        c.add_edge(64, 3);
+        c.add_edge(3, 4);
+        c.add_edge(4, 3);
+        assert_eq!(c.size(), 3);
+        assert_eq!(c.order(), 5);
+        assert_eq!(c.degree(3), 2);
+        assert!(c.has_edge(69, 0) && !c.has_edge(3, 3));
+        let tmp: Vec<u32> = c.neighbors(3).collect();
+        assert_eq!(tmp, vec![4, 64]);
+        let tmp: Vec<(u32, u32)> = c.edges().collect();
+        assert_eq!(tmp, vec![(3, 4), (3, 64), (0, 69)]);
+        c.contract_edge(3, 64);
+        assert_eq!(c.degree(64), 0);
+        c.clear_vertex(0);
+        assert_eq!(c.size(), 1);
+        c.clear();
+        assert_eq!(c.order(), 0);
+
+        let full = MatrixBitGraph::with_capacity(capacity as u32);
+        assert!(2 * c.memory_usage() < full.memory_usage() + 64);
+        assert_eq!(TriangleBitGraph::with_capacity(1).edges().count(), 0);
+    }
+
+    #[test]
+    fn matches_bit_graph() {
+        let capacity: usize = 90;
+        let mut rng = SplitMix64::new(81);
+        let a = generators::gnp(capacity as u32, 0.1, &mut rng);
+        let c = TriangleBitGraph::from_bit_graph(&a);
+        assert_eq!(c.to_bit_graph(), a);
+        assert_eq!(c.size(), a.size());
+        assert_eq!(c.order(), a.order());
+        let mut edges: Vec<(u32, u32)> = c.edges().collect();
+        edges.sort_unstable();
+        assert_eq!(edges, a.edges().collect::<Vec<(u32, u32)>>());
+        for v in 0..capacity as u32 {
+            assert!(c.neighbors(v).eq(a.neighbors(v)));
+        }
+        assert_eq!(
+            algorithms::connected_components(&c),
+            algorithms::connected_components(&a)
+        );
+        let mut d = c.clone();
+        let mut b: BitGraph = a.clone();
+        let (u, w) = a.edges().next().unwrap();
+        d.contract_edge(w, u);
+        b.contract_edge(w, u);
+        assert_eq!(d.to_bit_graph(), b);
+    }
+}
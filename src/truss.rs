@@ -0,0 +1,109 @@
+//! Truss decomposition
+
+use crate::BitGraph;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use hibitset::{BitSetAnd, BitSetLike};
+
+impl BitGraph {
+    /// Truss number of every edge as `(u, v, k)` with `u < v`, sorted by edge. The truss number
+    /// is the largest `k` such that the edge belongs to a subgraph in which every edge lies on at
+    /// least `k - 2` triangles, so edges on no triangle have truss number 2.
+    /// Edges are peeled in order of their remaining support, the number of triangles on them
+    pub fn truss_numbers(&self) -> Vec<(u32, u32, u32)> {
+        let mut edges: Vec<(u32, u32)> = Vec::new();
+        let mut index: BTreeMap<(u32, u32), usize> = BTreeMap::new();
+        for u in 0..self.m_data.len() as u32 {
+            for v in self.neighbors(u).filter(|&v| u < v) {
+                index.insert((u, v), edges.len());
+                edges.push((u, v));
+            }
+        }
+        let mut support: Vec<u32> = edges
+            .iter()
+            .map(|&(u, v)| self.common_count(u, v))
+            .collect();
+        let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); self.m_data.len()];
+        for (e, &s) in support.iter().enumerate() {
+            buckets[s as usize].push(e);
+        }
+        let mut truss = vec![0; edges.len()];
+        let mut working = self.clone();
+        working.disable_invariant_cache();
+        let mut k = 2;
+        for s in 0..buckets.len() {
+            while let Some(e) = buckets[s].pop() {
+                // skip removed edges and stale bucket entries
+                if truss[e] != 0 || support[e] as usize != s {
+                    continue;
+                }
+                k = k.max(s as u32 + 2);
+                truss[e] = k;
+                let (u, v) = edges[e];
+                let common: Vec<u32> = BitSetAnd(working.row(u), working.row(v)).iter().collect();
+                working.remove_edge(u, v);
+                for w in common {
+                    for &(a, b) in [(u, w), (v, w)].iter() {
+                        let f = index[&(a.min(b), a.max(b))];
+                        if support[f] as usize > s {
+                            support[f] -= 1;
+                            buckets[support[f] as usize].push(f);
+                        }
+                    }
+                }
+            }
+        }
+        edges
+            .into_iter()
+            .zip(truss)
+            .map(|((u, v), k)| (u, v, k))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::BitGraph;
+
+    #[test]
+    fn truss_numbers() {
+        let truss = BitGraph::complete(5).truss_numbers();
+        assert_eq!(truss.len(), 10);
+        assert!(truss.iter().all(|&(_, _, k)| k == 5));
+        assert!(generators::cycle(5)
+            .truss_numbers()
+            .iter()
+            .all(|&(_, _, k)| k == 2));
+
+        // K4 on 0..4, a triangle 3 4 5 attached to it and a pendant edge 5 6
+        let capacity: usize = 7;
+        let mut c = BitGraph::with_capacity(capacity as u32);
+        for u in 0..4 {
+            for v in u + 1..4 {
+                c.add_edge(u, v);
+            }
+        }
+        c.add_edge(3, 4);
+        c.add_edge(3, 5);
+        c.add_edge(4, 5);
+        c.add_edge(5, 6);
+        assert_eq!(
+            c.truss_numbers(),
+            vec![
+                (0, 1, 4),
+                (0, 2, 4),
+                (0, 3, 4),
+                (1, 2, 4),
+                (1, 3, 4),
+                (2, 3, 4),
+                (3, 4, 3),
+                (3, 5, 3),
+                (4, 5, 3),
+                (5, 6, 2),
+            ]
+        );
+        assert!(BitGraph::with_capacity(0).truss_numbers().is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+//! Integer types that can address the vertices of a `BitGraph`
+
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::{AddAssign, SubAssign};
+use hibitset::BitIter;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for usize {}
+}
+
+/// Index type of the vertices of a `BitGraph`, implemented for `u16`, `u32` and `usize`.
+/// Degrees are stored in the same type, so `u16` halves the degree vector of small graphs.
+/// `usize` is only a convenience for code that indexes with it: the capacity is still bounded
+/// by `MAX_CAPACITY`, which fits into a `u32`, and the rest of the API takes `u32` vertices
+pub trait VertexId:
+    sealed::Sealed + Copy + Ord + Hash + Debug + Default + AddAssign + SubAssign + 'static
+{
+    /// Iterator over the neighbors of a vertex, created by `BitGraph::neighbors`
+    type Neighbors<'a>: Iterator<Item = Self>;
+
+    /// Largest capacity this type can address
+    const LIMIT: usize;
+
+    /// The index `1`
+    const ONE: Self;
+
+    /// The index as a `usize`
+    fn index(self) -> usize;
+
+    /// The index `i`, which must fit into the type
+    fn from_index(i: usize) -> Self;
+
+    #[doc(hidden)]
+    fn neighbors(bits: BitIter<&hibitset::BitSet>) -> Self::Neighbors<'_>;
+}
+
+impl VertexId for u32 {
+    type Neighbors<'a> = BitIter<&'a hibitset::BitSet>;
+
+    const LIMIT: usize = u32::MAX as usize;
+    const ONE: u32 = 1;
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_index(i: usize) -> u32 {
+        i as u32
+    }
+
+    #[inline]
+    fn neighbors(bits: BitIter<&hibitset::BitSet>) -> Self::Neighbors<'_> {
+        bits
+    }
+}
+
+impl VertexId for u16 {
+    type Neighbors<'a> = VertexIds<'a, u16>;
+
+    const LIMIT: usize = u16::MAX as usize;
+    const ONE: u16 = 1;
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_index(i: usize) -> u16 {
+        i as u16
+    }
+
+    #[inline]
+    fn neighbors(bits: BitIter<&hibitset::BitSet>) -> Self::Neighbors<'_> {
+        VertexIds::new(bits)
+    }
+}
+
+/// Adds no capacity over `u32`, the rows are hibitsets limited to `MAX_CAPACITY` bits
+impl VertexId for usize {
+    type Neighbors<'a> = VertexIds<'a, usize>;
+
+    const LIMIT: usize = usize::MAX;
+    const ONE: usize = 1;
+
+    #[inline]
+    fn index(self) -> usize {
+        self
+    }
+
+    #[inline]
+    fn from_index(i: usize) -> usize {
+        i
+    }
+
+    #[inline]
+    fn neighbors(bits: BitIter<&hibitset::BitSet>) -> Self::Neighbors<'_> {
+        VertexIds::new(bits)
+    }
+}
+
+/// Iterator over the neighbors of a vertex as indices of type `I`, created by
+/// `BitGraph::neighbors` for index types other than `u32`
+pub struct VertexIds<'a, I> {
+    m_bits: BitIter<&'a hibitset::BitSet>,
+    m_index: PhantomData<I>,
+}
+
+impl<'a, I> VertexIds<'a, I> {
+    fn new(bits: BitIter<&'a hibitset::BitSet>) -> VertexIds<'a, I> {
+        VertexIds {
+            m_bits: bits,
+            m_index: PhantomData,
+        }
+    }
+}
+
+impl<'a, I: VertexId> Iterator for VertexIds<'a, I> {
+    type Item = I;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.m_bits.next().map(|v| I::from_index(v as usize))
+    }
+}
+
+impl<'a, I: VertexId> FusedIterator for VertexIds<'a, I> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BitGraph, MAX_CAPACITY};
+
+    #[test]
+    fn small_index() {
+        let capacity: usize = 1000;
+        let mut c = BitGraph::<u16>::indexed(capacity as u16);
+        let mut d = BitGraph::with_capacity(capacity as u32);
+        for (u, v) in [(0, 1), (1, 999), (999, 0), (500, 1), (0, 1)] {
+            c.add_edge(u, v);
+            d.add_edge(u as u32, v as u32);
+        }
+        c.remove_edge(500, 1);
+        d.remove_edge(500, 1);
+        assert_eq!(c.capacity(), 1000u16);
+        assert_eq!(c.order(), 3);
+        assert_eq!(c.size(), 3);
+        assert_eq!(c.degree(1), 2);
+        assert!(c.has_edge(999, 1) && !c.has_edge(500, 1));
+        let tmp: Vec<u16> = c.neighbors(0).collect();
+        assert_eq!(tmp, vec![1, 999]);
+        let tmp: Vec<(u16, u16)> = c.edges().collect();
+        assert_eq!(tmp, vec![(0, 1), (0, 999), (1, 999)]);
+        assert!(c.edges().map(|(u, v)| (u as u32, v as u32)).eq(d.edges()));
+        // the degree vector takes two bytes per vertex instead of four
+        assert!(c.memory_usage() + capacity * 2 <= d.memory_usage());
+        c.clear_vertex(0);
+        assert_eq!(c.size(), 1);
+
+        let mut e = BitGraph::<usize>::indexed(capacity);
+        e.add_edge(3, 998);
+        let tmp: Vec<usize> = e.neighbors(998).collect();
+        assert_eq!(tmp, vec![3]);
+        assert_eq!(e.order(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_capacity_above_limit() {
+        BitGraph::<usize>::indexed(MAX_CAPACITY + 1);
+    }
+}
@@ -0,0 +1,77 @@
+//! Read-only access to a graph
+
+use crate::BitGraph;
+use core::ops::Deref;
+
+/// A read-only view of a graph, returned by `BitGraph::view`. It derefs to the graph, so every
+/// query method and algorithm is available but no mutating method. The view is `Copy`, `Send`
+/// and `Sync`, one can be handed to many threads at once
+#[derive(Debug, Clone, Copy)]
+pub struct BitGraphView<'a> {
+    m_graph: &'a BitGraph,
+}
+
+impl<'a> BitGraphView<'a> {
+    /// The viewed graph, for the lifetime of the view
+    pub fn graph(&self) -> &'a BitGraph {
+        self.m_graph
+    }
+}
+
+impl<'a> Deref for BitGraphView<'a> {
+    type Target = BitGraph;
+
+    fn deref(&self) -> &BitGraph {
+        self.m_graph
+    }
+}
+
+impl<'a> From<&'a BitGraph> for BitGraphView<'a> {
+    fn from(graph: &'a BitGraph) -> BitGraphView<'a> {
+        graph.view()
+    }
+}
+
+impl BitGraph {
+    /// Returns a read-only view of the graph
+    pub fn view(&self) -> BitGraphView<'_> {
+        BitGraphView { m_graph: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators;
+    use crate::random::SplitMix64;
+    use crate::BitGraphView;
+
+    fn is_sync<T: Send + Sync + Copy>(_: &T) {}
+
+    #[test]
+    fn view_across_threads() {
+        let capacity: usize = 60;
+        let mut rng = SplitMix64::new(68);
+        let mut c = generators::gnp(capacity as u32, 0.2, &mut rng);
+        c.enable_invariant_cache();
+        let view = c.view();
+        is_sync(&view);
+        let degrees: Vec<u32> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4u32)
+                .map(|t| {
+                    scope.spawn(move || {
+                        // the cache is filled from whichever thread asks first
+                        assert_eq!(view.triangle_count(), view.graph().triangle_count());
+                        (t..capacity as u32)
+                            .step_by(4)
+                            .map(|v| view.neighbors(v).count() as u32)
+                            .sum::<u32>()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(degrees.iter().sum::<u32>() as u64, 2 * c.size());
+        assert_eq!(BitGraphView::from(&c).size(), c.size());
+        c.add_edge(0, 1);
+    }
+}
@@ -0,0 +1,278 @@
+//! Edge weights on top of a `BitGraph`
+
+use crate::BitGraph;
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::{Add, Deref};
+
+/// A `BitGraph` with a weight on every edge. The topology stays in the bitset rows, the weights
+/// are kept in a map and follow every mutation. All query methods of `BitGraph` are available
+/// through `Deref`
+#[derive(Debug, Clone)]
+pub struct WeightedBitGraph<W> {
+    m_graph: BitGraph,
+    m_weights: BTreeMap<(u32, u32), W>,
+}
+
+/// Map key of the edge `(u, v)`
+#[inline]
+fn key(u: u32, v: u32) -> (u32, u32) {
+    (u.min(v), u.max(v))
+}
+
+impl<W> WeightedBitGraph<W> {
+    /// Creates a new WeightedBitGraph with `capacity` vertices and no edges
+    pub fn with_capacity(capacity: u32) -> WeightedBitGraph<W> {
+        WeightedBitGraph {
+            m_graph: BitGraph::with_capacity(capacity),
+            m_weights: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the edge from `u` to `v` with `weight`. If the edge already exists its weight is
+    /// replaced and the old one returned
+    pub fn add_edge(&mut self, u: u32, v: u32, weight: W) -> Option<W> {
+        self.m_graph.add_edge(u, v);
+        self.m_weights.insert(key(u, v), weight)
+    }
+
+    /// Removes the edge from `u` to `v` and returns its weight, `None` if it is not present
+    pub fn remove_edge(&mut self, u: u32, v: u32) -> Option<W> {
+        self.m_graph.remove_edge(u, v);
+        self.m_weights.remove(&key(u, v))
+    }
+
+    /// Weight of the edge from `u` to `v`, `None` if it is not present
+    pub fn weight(&self, u: u32, v: u32) -> Option<&W> {
+        self.m_weights.get(&key(u, v))
+    }
+
+    /// Mutable weight of the edge from `u` to `v`, `None` if it is not present
+    pub fn weight_mut(&mut self, u: u32, v: u32) -> Option<&mut W> {
+        self.m_weights.get_mut(&key(u, v))
+    }
+
+    /// Removes all edges incident to `v`
+    pub fn clear_vertex(&mut self, v: u32) {
+        let neighbors: Vec<u32> = self.m_graph.neighbors(v).collect();
+        for w in neighbors {
+            self.remove_edge(v, w);
+        }
+    }
+
+    /// Contracts the edge (target, source) like `BitGraph::contract_edge`. The edges of `source`
+    /// move to `target` with their weights, and where `target` already has an edge to the same
+    /// neighbor both weights are combined with `merge(target_weight, source_weight)`.
+    /// The weight of the contracted edge is dropped
+    pub fn contract_edge<F: FnMut(W, W) -> W>(&mut self, target: u32, source: u32, mut merge: F) {
+        self.m_graph.contract_edge(target, source);
+        self.m_weights.remove(&key(target, source));
+        let moved: Vec<u32> = self.m_graph.neighbors(target).collect();
+        for w in moved {
+            if let Some(weight) = self.m_weights.remove(&key(source, w)) {
+                let merged = match self.m_weights.remove(&key(target, w)) {
+                    Some(existing) => merge(existing, weight),
+                    None => weight,
+                };
+                self.m_weights.insert(key(target, w), merged);
+            }
+        }
+    }
+
+    /// The topology of the graph
+    pub fn graph(&self) -> &BitGraph {
+        &self.m_graph
+    }
+
+    /// Returns an iterator over all edges `(u, v, weight)` with `u < v`, ordered by `u` and then `v`
+    pub fn weighted_edges(&self) -> WeightedEdges<'_, W> {
+        WeightedEdges {
+            m_edges: self.m_graph.edges(),
+            m_weights: &self.m_weights,
+        }
+    }
+}
+
+impl<W: Copy + PartialOrd + Add<Output = W> + Default> WeightedBitGraph<W> {
+    /// Length of a shortest path from `source` to every vertex with Dijkstra's algorithm,
+    /// `None` for unreachable vertices. Weights must not be negative
+    pub fn dijkstra(&self, source: u32) -> Vec<Option<W>> {
+        self.dijkstra_until(source, None).0
+    }
+
+    /// Returns the length of a shortest path from `u` to `v` and the path itself,
+    /// `None` if `v` is not reachable from `u`. Weights must not be negative
+    pub fn shortest_path_weighted(&self, u: u32, v: u32) -> Option<(W, Vec<u32>)> {
+        let (distances, parents) = self.dijkstra_until(u, Some(v));
+        let length = distances[v as usize]?;
+        let mut path = vec![v];
+        while *path.last().unwrap() != u {
+            path.push(parents[*path.last().unwrap() as usize]);
+        }
+        path.reverse();
+        Some((length, path))
+    }
+
+    /// Distances and shortest path parents from `source`, stops once `target` is settled
+    fn dijkstra_until(&self, source: u32, target: Option<u32>) -> (Vec<Option<W>>, Vec<u32>) {
+        let n = self.m_graph.capacity() as usize;
+        let mut distances: Vec<Option<W>> = vec![None; n];
+        let mut parents = vec![u32::MAX; n];
+        let mut settled = hibitset::BitSet::with_capacity(n as u32);
+        let mut heap = BinaryHeap::new();
+        self.m_graph.check_bounds(source);
+        distances[source as usize] = Some(W::default());
+        heap.push(Candidate(W::default(), source));
+        while let Some(Candidate(d, v)) = heap.pop() {
+            if settled.add(v) {
+                continue;
+            }
+            if Some(v) == target {
+                break;
+            }
+            for w in self.m_graph.neighbors(v) {
+                let next = d + self.m_weights[&key(v, w)];
+                let shorter = match distances[w as usize] {
+                    Some(old) => next < old,
+                    None => true,
+                };
+                if shorter {
+                    distances[w as usize] = Some(next);
+                    parents[w as usize] = v;
+                    heap.push(Candidate(next, w));
+                }
+            }
+        }
+        (distances, parents)
+    }
+}
+
+impl<W> Deref for WeightedBitGraph<W> {
+    type Target = BitGraph;
+
+    fn deref(&self) -> &BitGraph {
+        &self.m_graph
+    }
+}
+
+/// Iterator over the edges of a `WeightedBitGraph` with their weights,
+/// created by `WeightedBitGraph::weighted_edges`
+pub struct WeightedEdges<'a, W> {
+    m_edges: crate::Edges<'a>,
+    m_weights: &'a BTreeMap<(u32, u32), W>,
+}
+
+impl<'a, W> Iterator for WeightedEdges<'a, W> {
+    type Item = (u32, u32, &'a W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (u, v) = self.m_edges.next()?;
+        Some((u, v, &self.m_weights[&(u, v)]))
+    }
+}
+
+/// Heap entry of Dijkstra's algorithm, ordered so the smallest distance is popped first
+struct Candidate<W>(W, u32);
+
+impl<W: PartialOrd> PartialEq for Candidate<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: PartialOrd> Eq for Candidate<W> {}
+
+impl<W: PartialOrd> PartialOrd for Candidate<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: PartialOrd> Ord for Candidate<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .partial_cmp(&self.0)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{RandomSource, SplitMix64};
+    use crate::WeightedBitGraph;
+
+    #[test]
+    fn weights_follow_mutations() {
+        let capacity: usize = 6;
+        let mut c: WeightedBitGraph<u32> = WeightedBitGraph::with_capacity(capacity as u32);
+        assert_eq!(c.add_edge(0, 1, 4), None);
+        assert_eq!(c.add_edge(1, 0, 5), Some(4));
+        c.add_edge(1, 2, 1);
+        c.add_edge(0, 3, 2);
+        c.add_edge(3, 2, 7);
+        c.add_edge(2, 4, 3);
+        assert_eq!(c.size(), 5);
+        assert_eq!(c.weight(0, 1), Some(&5));
+        *c.weight_mut(2, 4).unwrap() += 1;
+        assert_eq!(c.remove_edge(4, 2), Some(4));
+        assert_eq!(c.remove_edge(4, 2), None);
+        assert_eq!(c.degree(4), 0);
+
+        // 3 moves into 0, its edge to 2 is merged with the one of 0 after 1 moved in
+        c.contract_edge(0, 1, |a, b| a + b);
+        assert_eq!(c.weight(0, 2), Some(&1));
+        c.contract_edge(0, 3, |a, b| a + b);
+        assert_eq!(c.weight(0, 2), Some(&8));
+        assert_eq!(c.weight(0, 3), None);
+        let tmp: Vec<(u32, u32, u32)> = c.weighted_edges().map(|(u, v, &w)| (u, v, w)).collect();
+        assert_eq!(tmp, vec![(0, 2, 8)]);
+        c.clear_vertex(0);
+        assert_eq!(c.weighted_edges().count(), 0);
+    }
+
+    #[test]
+    fn dijkstra() {
+        let capacity: usize = 6;
+        let mut c: WeightedBitGraph<f64> = WeightedBitGraph::with_capacity(capacity as u32);
+        for &(u, v, w) in [
+            (0, 1, 7.0),
+            (0, 2, 9.0),
+            (0, 5, 14.0),
+            (1, 2, 10.0),
+            (1, 3, 15.0),
+        ]
+        .iter()
+        .chain([(2, 3, 11.0), (2, 5, 2.0), (3, 4, 6.0), (4, 5, 9.0)].iter())
+        {
+            c.add_edge(u, v, w);
+        }
+        let distances = c.dijkstra(0);
+        assert_eq!(distances[4], Some(20.0));
+        assert_eq!(distances[5], Some(11.0));
+        assert_eq!(
+            c.shortest_path_weighted(0, 4),
+            Some((20.0, vec![0, 2, 5, 4]))
+        );
+        assert_eq!(c.shortest_path_weighted(3, 3), Some((0.0, vec![3])));
+
+        // unit weights give hop distances
+        let mut rng = SplitMix64::new(71);
+        let mut d: WeightedBitGraph<u32> = WeightedBitGraph::with_capacity(40);
+        for _ in 0..60 {
+            let u = rng.below(40) as u32;
+            let v = rng.below(40) as u32;
+            if u != v {
+                d.add_edge(u, v, 1);
+            }
+        }
+        let distances = d.dijkstra(0);
+        for v in 0..40 {
+            let hops = d.shortest_path(0, v).map(|p| p.len() as u32 - 1);
+            assert_eq!(distances[v as usize], hops);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Builds the static library on demand, as documented in `include/hibitgraph.h`, and links a
+//! C program against the header
+#![cfg(feature = "ffi")]
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn c_program() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // a target directory of its own, the one of this test is locked by the running cargo
+    let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    let build = Command::new(env!("CARGO"))
+        .current_dir(root)
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "ffi",
+            "--crate-type",
+            "staticlib",
+        ])
+        .arg("--target-dir")
+        .arg(&target)
+        .args(["--", "--print", "native-static-libs"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&build.stderr);
+    assert!(build.status.success(), "{}", stderr);
+    // the system libraries the standard library needs, printed by rustc
+    let native: Vec<&str> = stderr
+        .lines()
+        .find_map(|line| line.split("native-static-libs:").nth(1))
+        .expect("rustc did not print the native libraries")
+        .split_whitespace()
+        .collect();
+
+    let program = target.join("neighbors");
+    let link = Command::new("cc")
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(root.join("tests/ffi/neighbors.c"))
+        .arg(target.join("debug/libhibitgraph.a"))
+        .args(&native)
+        .arg("-o")
+        .arg(&program)
+        .output()
+        .unwrap();
+    assert!(
+        link.status.success(),
+        "{}",
+        String::from_utf8_lossy(&link.stderr)
+    );
+
+    let run = Command::new(&program).output().unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "1\n130\n199\n");
+}
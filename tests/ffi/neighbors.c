@@ -0,0 +1,47 @@
+/* Walks the neighbors of a vertex through the resumable iterator of include/hibitgraph.h and
+ * prints them, editing the row halfway through. Exits with 1 if a call fails */
+
+#include <stdio.h>
+
+#include "hibitgraph.h"
+
+static int check(bool ok, const char *what) {
+    if (!ok) {
+        fprintf(stderr, "failed: %s\n", what);
+    }
+    return ok ? 0 : 1;
+}
+
+int main(void) {
+    int failed = 0;
+    BitGraph *graph = bitgraph_new(200);
+    failed |= check(graph != NULL, "bitgraph_new");
+    failed |= check(bitgraph_new(UINT32_MAX) == NULL, "bitgraph_new above the limit");
+    failed |= check(bitgraph_add_edge(graph, 0, 1), "add 0 1");
+    failed |= check(bitgraph_add_edge(graph, 0, 70), "add 0 70");
+    failed |= check(bitgraph_add_edge(graph, 0, 130), "add 0 130");
+    failed |= check(!bitgraph_add_edge(graph, 0, 200), "add out of bounds");
+    failed |= check(bitgraph_size(graph) == 3 && bitgraph_degree(graph, 0) == 3, "size");
+    failed |= check(bitgraph_neighbors_iter(graph, 200) == NULL, "iterator out of bounds");
+
+    BitGraphNeighbors *it = bitgraph_neighbors_iter(graph, 0);
+    uint32_t w;
+    int seen = 0;
+    while (bitgraph_neighbors_next(it, &w)) {
+        printf("%u\n", w);
+        /* the iterator resumes from the current row, so edges after w show up */
+        if (seen++ == 0) {
+            failed |= check(bitgraph_remove_edge(graph, 0, 70), "remove 0 70");
+            failed |= check(bitgraph_add_edge(graph, 0, 199), "add 0 199");
+        }
+    }
+    bitgraph_neighbors_free(it);
+
+    BitGraph *copy = bitgraph_clone(graph);
+    failed |= check(bitgraph_contract_edge(copy, 0, 1), "contract 0 1");
+    failed |= check(bitgraph_degree(copy, 0) == 2 && bitgraph_degree(graph, 0) == 3, "clone");
+    bitgraph_free(copy);
+    bitgraph_free(graph);
+    bitgraph_free(NULL);
+    return failed;
+}